@@ -0,0 +1,569 @@
+use clap::builder::PossibleValuesParser;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use colored::Colorize;
+use minisoft_compiler::codegen::generator::CodeGenerator;
+use minisoft_compiler::compiler::{compile_code, execute_code};
+use minisoft_compiler::error_reporter::ErrorReportFormatter;
+use minisoft_compiler::lexer::lexer_core::{TokenWithMetaData, tokenize, tokenize_with_comments};
+use minisoft_compiler::lint::Linter;
+use minisoft_compiler::optimizer::{OptimizationLevel, PassManager};
+use minisoft_compiler::parser::ast::Program;
+use minisoft_compiler::parser::formatter::{FormatOptions, format_program_with};
+use minisoft_compiler::parser::parser_core::parse;
+use minisoft_compiler::repl::{Fragment, FragmentKind, Repl, ReplError};
+use minisoft_compiler::runtime::VirtualMachine;
+use minisoft_compiler::runtime::io::{BufferedInput, InputSource, StdinInput, StdoutSink};
+use minisoft_compiler::semantics::SemanticAnalyzer;
+use minisoft_compiler::{CompilerOptions, SerializableCompilationResult};
+use serde::Serialize;
+use serde_json::json;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::ExitCode;
+
+/// Exit status of a program with compilation or runtime errors, or an unformatted file
+const FAILURE: u8 = 1;
+/// Exit status when the command itself can't run, such as an unreadable file
+const USAGE_ERROR: u8 = 2;
+
+fn cli() -> Command {
+    let file = Arg::new("file")
+        .value_name("FILE")
+        .help("Source file, standard input is read when it is missing or `-`");
+    let opt_level = Arg::new("opt-level")
+        .short('O')
+        .long("opt-level")
+        .value_name("LEVEL")
+        .value_parser(|level: &str| level.parse::<OptimizationLevel>());
+
+    Command::new("minisoftc")
+        .about("Compiles, checks and runs MiniSoft programs")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Print results and diagnostics as JSON"),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .global(true)
+                .value_name("WHEN")
+                .value_parser(["auto", "always", "never"])
+                .default_value("auto")
+                .help("Color diagnostics, `auto` only does when printing to a terminal"),
+        )
+        .subcommand(
+            Command::new("tokens")
+                .about("Print the tokens of a program")
+                .arg(file.clone()),
+        )
+        .subcommand(
+            Command::new("ast")
+                .about("Print the syntax tree of a program")
+                .arg(file.clone()),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("Report errors, warnings and lints without generating code")
+                .arg(file.clone())
+                .arg(
+                    Arg::new("disable-lint")
+                        .long("disable-lint")
+                        .value_name("RULE")
+                        .value_parser(PossibleValuesParser::new(Linter::new().rule_names()))
+                        .action(ArgAction::Append)
+                        .help("Lint rule not to run, may be repeated"),
+                ),
+        )
+        .subcommand(
+            Command::new("quads")
+                .about("Print the quadruples generated for a program")
+                .arg(file.clone())
+                .arg(
+                    opt_level
+                        .clone()
+                        .default_value("O2")
+                        .help("Optimization level, O0 prints the quadruples as generated"),
+                ),
+        )
+        .subcommand(
+            Command::new("run")
+                .about("Compile and execute a program")
+                .arg(file.clone())
+                .arg(
+                    opt_level
+                        .default_value("O0")
+                        .help("Optimization level of the executed quadruples"),
+                )
+                .arg(
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .value_name("VALUE")
+                        .action(ArgAction::Append)
+                        .help(
+                            "Value for the next `input` statement, may be repeated. Without any, \
+                             lines of standard input are used when the program comes from a file",
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("fmt")
+                .about("Print a program in canonical layout")
+                .arg(file)
+                .arg(
+                    Arg::new("check")
+                        .long("check")
+                        .action(ArgAction::SetTrue)
+                        .help("Only report whether the program is already formatted"),
+                )
+                .arg(
+                    Arg::new("one-declaration-per-line")
+                        .long("one-declaration-per-line")
+                        .action(ArgAction::SetTrue)
+                        .help("Give every declared name a `let` of its own"),
+                ),
+        )
+        .subcommand(
+            Command::new("repl").about(
+                "Read declarations and statements one at a time, showing what each compiles to",
+            ),
+        )
+}
+
+fn main() -> ExitCode {
+    // Stop quietly when whatever reads the output goes away, as in `minisoftc quads f.ms | head`
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+    let matches = cli().get_matches();
+    match matches.get_one::<String>("color").map(String::as_str) {
+        Some("always") => colored::control::set_override(true),
+        Some("never") => colored::control::set_override(false),
+        _ if !io::stdout().is_terminal() => colored::control::set_override(false),
+        _ => {}
+    }
+
+    let json = matches.get_flag("json");
+    let Some((command, arguments)) = matches.subcommand() else {
+        return ExitCode::from(USAGE_ERROR);
+    };
+    if command == "repl" {
+        repl();
+        return ExitCode::SUCCESS;
+    }
+    let source = match read_source(arguments) {
+        Ok(source) => source,
+        Err(message) => {
+            eprintln!("{}: {}", "error".red().bold(), message);
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+
+    let succeeded = match (command, json) {
+        ("tokens", false) => print_tokens(&source),
+        ("ast", false) => print_ast(&source),
+        ("check", false) => check(&source, &disabled_lints(arguments)),
+        ("quads", false) => print_quadruples(&source, optimization_level(arguments)),
+        ("run", false) => run(
+            &source,
+            input_source(arguments).as_mut(),
+            optimization_level(arguments),
+        ),
+        ("fmt", false) => format(
+            &source,
+            &format_options(arguments),
+            arguments.get_flag("check"),
+        ),
+        ("run", true) => run_json(
+            &source,
+            input_source(arguments).as_mut(),
+            optimization_level(arguments),
+        ),
+        ("fmt", true) => format_json(&source, &format_options(arguments)),
+        (command, true) => compile_json(&source, command, arguments),
+        _ => unreachable!("clap only accepts the declared subcommands"),
+    };
+    match succeeded {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(FAILURE),
+        Err(message) => {
+            eprintln!("{}: {}", "error".red().bold(), message);
+            ExitCode::from(USAGE_ERROR)
+        }
+    }
+}
+
+fn from_stdin(arguments: &ArgMatches) -> bool {
+    arguments
+        .get_one::<String>("file")
+        .is_none_or(|path| path == "-")
+}
+
+fn read_source(arguments: &ArgMatches) -> Result<String, String> {
+    if from_stdin(arguments) {
+        let mut source = String::new();
+        io::stdin()
+            .read_to_string(&mut source)
+            .map_err(|error| format!("cannot read standard input: {}", error))?;
+        return Ok(source);
+    }
+    let path = arguments.get_one::<String>("file").unwrap();
+    fs::read_to_string(path).map_err(|error| format!("cannot read '{}': {}", path, error))
+}
+
+fn disabled_lints(arguments: &ArgMatches) -> Vec<String> {
+    arguments
+        .get_many::<String>("disable-lint")
+        .map(|rules| rules.cloned().collect())
+        .unwrap_or_default()
+}
+
+fn optimization_level(arguments: &ArgMatches) -> OptimizationLevel {
+    arguments
+        .get_one::<OptimizationLevel>("opt-level")
+        .copied()
+        .unwrap_or_default()
+}
+
+fn format_options(arguments: &ArgMatches) -> FormatOptions {
+    FormatOptions {
+        one_declaration_per_line: arguments.get_flag("one-declaration-per-line"),
+    }
+}
+
+/// Values given with `--input`, otherwise lines of standard input read as the program asks
+/// for them. A program read from standard input has already used it up.
+fn input_source(arguments: &ArgMatches) -> Box<dyn InputSource> {
+    match arguments.get_many::<String>("input") {
+        Some(values) => Box::new(BufferedInput::new(values.cloned())),
+        None => Box::new(StdinInput),
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|error| error.to_string())?;
+    println!("{}", text);
+    Ok(())
+}
+
+/// Tokens of the program, or `None` once its lexical errors are printed
+fn lex(source: &str) -> Option<Vec<TokenWithMetaData>> {
+    let (tokens, errors) = tokenize(source);
+    if errors.is_empty() {
+        return Some(tokens);
+    }
+    ErrorReportFormatter::print_errors(&errors, Some(source));
+    None
+}
+
+/// Syntax tree of the program, or `None` once its lexical or syntax errors are printed
+fn parse_program(source: &str) -> Option<Program> {
+    let tokens = lex(source)?;
+    match parse(tokens, source) {
+        Ok(program) => Some(program),
+        Err(error) => {
+            ErrorReportFormatter::print_errors(&[error], Some(source));
+            None
+        }
+    }
+}
+
+/// Program and its analysis, or `None` once its errors are printed. Warnings are printed either way.
+fn analyze(source: &str) -> Option<(Program, SemanticAnalyzer)> {
+    let program = parse_program(source)?;
+    let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+    analyzer.analyze(&program);
+    if !analyzer.get_warnings().is_empty() {
+        ErrorReportFormatter::print_errors(analyzer.get_warnings(), Some(source));
+    }
+    if !analyzer.get_errors().is_empty() {
+        ErrorReportFormatter::print_errors(analyzer.get_errors(), Some(source));
+        return None;
+    }
+    Some((program, analyzer))
+}
+
+fn print_tokens(source: &str) -> Result<bool, String> {
+    let Some(tokens) = lex(source) else {
+        return Ok(false);
+    };
+    for token in tokens {
+        let kind = format!("{:?}", token.kind);
+        let kind = kind.split('(').next().unwrap_or_default();
+        println!(
+            "{:>4}:{:<4} {:<16} {}",
+            token.line, token.column, kind, token.value
+        );
+    }
+    Ok(true)
+}
+
+fn print_ast(source: &str) -> Result<bool, String> {
+    let Some(program) = parse_program(source) else {
+        return Ok(false);
+    };
+    program.pretty_print();
+    Ok(true)
+}
+
+fn check(source: &str, disabled_lints: &[String]) -> Result<bool, String> {
+    let Some((program, analyzer)) = analyze(source) else {
+        return Ok(false);
+    };
+    let lints =
+        Linter::without_rules(disabled_lints).run(&program, analyzer.get_symbol_table(), source);
+    if !lints.is_empty() {
+        ErrorReportFormatter::print_errors(&lints, Some(source));
+    }
+    println!(
+        "{} no errors, {} warning(s), {} lint(s)",
+        "Checked:".green().bold(),
+        analyzer.get_warnings().len(),
+        lints.len()
+    );
+    Ok(true)
+}
+
+fn print_quadruples(source: &str, level: OptimizationLevel) -> Result<bool, String> {
+    let Some((program, analyzer)) = analyze(source) else {
+        return Ok(false);
+    };
+    let mut quadruples = CodeGenerator::new()
+        .generate_code(&program)
+        .ok_or_else(|| "Code generation failed".to_string())?;
+    PassManager::for_level(level, analyzer.get_symbol_table()).run(&mut quadruples);
+    for (index, quad) in quadruples.quadruples.iter().enumerate() {
+        println!("{:>4}  {}", index, quad);
+    }
+    Ok(true)
+}
+
+/// Runs the program, printing its output as it goes so that a prompt shows before its input
+fn run(
+    source: &str,
+    input: &mut dyn InputSource,
+    level: OptimizationLevel,
+) -> Result<bool, String> {
+    let Some((program, analyzer)) = analyze(source) else {
+        return Ok(false);
+    };
+    let mut quadruples = CodeGenerator::new()
+        .generate_code(&program)
+        .ok_or_else(|| "Code generation failed".to_string())?;
+    PassManager::for_level(level, analyzer.get_symbol_table()).run(&mut quadruples);
+    let mut vm = VirtualMachine::new(&quadruples).with_source(&source.to_string());
+    vm.load_symbols(analyzer.get_symbol_table());
+    match vm.run(input, &mut StdoutSink) {
+        Err(error) => {
+            ErrorReportFormatter::print_errors(&[error], Some(source));
+            Ok(false)
+        }
+        Ok(()) => Ok(true),
+    }
+}
+
+fn format(source: &str, options: &FormatOptions, check_only: bool) -> Result<bool, String> {
+    let Some(program) = parse_program(source) else {
+        return Ok(false);
+    };
+    let (tokens, _) = tokenize_with_comments(source);
+    let formatted = format_program_with(&program, &tokens, options);
+    if !check_only {
+        print!("{}", formatted);
+        return Ok(true);
+    }
+    if formatted == source {
+        return Ok(true);
+    }
+    println!(
+        "{} the program is not formatted",
+        "Unformatted:".yellow().bold()
+    );
+    Ok(false)
+}
+
+/// Prints the parts of the compilation result a command is about, errors included
+fn compile_json(source: &str, command: &str, arguments: &ArgMatches) -> Result<bool, String> {
+    let options = CompilerOptions {
+        optimization_level: match command {
+            "quads" => optimization_level(arguments),
+            _ => OptimizationLevel::default(),
+        },
+        disabled_lints: match command {
+            "check" => disabled_lints(arguments),
+            _ => vec![],
+        },
+        pass_diffs: false,
+    };
+    let result: SerializableCompilationResult = compile_code(source.to_string(), false, &options)?;
+    let succeeded = result.errors.is_none();
+    match command {
+        "tokens" => print_json(&json!({ "tokens": result.tokens, "errors": result.errors }))?,
+        "ast" => print_json(&json!({ "ast": result.ast, "errors": result.errors }))?,
+        "quads" => print_json(&json!({
+            "optimization_level": result.optimization_level,
+            "quadruples": result.quadruples,
+            "optimized_quadruples": result.optimized_quadruples,
+            "errors": result.errors,
+            "warnings": result.warnings,
+        }))?,
+        _ => print_json(&result)?,
+    }
+    Ok(succeeded)
+}
+
+fn run_json(
+    source: &str,
+    input: &mut dyn InputSource,
+    level: OptimizationLevel,
+) -> Result<bool, String> {
+    let result = execute_code(source.to_string(), input, level)?;
+    let succeeded = result.compilation_errors.is_none() && result.runtime_error.is_none();
+    print_json(&result)?;
+    Ok(succeeded)
+}
+
+fn format_json(source: &str, options: &FormatOptions) -> Result<bool, String> {
+    let (tokens, lexical_errors) = tokenize(source);
+    let parsed = parse(tokens, source)
+        .ok()
+        .filter(|_| lexical_errors.is_empty());
+    let Some(program) = parsed else {
+        // Only lexical and syntax errors stop the formatter, the compiler reports them
+        let result = compile_code(source.to_string(), false, &CompilerOptions::default())?;
+        print_json(&json!({ "formatted": null, "changed": false, "errors": result.errors }))?;
+        return Ok(false);
+    };
+    let (tokens, _) = tokenize_with_comments(source);
+    let formatted = format_program_with(&program, &tokens, options);
+    print_json(&json!({
+        "changed": formatted != source,
+        "formatted": formatted,
+        "errors": null,
+    }))?;
+    Ok(true)
+}
+
+const REPL_HELP: &str = "Enter a `let` or `@define` declaration, or a statement, ending with `;`.
+Blocks continue over several lines until their braces are closed.
+  :symbols  list the declared names and their current values
+  :quads    list every quadruple generated so far
+  :help     show this message
+  :quit     leave, as does end of input";
+
+fn repl() {
+    let mut repl = Repl::new();
+    println!("MiniSoft REPL, :help lists the commands");
+    while let Some(source) = read_fragment() {
+        match source.trim() {
+            "" => {}
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", REPL_HELP),
+            ":symbols" => print_symbols(&repl),
+            ":quads" => {
+                for (index, quad) in repl.quadruples().quadruples.iter().enumerate() {
+                    println!("{:>4}  {}", index, quad);
+                }
+            }
+            command if command.starts_with(':') => {
+                println!("{} unknown command '{}'", "error:".red().bold(), command)
+            }
+            _ => match repl.compile(&source) {
+                Ok(fragment) => {
+                    show_fragment(&fragment);
+                    if let Err(error) = repl.execute(&fragment, &mut StdinInput, &mut StdoutSink) {
+                        ErrorReportFormatter::print_errors(&[error], Some(&fragment.source));
+                    }
+                }
+                Err(ReplError::Lexical(errors)) => {
+                    ErrorReportFormatter::print_errors(&errors, Some(&source))
+                }
+                Err(ReplError::Syntax(error)) => {
+                    ErrorReportFormatter::print_errors(&[error], Some(&source))
+                }
+                Err(ReplError::Semantic(errors)) => {
+                    ErrorReportFormatter::print_errors(&errors, Some(&source))
+                }
+            },
+        }
+    }
+}
+
+/// Lines up to the end of a fragment, as long as its braces are left open.
+/// `None` at the end of input.
+fn read_fragment() -> Option<String> {
+    let mut source = String::new();
+    let mut prompt = "minisoft> ";
+    loop {
+        print!("{}", prompt.green().bold());
+        io::stdout().flush().ok()?;
+        match io::stdin().read_line(&mut source) {
+            Ok(0) | Err(_) if source.trim().is_empty() => return None,
+            Ok(0) | Err(_) => return Some(source),
+            Ok(_) => {}
+        }
+        if source.matches('{').count() <= source.matches('}').count() {
+            return Some(source);
+        }
+        prompt = "      ... ";
+    }
+}
+
+fn show_fragment(fragment: &Fragment) {
+    let kinds: Vec<String> = fragment
+        .tokens
+        .iter()
+        .map(|token| token.kind.to_string())
+        .collect();
+    println!("{} {}", "tokens:".cyan().bold(), kinds.join(" "));
+
+    println!("{}", "ast:".cyan().bold());
+    match &fragment.kind {
+        FragmentKind::Declaration(declaration) => declaration.to_tree().pretty_print(),
+        FragmentKind::Statement(statement) => statement.to_tree().pretty_print(),
+    }
+
+    println!("{}", "quadruples:".cyan().bold());
+    for (offset, quad) in fragment.quadruples.quadruples.iter().enumerate() {
+        println!("{:>4}  {}", fragment.first_index + offset, quad);
+    }
+
+    if !fragment.warnings.is_empty() {
+        ErrorReportFormatter::print_errors(&fragment.warnings, Some(&fragment.source));
+    }
+    println!("{}", "output:".cyan().bold());
+}
+
+fn print_symbols(repl: &Repl) {
+    let mut symbols = repl.symbol_table().get_all();
+    symbols.sort_by_key(|symbol| symbol.qualified_name());
+    for symbol in symbols {
+        let memory = repl.memory();
+        let name = symbol.qualified_name();
+        let value = match memory.arrays.get(&name) {
+            Some(values) => {
+                let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+                format!("{{{}}}", values.join(", "))
+            }
+            None => memory
+                .variables
+                .get(&name)
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        };
+        println!(
+            "{:<14} {:<10} {:<6} {}",
+            name,
+            format!("{:?}", symbol.kind),
+            symbol.symbol_type,
+            value
+        );
+    }
+}
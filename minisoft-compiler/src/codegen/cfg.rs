@@ -0,0 +1,294 @@
+use crate::codegen::quadruple::{Operation, Quadruple, QuadrupleProgram};
+use std::collections::{BTreeSet, HashMap};
+
+/// A maximal run of quadruples entered only at the top and left only at the bottom
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicBlock {
+    pub id: usize,
+    /// Index of the first quadruple of the block in the original program
+    pub start: usize,
+    pub quadruples: Vec<Quadruple>,
+    pub successors: Vec<usize>,
+    pub predecessors: Vec<usize>,
+}
+
+impl BasicBlock {
+    /// Label placed at the top of the block, if any
+    pub fn label(&self) -> Option<usize> {
+        match self.quadruples.first().map(|quad| &quad.operation) {
+            Some(Operation::Label(label)) => Some(*label),
+            _ => None,
+        }
+    }
+
+    /// Subprogram whose code starts with the block, if any
+    pub fn subprogram(&self) -> Option<&str> {
+        match self.quadruples.first().map(|quad| &quad.operation) {
+            Some(Operation::Entry(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The quadruple that decides where control goes after the block
+    pub fn terminator(&self) -> Option<&Quadruple> {
+        self.quadruples
+            .last()
+            .filter(|quad| is_terminator(&quad.operation))
+    }
+}
+
+/// A loop found from a back edge: the header dominates every block of the body
+#[derive(Debug, Clone, PartialEq)]
+pub struct NaturalLoop {
+    pub header: usize,
+    /// Blocks of the loop, header included, in program order
+    pub blocks: Vec<usize>,
+    /// Blocks with an edge back to the header
+    pub latches: Vec<usize>,
+}
+
+impl NaturalLoop {
+    pub fn contains(&self, block: usize) -> bool {
+        self.blocks.binary_search(&block).is_ok()
+    }
+}
+
+/// Control-flow graph of a quadruple program, block 0 is the entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlFlowGraph {
+    pub blocks: Vec<BasicBlock>,
+    pub next_temp: usize,
+    pub next_label: usize,
+}
+
+impl ControlFlowGraph {
+    /// Splits the program into basic blocks and links them
+    pub fn build(program: &QuadrupleProgram) -> Self {
+        let quadruples = &program.quadruples;
+
+        // A block starts at the first quadruple, at every label or subprogram entry and
+        // after every jump
+        let mut leaders = vec![false; quadruples.len()];
+        for (index, quad) in quadruples.iter().enumerate() {
+            if index == 0 || matches!(quad.operation, Operation::Label(_) | Operation::Entry(_)) {
+                leaders[index] = true;
+            }
+            if is_terminator(&quad.operation) && index + 1 < quadruples.len() {
+                leaders[index + 1] = true;
+            }
+        }
+
+        let mut blocks: Vec<BasicBlock> = Vec::new();
+        for (index, quad) in quadruples.iter().enumerate() {
+            if leaders[index] {
+                blocks.push(BasicBlock {
+                    id: blocks.len(),
+                    start: index,
+                    quadruples: Vec::new(),
+                    successors: Vec::new(),
+                    predecessors: Vec::new(),
+                });
+            }
+            if let Some(block) = blocks.last_mut() {
+                block.quadruples.push(quad.clone());
+            }
+        }
+
+        let mut cfg = ControlFlowGraph {
+            blocks,
+            next_temp: program.next_temp,
+            next_label: program.next_label,
+        };
+        cfg.link();
+        cfg
+    }
+
+    /// Recomputes successor and predecessor edges from the block contents
+    pub fn link(&mut self) {
+        let label_blocks: HashMap<usize, usize> = self
+            .blocks
+            .iter()
+            .filter_map(|block| block.label().map(|label| (label, block.id)))
+            .collect();
+
+        let count = self.blocks.len();
+        for block in &mut self.blocks {
+            let next = (block.id + 1 < count).then_some(block.id + 1);
+            let target = |label: &usize| label_blocks.get(label).copied();
+
+            let mut successors: Vec<usize> = match block.quadruples.last().map(|q| &q.operation) {
+                Some(Operation::Jump(label)) => target(label).into_iter().collect(),
+                Some(Operation::JumpIfTrue(label)) | Some(Operation::JumpIfFalse(label)) => {
+                    target(label).into_iter().chain(next).collect()
+                }
+                Some(Operation::Return) => Vec::new(),
+                _ => next.into_iter().collect(),
+            };
+            successors.dedup();
+            block.successors = successors;
+            block.predecessors.clear();
+        }
+
+        for id in 0..count {
+            for successor in self.blocks[id].successors.clone() {
+                self.blocks[successor].predecessors.push(id);
+            }
+        }
+    }
+
+    pub fn entry(&self) -> Option<&BasicBlock> {
+        self.blocks.first()
+    }
+
+    /// Blocks control can start from: the entry and the first block of every subprogram,
+    /// which calls reach without an edge of the graph
+    pub fn roots(&self) -> Vec<usize> {
+        self.blocks
+            .iter()
+            .filter(|block| block.id == 0 || block.subprogram().is_some())
+            .map(|block| block.id)
+            .collect()
+    }
+
+    /// Block that starts with the given label
+    pub fn block_of_label(&self, label: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .find(|block| block.label() == Some(label))
+            .map(|block| block.id)
+    }
+
+    /// Blocks reachable from the roots, in reverse post-order. The entry comes first,
+    /// followed by what it reaches, then each subprogram in program order.
+    pub fn reverse_post_order(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.blocks.len());
+        let mut visited = vec![false; self.blocks.len()];
+
+        // Iterative depth-first search, each frame remembers the next successor to visit.
+        // Roots are searched last to first so that reversing puts the entry first.
+        for root in self.roots().into_iter().rev() {
+            if visited[root] {
+                continue;
+            }
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            visited[root] = true;
+            while let Some((block, next)) = stack.pop() {
+                if let Some(&successor) = self.blocks[block].successors.get(next) {
+                    stack.push((block, next + 1));
+                    if !visited[successor] {
+                        visited[successor] = true;
+                        stack.push((successor, 0));
+                    }
+                } else {
+                    order.push(block);
+                }
+            }
+        }
+
+        order.reverse();
+        order
+    }
+
+    /// Dominator set of every block, empty for blocks not reachable from a root
+    pub fn dominators(&self) -> Vec<BTreeSet<usize>> {
+        let order = self.reverse_post_order();
+        let roots = self.roots();
+        let all: BTreeSet<usize> = order.iter().copied().collect();
+        let mut dominators = vec![BTreeSet::new(); self.blocks.len()];
+        for &block in &order {
+            dominators[block] = if roots.contains(&block) {
+                BTreeSet::from([block])
+            } else {
+                all.clone()
+            };
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in order.iter().filter(|block| !roots.contains(block)) {
+                let mut set = self.blocks[block]
+                    .predecessors
+                    .iter()
+                    .filter(|&&predecessor| !dominators[predecessor].is_empty())
+                    .map(|&predecessor| dominators[predecessor].clone())
+                    .reduce(|set, other| set.intersection(&other).copied().collect())
+                    .unwrap_or_default();
+                set.insert(block);
+                if set != dominators[block] {
+                    dominators[block] = set;
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// Loops formed by back edges, one per header, innermost first
+    pub fn natural_loops(&self) -> Vec<NaturalLoop> {
+        let dominators = self.dominators();
+        let mut loops: Vec<NaturalLoop> = Vec::new();
+
+        for block in &self.blocks {
+            for &header in &block.successors {
+                if !dominators[block.id].contains(&header) {
+                    continue;
+                }
+
+                // The body is everything reaching the latch without passing through the header
+                let mut body = BTreeSet::from([header, block.id]);
+                let mut stack = vec![block.id];
+                while let Some(current) = stack.pop() {
+                    if current == header {
+                        continue;
+                    }
+                    for &predecessor in &self.blocks[current].predecessors {
+                        let reachable = !dominators[predecessor].is_empty();
+                        if reachable && body.insert(predecessor) {
+                            stack.push(predecessor);
+                        }
+                    }
+                }
+
+                match loops.iter_mut().find(|natural| natural.header == header) {
+                    Some(natural) => {
+                        body.extend(natural.blocks.iter().copied());
+                        natural.blocks = body.into_iter().collect();
+                        natural.latches.push(block.id);
+                    }
+                    None => loops.push(NaturalLoop {
+                        header,
+                        blocks: body.into_iter().collect(),
+                        latches: vec![block.id],
+                    }),
+                }
+            }
+        }
+
+        loops.sort_by_key(|natural| (natural.blocks.len(), natural.header));
+        loops
+    }
+
+    /// Flattens the blocks back into a quadruple program, in block order
+    pub fn to_program(&self) -> QuadrupleProgram {
+        QuadrupleProgram {
+            quadruples: self
+                .blocks
+                .iter()
+                .flat_map(|block| block.quadruples.iter().cloned())
+                .collect(),
+            next_temp: self.next_temp,
+            next_label: self.next_label,
+        }
+    }
+}
+
+fn is_terminator(operation: &Operation) -> bool {
+    matches!(
+        operation,
+        Operation::Jump(_)
+            | Operation::JumpIfTrue(_)
+            | Operation::JumpIfFalse(_)
+            | Operation::Return
+    )
+}
@@ -1,29 +1,267 @@
+use std::collections::HashMap;
+
 use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::runtime::memory::{INT_MAX, INT_MIN};
+use crate::semantics::symbol_table::ScopeNames;
 use crate::parser::ast::{
-    Expression, ExpressionKind, LiteralKind, Operator, Program, Statement, StatementKind,
-    UnaryOperator,
+    Declaration, DeclarationKind, Expression, ExpressionKind, Located, LiteralKind, Operator,
+    Program, Statement, StatementKind, Subprogram, Type, UnaryOperator,
 };
 
 pub struct CodeGenerator {
     pub program: QuadrupleProgram,
+    /// Qualified storage name of every parameter, by subprogram
+    parameters: HashMap<String, Vec<String>>,
+    /// Storage of the names in scope at the statement being generated
+    names: ScopeNames,
+    /// Declared type of every variable, array and parameter, by storage name
+    types: HashMap<String, Type>,
+    /// Type of the value of every function
+    return_types: HashMap<String, Type>,
 }
 
 impl CodeGenerator {
     pub fn new() -> Self {
         CodeGenerator {
             program: QuadrupleProgram::new(),
+            parameters: HashMap::new(),
+            names: ScopeNames::new(),
+            types: HashMap::new(),
+            return_types: HashMap::new(),
         }
     }
 
     pub fn generate_code(&mut self, ast: &Program) -> Option<QuadrupleProgram> {
+        for subprogram in &ast.subprograms {
+            let subprogram = &subprogram.node;
+            let parameters = subprogram
+                .parameters
+                .iter()
+                .map(|parameter| subprogram.qualified_name(&parameter.node.name))
+                .collect();
+            self.parameters.insert(subprogram.name.clone(), parameters);
+            for parameter in &subprogram.parameters {
+                self.types.insert(
+                    subprogram.qualified_name(&parameter.node.name),
+                    parameter.node.typ.clone(),
+                );
+            }
+            if let Some(return_type) = &subprogram.return_type {
+                self.return_types
+                    .insert(subprogram.name.clone(), return_type.clone());
+            }
+        }
+
+        // Initialize constants and declared values before the program body runs
+        for declaration in &ast.declarations {
+            self.generate_declaration(declaration);
+        }
+
         // Process each statement in the program
-        for statement in &ast.statements {
-            self.generate_statement(statement);
+        self.generate_block(&ast.statements);
+
+        // Subprograms are placed after the program body, which must not fall into them
+        if !ast.subprograms.is_empty() {
+            self.add_return(Operand::Empty);
+            for subprogram in &ast.subprograms {
+                self.generate_subprogram(subprogram);
+            }
         }
         Some(self.program.clone())
     }
 
-    fn generate_statement(&mut self, statement: &Statement) {
+    /// Generates the entry of a subprogram followed by its local declarations and body.
+    /// Parameters and locals are stored under their qualified name.
+    fn generate_subprogram(&mut self, subprogram: &Located<Subprogram>) {
+        let first = self.program.quadruples.len();
+        let node = &subprogram.node;
+        self.names.enter_subprogram(node);
+
+        self.program.add(Quadruple {
+            operation: Operation::Entry(node.name.clone()),
+            operand1: Operand::Empty,
+            operand2: Operand::Empty,
+            result: Operand::Empty,
+            span: None,
+            statement_span: None,
+        });
+        self.program.set_missing_statement_spans(first, &subprogram.span);
+
+        for declaration in &node.declarations {
+            self.generate_declaration(declaration);
+        }
+        self.generate_block(&node.statements);
+
+        // A body that runs to its end returns to the caller
+        if !self.returned() {
+            let first = self.program.quadruples.len();
+            self.add_return(Operand::Empty);
+            self.program.set_missing_statement_spans(first, &subprogram.span);
+        }
+        self.names.exit();
+    }
+
+    /// Generates statements up to the first one that returns, the rest can never run
+    fn generate_block(&mut self, statements: &[Statement]) {
+        for (index, statement) in statements.iter().enumerate() {
+            if self.returned() {
+                self.names.skip(&statements[index..]);
+                break;
+            }
+            self.generate_statement(statement);
+        }
+    }
+
+    /// Whether the last quadruple generated leaves the current subprogram
+    fn returned(&self) -> bool {
+        matches!(
+            self.program.quadruples.last().map(|quad| &quad.operation),
+            Some(Operation::Return)
+        )
+    }
+
+    fn add_return(&mut self, value: Operand) {
+        self.program.add(Quadruple {
+            operation: Operation::Return,
+            operand1: value,
+            operand2: Operand::Empty,
+            result: Operand::Empty,
+            span: None,
+            statement_span: None,
+        });
+    }
+
+    /// Storage of a name, qualified when it's declared in a subprogram or a block
+    fn variable(&self, name: &str) -> Operand {
+        Operand::Variable(self.names.resolve(name))
+    }
+
+    /// Passes the arguments to the qualified parameters of the subprogram, then calls it.
+    /// Every argument is evaluated before the first one is passed. Gives the temporary
+    /// holding the value of a function, `Empty` when the value isn't used.
+    fn generate_call(&mut self, name: &str, arguments: &[Expression], value: bool) -> Operand {
+        let values: Vec<Operand> = arguments
+            .iter()
+            .map(|argument| self.generate_expression(argument))
+            .collect();
+        let parameters = self.parameters.get(name).cloned().unwrap_or_default();
+        for (value, parameter) in values.into_iter().zip(parameters) {
+            self.program.add(Quadruple {
+                operation: Operation::Param,
+                operand1: value,
+                operand2: Operand::Empty,
+                result: Operand::Variable(parameter),
+                span: None,
+                statement_span: None,
+            });
+        }
+        let result = if value {
+            self.program.new_temp()
+        } else {
+            Operand::Empty
+        };
+        self.program.add(Quadruple {
+            operation: Operation::Call(name.to_string()),
+            operand1: Operand::IntLiteral(arguments.len() as i32),
+            operand2: Operand::Empty,
+            result: result.clone(),
+            span: None,
+            statement_span: None,
+        });
+        result
+    }
+
+    /// Makes the names of a declaration visible and records their type
+    fn declare(&mut self, declaration: &Declaration) {
+        self.names.declare(declaration);
+        let typ = match &declaration.node {
+            DeclarationKind::Variable(_, typ)
+            | DeclarationKind::Array(_, typ, _)
+            | DeclarationKind::VariableWithInit(_, typ, _)
+            | DeclarationKind::ArrayWithInit(_, typ, _, _)
+            | DeclarationKind::Constant(_, typ, _) => typ,
+        };
+        for name in declaration.node.names() {
+            self.types.insert(self.names.resolve(name), typ.clone());
+        }
+    }
+
+    /// Whether an expression gives a string, which `+` joins instead of adding
+    fn is_string(&self, expression: &Expression) -> bool {
+        match &expression.node {
+            ExpressionKind::Literal(literal) => matches!(literal.node, LiteralKind::String(_)),
+            ExpressionKind::Identifier(name) | ExpressionKind::ArrayAccess(name, _) => {
+                self.types.get(&self.names.resolve(name)) == Some(&Type::String)
+            }
+            ExpressionKind::Call(name, _) => self.return_types.get(name) == Some(&Type::String),
+            ExpressionKind::BinaryOp(left, Operator::Add, _) => self.is_string(left),
+            ExpressionKind::BinaryOp(..) | ExpressionKind::UnaryOp(..) => false,
+        }
+    }
+
+    pub fn generate_declaration(&mut self, declaration: &Declaration) {
+        let first = self.program.quadruples.len();
+        match &declaration.node {
+            DeclarationKind::Variable(_, _) | DeclarationKind::Array(_, _, _) => {
+                // Nothing to emit, storage is reserved from the symbol table
+                self.declare(declaration);
+            }
+            DeclarationKind::VariableWithInit(names, _, init) => {
+                // Evaluate the initializer once and copy it into every declared name
+                let value = self.generate_expression(init);
+                self.declare(declaration);
+                for name in names {
+                    self.program.add(Quadruple {
+                        operation: Operation::Assign,
+                        operand1: value.clone(),
+                        operand2: Operand::Empty,
+                        result: self.variable(name),
+                        span: None,
+                        statement_span: None,
+                    });
+                }
+            }
+            DeclarationKind::ArrayWithInit(names, _, _, values) => {
+                let values: Vec<Operand> = values
+                    .iter()
+                    .map(|value| self.generate_expression(value))
+                    .collect();
+                self.declare(declaration);
+                for name in names {
+                    for (index, value) in values.iter().enumerate() {
+                        self.program.add(Quadruple {
+                            operation: Operation::ArrayStore,
+                            operand1: value.clone(),
+                            operand2: Operand::IntLiteral(index as i32),
+                            result: self.variable(name),
+                            span: None,
+                            statement_span: None,
+                        });
+                    }
+                }
+            }
+            DeclarationKind::Constant(name, _, literal) => {
+                let value = match &literal.node {
+                    LiteralKind::Int(value) => Operand::IntLiteral(*value),
+                    LiteralKind::Float(value) => Operand::FloatLiteral(*value),
+                    LiteralKind::String(value) => Operand::StringLiteral(value.clone()),
+                };
+                self.declare(declaration);
+                self.program.add(Quadruple {
+                    operation: Operation::Assign,
+                    operand1: value,
+                    operand2: Operand::Empty,
+                    result: self.variable(name),
+                    span: None,
+                    statement_span: None,
+                });
+            }
+        }
+        self.program.set_missing_statement_spans(first, &declaration.span);
+    }
+
+    pub fn generate_statement(&mut self, statement: &Statement) {
+        let first = self.program.quadruples.len();
         match &statement.node {
             StatementKind::Assignment(lhs, rhs) => {
                 // Generate RHS expression first
@@ -37,7 +275,9 @@ impl CodeGenerator {
                             operation: Operation::Assign,
                             operand1: rhs_result,
                             operand2: Operand::Empty,
-                            result: Operand::Variable(name.clone()),
+                            result: self.variable(name),
+                            span: None,
+                            statement_span: None,
                         });
                     }
                     ExpressionKind::ArrayAccess(name, index_expr) => {
@@ -47,7 +287,9 @@ impl CodeGenerator {
                             operation: Operation::ArrayStore,
                             operand1: rhs_result,
                             operand2: index,
-                            result: Operand::Variable(name.clone()),
+                            result: self.variable(name),
+                            span: None,
+                            statement_span: None,
                         });
                     }
                     _ => {
@@ -66,12 +308,12 @@ impl CodeGenerator {
                     operand1: cond_result,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
 
                 // Generate code for then block
-                for stmt in then_block {
-                    self.generate_statement(stmt);
-                }
+                self.generate_block(then_block);
 
                 // Add else label
                 self.program.add(Quadruple {
@@ -79,10 +321,13 @@ impl CodeGenerator {
                     operand1: Operand::Empty,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
             }
             StatementKind::IfThenElse(condition, then_block, else_block) => {
                 let else_label = self.program.new_label();
+                let end_label = self.program.new_label();
                 let cond_result = self.generate_expression(condition);
 
                 // Jump to else label if condition is false
@@ -91,11 +336,23 @@ impl CodeGenerator {
                     operand1: cond_result,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
 
                 // Generate code for then block
-                for stmt in then_block {
-                    self.generate_statement(stmt);
+                self.generate_block(then_block);
+
+                // Skip over the else block once the then block is done
+                if !self.returned() {
+                    self.program.add(Quadruple {
+                        operation: Operation::Jump(end_label),
+                        operand1: Operand::Empty,
+                        operand2: Operand::Empty,
+                        result: Operand::Empty,
+                        span: None,
+                        statement_span: None,
+                    });
                 }
 
                 // Add else label
@@ -104,12 +361,22 @@ impl CodeGenerator {
                     operand1: Operand::Empty,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
 
                 // Generate code for else block
-                for stmt in else_block {
-                    self.generate_statement(stmt);
-                }
+                self.generate_block(else_block);
+
+                // Both branches join at the end label
+                self.program.add(Quadruple {
+                    operation: Operation::Label(end_label),
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
             }
             StatementKind::DoWhile(body, condition) => {
                 let start_label = self.program.new_label();
@@ -120,22 +387,72 @@ impl CodeGenerator {
                     operand1: Operand::Empty,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
 
                 // Generate code for body
-                for stmt in body {
-                    self.generate_statement(stmt);
+                self.generate_block(body);
+
+                // A body that always returns never tests the condition
+                if !self.returned() {
+                    // Generate condition
+                    let cond_result = self.generate_expression(condition);
+
+                    // Jump to start if condition is true
+                    self.program.add(Quadruple {
+                        operation: Operation::JumpIfTrue(start_label),
+                        operand1: cond_result,
+                        operand2: Operand::Empty,
+                        result: Operand::Empty,
+                        span: None,
+                        statement_span: None,
+                    });
                 }
+            }
+            StatementKind::While(condition, body) => {
+                let start_label = self.program.new_label();
+                let end_label = self.program.new_label();
 
-                // Generate condition
+                // The condition is tested at the top, before every iteration
+                self.program.add(Quadruple {
+                    operation: Operation::Label(start_label),
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
                 let cond_result = self.generate_expression(condition);
-
-                // Jump to start if condition is true
                 self.program.add(Quadruple {
-                    operation: Operation::JumpIfTrue(start_label),
+                    operation: Operation::JumpIfFalse(end_label),
                     operand1: cond_result,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
+
+                self.generate_block(body);
+
+                if !self.returned() {
+                    // Back to the test once the body is done
+                    self.program.add(Quadruple {
+                        operation: Operation::Jump(start_label),
+                        operand1: Operand::Empty,
+                        operand2: Operand::Empty,
+                        result: Operand::Empty,
+                        span: None,
+                        statement_span: None,
+                    });
+                }
+                self.program.add(Quadruple {
+                    operation: Operation::Label(end_label),
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
             }
             StatementKind::For(var_name, init, end, step, body) => {
@@ -145,15 +462,52 @@ impl CodeGenerator {
                     _ => "unknown".to_string(), // Fallback, ideally handled by semantic analysis
                 };
 
-                // Generate initialization
+                // Bounds and step are evaluated once, before the first iteration
                 let init_val = self.generate_expression(init);
+                let end_val = self.generate_expression(end);
+                let end_val = self.freeze_operand(end_val);
+                let step_val = self.generate_expression(step);
+                let step_val = self.freeze_operand(step_val);
+                let var_operand = self.variable(&var_str);
+
                 self.program.add(Quadruple {
                     operation: Operation::Assign,
                     operand1: init_val,
                     operand2: Operand::Empty,
-                    result: Operand::Variable(var_str.clone()),
+                    result: var_operand.clone(),
+                    span: None,
+                    statement_span: None,
                 });
 
+                // A literal step fixes the direction, otherwise it's decided at runtime
+                let constant_step = match &step_val {
+                    Operand::IntLiteral(value) if *value != 0 => Some(*value),
+                    _ => None,
+                };
+                let ascending = match constant_step {
+                    Some(_) => None,
+                    None => {
+                        self.program.add(Quadruple {
+                            operation: Operation::CheckStep,
+                            operand1: step_val.clone(),
+                            operand2: Operand::Empty,
+                            result: Operand::Empty,
+                            span: Some(step.span.clone()),
+                            statement_span: None,
+                        });
+                        let ascending = self.program.new_temp();
+                        self.program.add(Quadruple {
+                            operation: Operation::GreaterThan,
+                            operand1: step_val.clone(),
+                            operand2: Operand::IntLiteral(0),
+                            result: ascending.clone(),
+                            span: None,
+                            statement_span: None,
+                        });
+                        Some(ascending)
+                    }
+                };
+
                 let loop_start = self.program.new_label();
                 let loop_end = self.program.new_label();
 
@@ -163,66 +517,153 @@ impl CodeGenerator {
                     operand1: Operand::Empty,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
 
-                // Generate end condition
-                let end_val = self.generate_expression(end);
-                let var_operand = Operand::Variable(var_str.clone());
+                // The end bound is inclusive in both directions
                 let temp = self.program.new_temp();
+                match &ascending {
+                    None => {
+                        let comparison = if constant_step.unwrap_or(1) > 0 {
+                            Operation::LessEqual
+                        } else {
+                            Operation::GreaterEqual
+                        };
+                        self.program.add(Quadruple {
+                            operation: comparison,
+                            operand1: var_operand.clone(),
+                            operand2: end_val.clone(),
+                            result: temp.clone(),
+                            span: None,
+                            statement_span: None,
+                        });
+                    }
+                    Some(ascending) => {
+                        let down_label = self.program.new_label();
+                        let test_label = self.program.new_label();
+                        self.program.add(Quadruple {
+                            operation: Operation::JumpIfFalse(down_label),
+                            operand1: ascending.clone(),
+                            operand2: Operand::Empty,
+                            result: Operand::Empty,
+                            span: None,
+                            statement_span: None,
+                        });
+                        self.program.add(Quadruple {
+                            operation: Operation::LessEqual,
+                            operand1: var_operand.clone(),
+                            operand2: end_val.clone(),
+                            result: temp.clone(),
+                            span: None,
+                            statement_span: None,
+                        });
+                        self.program.add(Quadruple {
+                            operation: Operation::Jump(test_label),
+                            operand1: Operand::Empty,
+                            operand2: Operand::Empty,
+                            result: Operand::Empty,
+                            span: None,
+                            statement_span: None,
+                        });
+                        self.program.add(Quadruple {
+                            operation: Operation::Label(down_label),
+                            operand1: Operand::Empty,
+                            operand2: Operand::Empty,
+                            result: Operand::Empty,
+                            span: None,
+                            statement_span: None,
+                        });
+                        self.program.add(Quadruple {
+                            operation: Operation::GreaterEqual,
+                            operand1: var_operand.clone(),
+                            operand2: end_val.clone(),
+                            result: temp.clone(),
+                            span: None,
+                            statement_span: None,
+                        });
+                        self.program.add(Quadruple {
+                            operation: Operation::Label(test_label),
+                            operand1: Operand::Empty,
+                            operand2: Operand::Empty,
+                            result: Operand::Empty,
+                            span: None,
+                            statement_span: None,
+                        });
+                    }
+                }
 
-                // Compare var with end value
-                self.program.add(Quadruple {
-                    operation: Operation::LessThan,
-                    operand1: var_operand.clone(),
-                    operand2: end_val,
-                    result: temp.clone(),
-                });
-
-                // If var >= end, exit loop
+                // Leave the loop once the iterator has passed the end bound
                 self.program.add(Quadruple {
                     operation: Operation::JumpIfFalse(loop_end),
                     operand1: temp,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
 
                 // Generate loop body
-                for stmt in body {
-                    self.generate_statement(stmt);
-                }
-
-                // Step increment
-                let step_val = self.generate_expression(step);
-                let new_val = self.program.new_temp();
-
-                self.program.add(Quadruple {
-                    operation: Operation::Add,
-                    operand1: var_operand.clone(),
-                    operand2: step_val,
-                    result: new_val.clone(),
-                });
+                self.generate_block(body);
+
+                // A body that always returns never reaches the step
+                if !self.returned() {
+                    // A step past the largest or smallest Int would overflow before the
+                    // bound test sees it, but it also passes any end bound: leave instead
+                    let bounded = match (&end_val, constant_step) {
+                        (Operand::IntLiteral(end), Some(step)) => {
+                            (INT_MIN..=INT_MAX).contains(&(*end as i64 + step as i64))
+                        }
+                        _ => false,
+                    };
+                    if !bounded {
+                        self.generate_step_limit(
+                            &var_operand,
+                            &step_val,
+                            constant_step,
+                            ascending.as_ref(),
+                            loop_end,
+                        );
+                    }
 
-                self.program.add(Quadruple {
-                    operation: Operation::Assign,
-                    operand1: new_val,
-                    operand2: Operand::Empty,
-                    result: var_operand,
-                });
+                    // Step increment
+                    let new_val = self.program.new_temp();
+                    self.program.add(Quadruple {
+                        operation: Operation::Add,
+                        operand1: var_operand.clone(),
+                        operand2: step_val,
+                        result: new_val.clone(),
+                        span: None,
+                        statement_span: None,
+                    });
 
-                // Jump back to condition
-                self.program.add(Quadruple {
-                    operation: Operation::Jump(loop_start),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                    self.program.add(Quadruple {
+                        operation: Operation::Assign,
+                        operand1: new_val,
+                        operand2: Operand::Empty,
+                        result: var_operand,
+                        span: None,
+                        statement_span: None,
+                    });
 
+                    // Jump back to condition
+                    self.program.add(Quadruple {
+                        operation: Operation::Jump(loop_start),
+                        operand1: Operand::Empty,
+                        operand2: Operand::Empty,
+                        result: Operand::Empty,
+                        span: None,
+                        statement_span: None,
+                    });
+                }
                 // Loop end label
                 self.program.add(Quadruple {
                     operation: Operation::Label(loop_end),
                     operand1: Operand::Empty,
                     operand2: Operand::Empty,
                     result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
                 });
             }
             StatementKind::Input(expr) => {
@@ -233,25 +674,21 @@ impl CodeGenerator {
                             operation: Operation::Input,
                             operand1: Operand::Empty,
                             operand2: Operand::Empty,
-                            result: Operand::Variable(name.clone()),
+                            result: self.variable(name),
+                            span: None,
+                            statement_span: None,
                         });
                     }
                     ExpressionKind::ArrayAccess(name, index_expr) => {
+                        // Reading straight into the element parses the value as the array's type
                         let index = self.generate_expression(index_expr);
-                        let temp = self.program.new_temp();
-
                         self.program.add(Quadruple {
                             operation: Operation::Input,
                             operand1: Operand::Empty,
                             operand2: Operand::Empty,
-                            result: temp.clone(),
-                        });
-
-                        self.program.add(Quadruple {
-                            operation: Operation::ArrayStore,
-                            operand1: temp,
-                            operand2: index,
-                            result: Operand::Variable(name.clone()),
+                            result: Operand::ArrayElement(self.names.resolve(name), Box::new(index)),
+                            span: None,
+                            statement_span: None,
                         });
                     }
                     _ => {
@@ -267,37 +704,194 @@ impl CodeGenerator {
                         operand1: result,
                         operand2: Operand::Empty,
                         result: Operand::Empty,
+                        span: None,
+                        statement_span: None,
                     });
                 }
+
+                // An output with no operand terminates the printed line
+                self.program.add(Quadruple {
+                    operation: Operation::Output,
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
             }
-            StatementKind::Scope(statements) => {
-                // Generate code for all statements in the scope
-                for stmt in statements {
-                    self.generate_statement(stmt);
+            StatementKind::Scope(declarations, statements) => {
+                // Declarations run every time the block is entered
+                self.names.enter_block();
+                for declaration in declarations {
+                    self.generate_declaration(declaration);
                 }
+                self.generate_block(statements);
+                self.names.exit();
+            }
+            StatementKind::Call(name, arguments) => {
+                self.generate_call(name, arguments, false);
+            }
+            StatementKind::Return(value) => {
+                let value = match value {
+                    Some(value) => self.generate_expression(value),
+                    None => Operand::Empty,
+                };
+                self.add_return(value);
             }
             StatementKind::Empty => {
                 // Do nothing for empty statements
             }
         }
+        self.program.set_missing_statement_spans(first, &statement.span);
+    }
+
+    /// Jumps to `exit` when adding the step would take the iterator out of the `Int` range.
+    /// The limit is computed from the step, which can't overflow.
+    fn generate_step_limit(
+        &mut self,
+        iterator: &Operand,
+        step: &Operand,
+        constant_step: Option<i32>,
+        ascending: Option<&Operand>,
+        exit: usize,
+    ) {
+        let past = self.program.new_temp();
+        match (constant_step, ascending) {
+            (Some(constant), _) => {
+                let (comparison, limit) = if constant > 0 {
+                    (Operation::GreaterThan, INT_MAX as i32 - constant)
+                } else {
+                    (Operation::LessThan, INT_MIN as i32 - constant)
+                };
+                self.program.add(Quadruple {
+                    operation: comparison,
+                    operand1: iterator.clone(),
+                    operand2: Operand::IntLiteral(limit),
+                    result: past.clone(),
+                    span: None,
+                    statement_span: None,
+                });
+            }
+            (None, Some(ascending)) => {
+                let down_label = self.program.new_label();
+                let test_label = self.program.new_label();
+                let limit = self.program.new_temp();
+                self.program.add(Quadruple {
+                    operation: Operation::JumpIfFalse(down_label),
+                    operand1: ascending.clone(),
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::Subtract,
+                    operand1: Operand::IntLiteral(INT_MAX as i32),
+                    operand2: step.clone(),
+                    result: limit.clone(),
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::GreaterThan,
+                    operand1: iterator.clone(),
+                    operand2: limit.clone(),
+                    result: past.clone(),
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::Jump(test_label),
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::Label(down_label),
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::Subtract,
+                    operand1: Operand::IntLiteral(INT_MIN as i32),
+                    operand2: step.clone(),
+                    result: limit.clone(),
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::LessThan,
+                    operand1: iterator.clone(),
+                    operand2: limit,
+                    result: past.clone(),
+                    span: None,
+                    statement_span: None,
+                });
+                self.program.add(Quadruple {
+                    operation: Operation::Label(test_label),
+                    operand1: Operand::Empty,
+                    operand2: Operand::Empty,
+                    result: Operand::Empty,
+                    span: None,
+                    statement_span: None,
+                });
+            }
+            (None, None) => return,
+        }
+        self.program.add(Quadruple {
+            operation: Operation::JumpIfTrue(exit),
+            operand1: past,
+            operand2: Operand::Empty,
+            result: Operand::Empty,
+            span: None,
+            statement_span: None,
+        });
+    }
+
+    /// Copies a variable operand into a temporary so later writes to the variable don't affect it
+    fn freeze_operand(&mut self, operand: Operand) -> Operand {
+        match operand {
+            Operand::Variable(_) => {
+                let temp = self.program.new_temp();
+                self.program.add(Quadruple {
+                    operation: Operation::Assign,
+                    operand1: operand,
+                    operand2: Operand::Empty,
+                    result: temp.clone(),
+                    span: None,
+                    statement_span: None,
+                });
+                temp
+            }
+            _ => operand,
+        }
     }
 
     fn generate_expression(&mut self, expr: &Expression) -> Operand {
-        match &expr.node {
-            ExpressionKind::Identifier(name) => Operand::Variable(name.clone()),
+        let first = self.program.quadruples.len();
+        let result = match &expr.node {
+            ExpressionKind::Identifier(name) => self.variable(name),
             ExpressionKind::ArrayAccess(name, index_expr) => {
                 let index = self.generate_expression(index_expr);
                 let temp = self.program.new_temp();
 
                 self.program.add(Quadruple {
                     operation: Operation::ArrayLoad,
-                    operand1: Operand::Variable(name.clone()),
+                    operand1: self.variable(name),
                     operand2: index,
                     result: temp.clone(),
+                    span: None,
+                    statement_span: None,
                 });
 
                 temp
             }
+            ExpressionKind::Call(name, arguments) => self.generate_call(name, arguments, true),
             ExpressionKind::Literal(lit) => match &lit.node {
                 LiteralKind::Int(value) => Operand::IntLiteral(*value),
                 LiteralKind::Float(value) => Operand::FloatLiteral(*value),
@@ -310,6 +904,7 @@ impl CodeGenerator {
 
                 // Map AST operator to quadruple operation
                 let operation = match op {
+                    Operator::Add if self.is_string(left) => Operation::Concat,
                     Operator::Add => Operation::Add,
                     Operator::Subtract => Operation::Subtract,
                     Operator::Multiply => Operation::Multiply,
@@ -329,6 +924,8 @@ impl CodeGenerator {
                     operand1: left_result,
                     operand2: right_result,
                     result: result.clone(),
+                    span: None,
+                    statement_span: None,
                 });
 
                 result
@@ -339,6 +936,7 @@ impl CodeGenerator {
 
                 let operation = match op {
                     UnaryOperator::Not => Operation::Not,
+                    UnaryOperator::Length => Operation::Length,
                 };
 
                 self.program.add(Quadruple {
@@ -346,10 +944,14 @@ impl CodeGenerator {
                     operand1: expr_result,
                     operand2: Operand::Empty,
                     result: result.clone(),
+                    span: None,
+                    statement_span: None,
                 });
 
                 result
             }
-        }
+        };
+        self.program.set_missing_spans(first, &expr.span);
+        result
     }
 }
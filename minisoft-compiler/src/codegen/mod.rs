@@ -1,4 +1,8 @@
+pub mod cfg;
 pub mod generator;
 pub mod quadruple;
+pub mod validator;
 
+pub use cfg::{BasicBlock, ControlFlowGraph, NaturalLoop};
 pub use quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+pub use validator::{ValidationError, validate, validate_branches};
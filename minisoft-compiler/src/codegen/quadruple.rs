@@ -1,4 +1,5 @@
 use std::fmt;
+use std::ops::Range;
 
 /// Represents the type of operation in a quadruple
 #[derive(Debug, Clone, PartialEq)]
@@ -9,6 +10,12 @@ pub enum Operation {
     Multiply,
     Divide,
 
+    // String operations
+    /// Joins the string in `operand1` and the one in `operand2`
+    Concat,
+    /// Number of characters of the string in `operand1`
+    Length,
+
     // Assignment and memory operations
     Assign,
     ArrayStore,
@@ -19,6 +26,8 @@ pub enum Operation {
     Jump(usize),
     JumpIfTrue(usize),
     JumpIfFalse(usize),
+    /// Fails at runtime when a `for` loop step evaluates to zero
+    CheckStep,
 
     // Comparison operations
     Equal,
@@ -38,8 +47,14 @@ pub enum Operation {
     Output,
 
     // Function operations
+    /// Hands the value of `operand1` to the parameter named by `result` for the next call
+    Param,
+    /// Calls a subprogram with `operand1` parameters, a function stores its value in `result`
     Call(String),
+    /// Leaves the current subprogram with the value of `operand1`, or stops the main program
     Return,
+    /// First quadruple of a subprogram, where calls to it jump
+    Entry(String),
 }
 
 /// Represents an operand in a quadruple
@@ -61,6 +76,80 @@ pub struct Quadruple {
     pub operand1: Operand,
     pub operand2: Operand,
     pub result: Operand,
+    /// Source span of the expression or statement the quadruple was generated from
+    pub span: Option<Range<usize>>,
+    /// Source span of the enclosing statement, used to step through source statements
+    pub statement_span: Option<Range<usize>>,
+}
+
+impl Operation {
+    /// Arithmetic, concatenation, comparison and logical operations reading both operands
+    pub fn is_binary(&self) -> bool {
+        matches!(
+            self,
+            Operation::Add
+                | Operation::Subtract
+                | Operation::Multiply
+                | Operation::Divide
+                | Operation::Concat
+                | Operation::Equal
+                | Operation::NotEqual
+                | Operation::LessThan
+                | Operation::GreaterThan
+                | Operation::LessEqual
+                | Operation::GreaterEqual
+                | Operation::And
+                | Operation::Or
+        )
+    }
+}
+
+impl Quadruple {
+    /// Operands whose values are read, array names excluded
+    pub fn uses(&self) -> Vec<&Operand> {
+        let mut uses = Vec::new();
+        match self.operation {
+            Operation::Label(_) | Operation::Jump(_) | Operation::Input | Operation::Entry(_) => {}
+            Operation::ArrayLoad => uses.push(&self.operand2),
+            _ => {
+                uses.push(&self.operand1);
+                uses.push(&self.operand2);
+            }
+        }
+        if let Operand::ArrayElement(_, index) = &self.result {
+            uses.push(index);
+        }
+        uses.retain(|operand| **operand != Operand::Empty);
+        uses
+    }
+
+    /// Mutable access to the operands returned by `uses`
+    pub fn uses_mut(&mut self) -> Vec<&mut Operand> {
+        let mut uses = Vec::new();
+        match self.operation {
+            Operation::Label(_) | Operation::Jump(_) | Operation::Input | Operation::Entry(_) => {}
+            Operation::ArrayLoad => uses.push(&mut self.operand2),
+            _ => {
+                uses.push(&mut self.operand1);
+                uses.push(&mut self.operand2);
+            }
+        }
+        if let Operand::ArrayElement(_, index) = &mut self.result {
+            uses.push(index.as_mut());
+        }
+        uses.retain(|operand| **operand != Operand::Empty);
+        uses
+    }
+
+    /// The variable or temporary the quadruple writes, array elements excluded.
+    /// A parameter is only written once the call it is passed to starts.
+    pub fn definition(&self) -> Option<&Operand> {
+        match (&self.operation, &self.result) {
+            (Operation::ArrayStore | Operation::Param, _) => None,
+            (_, Operand::Variable(_) | Operand::TempVariable(_)) => Some(&self.result),
+            _ => None,
+        }
+    }
 }
 
 /// Collection of quadruples representing a program
@@ -93,6 +182,25 @@ impl QuadrupleProgram {
         Operand::TempVariable(temp)
     }
 
+    /// Attach a source span to every quadruple from `start` that doesn't have one yet
+    pub fn set_missing_spans(&mut self, start: usize, span: &Range<usize>) {
+        for quad in &mut self.quadruples[start..] {
+            if quad.span.is_none() {
+                quad.span = Some(span.clone());
+            }
+        }
+    }
+
+    /// Attach the enclosing statement span to every quadruple from `start` that doesn't have one yet
+    pub fn set_missing_statement_spans(&mut self, start: usize, span: &Range<usize>) {
+        for quad in &mut self.quadruples[start..] {
+            if quad.statement_span.is_none() {
+                quad.statement_span = Some(span.clone());
+            }
+        }
+        self.set_missing_spans(start, span);
+    }
+
     /// Generate a new label identifier
     pub fn new_label(&mut self) -> usize {
         let label = self.next_label;
@@ -114,6 +222,8 @@ impl fmt::Display for Operation {
             Operation::Subtract => write!(f, "SUB"),
             Operation::Multiply => write!(f, "MUL"),
             Operation::Divide => write!(f, "DIV"),
+            Operation::Concat => write!(f, "CONCAT"),
+            Operation::Length => write!(f, "LEN"),
             Operation::Assign => write!(f, "ASSIGN"),
             Operation::ArrayStore => write!(f, "ASTORE"),
             Operation::ArrayLoad => write!(f, "ALOAD"),
@@ -121,6 +231,7 @@ impl fmt::Display for Operation {
             Operation::Jump(id) => write!(f, "JUMP_{}", id),
             Operation::JumpIfTrue(id) => write!(f, "JMPT_{}", id),
             Operation::JumpIfFalse(id) => write!(f, "JMPF_{}", id),
+            Operation::CheckStep => write!(f, "CHKSTEP"),
             Operation::Equal => write!(f, "EQ"),
             Operation::NotEqual => write!(f, "NEQ"),
             Operation::LessThan => write!(f, "LT"),
@@ -132,8 +243,10 @@ impl fmt::Display for Operation {
             Operation::Not => write!(f, "NOT"),
             Operation::Input => write!(f, "INPUT"),
             Operation::Output => write!(f, "OUTPUT"),
+            Operation::Param => write!(f, "PARAM"),
             Operation::Call(name) => write!(f, "CALL_{}", name),
             Operation::Return => write!(f, "RETURN"),
+            Operation::Entry(name) => write!(f, "ENTRY_{}", name),
         }
     }
 }
@@ -0,0 +1,270 @@
+use crate::codegen::cfg::ControlFlowGraph;
+use crate::codegen::quadruple::{Operand, Operation, QuadrupleProgram};
+use crate::parser::ast::{Program, Statement, StatementKind};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+/// A violation of the structural rules every generated quadruple program must follow
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// A jump refers to a label that is never placed
+    UndefinedLabel { index: usize, label: usize },
+
+    /// The same label is placed more than once
+    DuplicateLabel {
+        index: usize,
+        label: usize,
+        first_index: usize,
+    },
+
+    /// A label id that was never handed out by `new_label`
+    UnallocatedLabel { index: usize, label: usize },
+
+    /// A quadruple directly after an unconditional jump that no label makes reachable
+    UnreachableCode { index: usize },
+
+    /// A temporary read before any quadruple defines it
+    TempUsedBeforeDefinition { index: usize, name: String },
+
+    /// A call to a subprogram whose entry is never placed
+    UndefinedSubprogram { index: usize, name: String },
+
+    /// One branch of an if-then-else runs on into the other without testing again
+    BranchFallsThrough { index: usize, into: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UndefinedLabel { index, label } => {
+                write!(f, "quadruple {} jumps to undefined label {}", index, label)
+            }
+            ValidationError::DuplicateLabel {
+                index,
+                label,
+                first_index,
+            } => write!(
+                f,
+                "label {} at quadruple {} was already placed at quadruple {}",
+                label, index, first_index
+            ),
+            ValidationError::UnallocatedLabel { index, label } => write!(
+                f,
+                "quadruple {} uses label {} which was never allocated",
+                index, label
+            ),
+            ValidationError::UnreachableCode { index } => write!(
+                f,
+                "quadruple {} follows an unconditional jump without a label",
+                index
+            ),
+            ValidationError::TempUsedBeforeDefinition { index, name } => write!(
+                f,
+                "quadruple {} reads temporary '{}' before it is defined",
+                index, name
+            ),
+            ValidationError::UndefinedSubprogram { index, name } => write!(
+                f,
+                "quadruple {} calls subprogram '{}' which has no entry",
+                index, name
+            ),
+            ValidationError::BranchFallsThrough { index, into } => write!(
+                f,
+                "quadruple {} of a branch can run on into quadruple {} of the other branch",
+                index, into
+            ),
+        }
+    }
+}
+
+/// Checks that a quadruple program is well formed:
+/// every jump targets an existing label and every call an existing subprogram,
+/// labels are unique, control never falls into code that only a missing label
+/// could reach, and temporaries are defined before they are read.
+pub fn validate(program: &QuadrupleProgram) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    // Collect label positions first so forward jumps can be resolved
+    let mut labels: HashMap<usize, usize> = HashMap::new();
+    for (index, quad) in program.quadruples.iter().enumerate() {
+        if let Operation::Label(label) = quad.operation {
+            if label >= program.next_label {
+                errors.push(ValidationError::UnallocatedLabel { index, label });
+            }
+            if let Some(&first_index) = labels.get(&label) {
+                errors.push(ValidationError::DuplicateLabel {
+                    index,
+                    label,
+                    first_index,
+                });
+            } else {
+                labels.insert(label, index);
+            }
+        }
+    }
+
+    let entries: HashSet<&str> = program
+        .quadruples
+        .iter()
+        .filter_map(|quad| match &quad.operation {
+            Operation::Entry(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut defined_temps: HashSet<&str> = HashSet::new();
+    let mut after_unconditional_jump = false;
+
+    for (index, quad) in program.quadruples.iter().enumerate() {
+        let reachable = matches!(quad.operation, Operation::Label(_) | Operation::Entry(_));
+        if after_unconditional_jump && !reachable {
+            errors.push(ValidationError::UnreachableCode { index });
+        }
+
+        match &quad.operation {
+            Operation::Jump(label)
+            | Operation::JumpIfTrue(label)
+            | Operation::JumpIfFalse(label)
+                if !labels.contains_key(label) =>
+            {
+                errors.push(ValidationError::UndefinedLabel {
+                    index,
+                    label: *label,
+                });
+            }
+            Operation::Call(name) if !entries.contains(name.as_str()) => {
+                errors.push(ValidationError::UndefinedSubprogram {
+                    index,
+                    name: name.clone(),
+                });
+            }
+            _ => {}
+        }
+
+        for operand in [&quad.operand1, &quad.operand2] {
+            for name in read_temps(operand) {
+                if !defined_temps.contains(name) {
+                    errors.push(ValidationError::TempUsedBeforeDefinition {
+                        index,
+                        name: name.to_string(),
+                    });
+                }
+            }
+        }
+        // Array element targets read their index operand
+        if let Operand::ArrayElement(_, index_operand) = &quad.result {
+            for name in read_temps(index_operand) {
+                if !defined_temps.contains(name) {
+                    errors.push(ValidationError::TempUsedBeforeDefinition {
+                        index,
+                        name: name.to_string(),
+                    });
+                }
+            }
+        }
+        if let Operand::TempVariable(name) = &quad.result {
+            defined_temps.insert(name);
+        }
+
+        after_unconditional_jump = matches!(quad.operation, Operation::Jump(_) | Operation::Return);
+    }
+
+    errors
+}
+
+/// Checks that no path runs both branches of an if-then-else of the program the
+/// quadruples were generated from. Quadruples belong to the branch holding their
+/// statement, and a path only gets from one branch to the other by going through
+/// the test of the if again, as a loop around it does.
+pub fn validate_branches(program: &QuadrupleProgram, ast: &Program) -> Vec<ValidationError> {
+    let mut branches = Vec::new();
+    collect_branches(&ast.statements, &mut branches);
+    for subprogram in &ast.subprograms {
+        collect_branches(&subprogram.node.statements, &mut branches);
+    }
+
+    let cfg = ControlFlowGraph::build(program);
+    let mut errors = Vec::new();
+    for (statement, then_span, else_span) in branches {
+        // Position of the first quadruple of each block belonging to a span
+        let first_in = |span: &Range<usize>| -> HashMap<usize, usize> {
+            cfg.blocks
+                .iter()
+                .filter_map(|block| {
+                    let offset = block.quadruples.iter().position(|quad| {
+                        quad.statement_span.as_ref().is_some_and(|statement| {
+                            span.start <= statement.start && statement.end <= span.end
+                        })
+                    })?;
+                    Some((block.id, block.start + offset))
+                })
+                .collect()
+        };
+        let tests: HashSet<usize> = cfg
+            .blocks
+            .iter()
+            .filter(|block| {
+                block.terminator().is_some_and(|quad| {
+                    matches!(quad.operation, Operation::JumpIfFalse(_))
+                        && quad.statement_span.as_ref() == Some(&statement)
+                })
+            })
+            .map(|block| block.id)
+            .collect();
+
+        let then_blocks = first_in(&then_span);
+        let else_blocks = first_in(&else_span);
+        for (from, to) in [(&then_blocks, &else_blocks), (&else_blocks, &then_blocks)] {
+            for (&block, &index) in from {
+                let mut visited = HashSet::from([block]);
+                let mut stack = vec![block];
+                while let Some(current) = stack.pop() {
+                    if let Some(&into) = to.get(&current) {
+                        errors.push(ValidationError::BranchFallsThrough { index, into });
+                        break;
+                    }
+                    for &successor in &cfg.blocks[current].successors {
+                        if !tests.contains(&successor) && visited.insert(successor) {
+                            stack.push(successor);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Span of every if-then-else with two non-empty branches, and the spans of its branches
+fn collect_branches(
+    statements: &[Statement],
+    branches: &mut Vec<(Range<usize>, Range<usize>, Range<usize>)>,
+) {
+    let span = |block: &[Statement]| Some(block.first()?.span.start..block.last()?.span.end);
+    for statement in statements {
+        match &statement.node {
+            StatementKind::IfThen(_, body)
+            | StatementKind::DoWhile(body, _)
+            | StatementKind::While(_, body)
+            | StatementKind::For(.., body)
+            | StatementKind::Scope(_, body) => collect_branches(body, branches),
+            StatementKind::IfThenElse(_, then_block, else_block) => {
+                if let (Some(then_span), Some(else_span)) = (span(then_block), span(else_block)) {
+                    branches.push((statement.span.clone(), then_span, else_span));
+                }
+                collect_branches(then_block, branches);
+                collect_branches(else_block, branches);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_temps(operand: &Operand) -> Vec<&str> {
+    match operand {
+        Operand::TempVariable(name) => vec![name],
+        Operand::ArrayElement(_, index) => read_temps(index),
+        _ => vec![],
+    }
+}
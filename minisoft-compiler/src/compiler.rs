@@ -1,12 +1,42 @@
+use crate::codegen::{ControlFlowGraph, QuadrupleProgram};
 use crate::error_reporter::ErrorReporter;
+use crate::lint::Linter;
+use crate::optimizer::{OptimizationLevel, PassManager};
+use crate::runtime::{Debugger, InputSource};
+use crate::semantics::SemanticAnalyzer;
 use crate::{SerializableCompilationResult, SerializableToken, SerializableProgram, 
            SerializableCompilationErrors, SerializableLexicalError, SerializableErrorPosition,
-           SerializableSymbol, SerializableQuadrupleProgram};
-
+           SerializableSymbol, SerializableQuadrupleProgram, SerializableExecutionResult,
+           SerializableControlFlowGraph, SerializableDotGraphs, SerializablePassReport,
+           SerializableRuleHits, SerializableDataflow, SerializableSemanticError};
+use serde::{Deserialize, Serialize};
 
+/// Settings of a compilation that don't come from the source code
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompilerOptions {
+    pub optimization_level: OptimizationLevel,
+    /// Names of the lint rules not to run, every other rule is enabled
+    pub disabled_lints: Vec<String>,
+    /// Whether each pass report carries a diff against the program before the pass
+    pub pass_diffs: bool,
+}
 
+impl CompilerOptions {
+    /// Options of a compilation that only checks the program for errors
+    pub fn check_only() -> Self {
+        CompilerOptions {
+            optimization_level: OptimizationLevel::O0,
+            ..CompilerOptions::default()
+        }
+    }
+}
 
-pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilationResult, String> {
+pub fn compile_code(
+    code: String,
+    verbose: bool,
+    options: &CompilerOptions,
+) -> Result<SerializableCompilationResult, String> {
     if verbose {
         println!("Starting compilation in verbose mode");
     }
@@ -30,6 +60,7 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
             ast: SerializableProgram {
                 name: "".to_string(),
                 declarations: vec![],
+                subprograms: vec![],
                 statements: vec![],
             },
             symbol_table: vec![],
@@ -38,6 +69,12 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                 next_temp: 0,
                 next_label: 0,
             },
+            optimized_quadruples: SerializableQuadrupleProgram::default(),
+            optimization_report: vec![],
+            optimization_level: options.optimization_level,
+            control_flow_graph: SerializableControlFlowGraph::default(),
+            dataflow: SerializableDataflow::default(),
+            dot: SerializableDotGraphs::default(),
             errors: Some(SerializableCompilationErrors {
                 lexical_errors: lexical_errors
                     .iter()
@@ -55,6 +92,8 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                 syntax_errors: vec![],
                 semantic_errors: vec![],
             }),
+            warnings: vec![],
+            lints: vec![],
         });
     }
 
@@ -77,6 +116,7 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
             ast: SerializableProgram {
                 name: "".to_string(),
                 declarations: vec![],
+                subprograms: vec![],
                 statements: vec![],
             },
             symbol_table: vec![],
@@ -85,11 +125,19 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                 next_temp: 0,
                 next_label: 0,
             },
+            optimized_quadruples: SerializableQuadrupleProgram::default(),
+            optimization_report: vec![],
+            optimization_level: options.optimization_level,
+            control_flow_graph: SerializableControlFlowGraph::default(),
+            dataflow: SerializableDataflow::default(),
+            dot: SerializableDotGraphs::default(),
             errors: Some(SerializableCompilationErrors {
                 lexical_errors: vec![],
                 syntax_errors: vec![crate::convert_syntax_error(syntax_error)],
                 semantic_errors: vec![],
             }),
+            warnings: vec![],
+            lints: vec![],
         });
     }
 
@@ -98,6 +146,13 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
     let mut analyzer = crate::semantics::SemanticAnalyzer::new(&code);
     analyzer.analyze(&ast);
     let semantic_errors = analyzer.get_errors();
+    let warnings: Vec<SerializableSemanticError> = analyzer
+        .get_warnings()
+        .iter()
+        .map(crate::convert_semantic_error)
+        .collect();
+
+    let ast_dot = crate::dot::ast_to_dot(&ast);
 
     if !semantic_errors.is_empty() {
         // Return semantic errors
@@ -119,6 +174,7 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                 .iter()
                 .map(|s| SerializableSymbol {
                     name: s.name.clone(),
+                    scope: s.scope_name().to_string(),
                     kind: format!("{:?}", s.kind),
                     symbol_type: s.symbol_type.to_string(),
                     value: format!("{:?}", s.value),
@@ -131,6 +187,16 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                 next_temp: 0,
                 next_label: 0,
             },
+            optimized_quadruples: SerializableQuadrupleProgram::default(),
+            optimization_report: vec![],
+            optimization_level: options.optimization_level,
+            control_flow_graph: SerializableControlFlowGraph::default(),
+            dataflow: SerializableDataflow::default(),
+            dot: SerializableDotGraphs {
+                ast: ast_dot,
+                control_flow_graph: String::new(),
+                symbol_table: crate::dot::symbol_table_to_dot(analyzer.get_symbol_table()),
+            },
             errors: Some(SerializableCompilationErrors {
                 lexical_errors: vec![],
                 syntax_errors: vec![],
@@ -139,16 +205,58 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                     .map(|e| crate::convert_semantic_error(e))
                     .collect(),
             }),
+            warnings,
+            lints: vec![],
         });
     }
 
-    // If we reach here, no errors were found - proceed with code generation
+    // If we reach here, no errors were found - lint, then proceed with code generation
+    let lints = Linter::without_rules(&options.disabled_lints)
+        .run(&ast, analyzer.get_symbol_table(), &code)
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
     let mut code_generator = crate::codegen::generator::CodeGenerator::new();
     let quadruples = match code_generator.generate_code(&ast) {
         Some(quads) => quads,
         None => return Err("Code generation failed".to_string()),
     };
 
+    let mut optimized = quadruples.clone();
+    let mut pass_manager =
+        PassManager::for_level(options.optimization_level, analyzer.get_symbol_table());
+    if options.pass_diffs {
+        pass_manager = pass_manager.with_diffs();
+    }
+    let snapshots = pass_manager.run(&mut optimized);
+    let optimization_report: Vec<SerializablePassReport> = snapshots
+        .into_iter()
+        .map(|snapshot| SerializablePassReport {
+            pass: snapshot.pass.to_string(),
+            changes: snapshot.changes,
+            rule_hits: snapshot
+                .rule_hits
+                .into_iter()
+                .map(|(rule, hits)| SerializableRuleHits {
+                    rule: rule.to_string(),
+                    hits,
+                })
+                .collect(),
+            quadruples: snapshot.program.into(),
+            diff: snapshot
+                .diff
+                .map(|diff| diff.into_iter().map(Into::into).collect()),
+        })
+        .collect();
+
+    let cfg = ControlFlowGraph::build(&quadruples);
+    let dot = SerializableDotGraphs {
+        ast: ast_dot,
+        control_flow_graph: crate::dot::cfg_to_dot(&cfg),
+        symbol_table: crate::dot::symbol_table_to_dot(analyzer.get_symbol_table()),
+    };
+
     // All successful - return the complete compilation result
     Ok(SerializableCompilationResult {
         tokens: tokens
@@ -168,6 +276,7 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
             .iter()
             .map(|s| SerializableSymbol {
                 name: s.name.clone(),
+                scope: s.scope_name().to_string(),
                 kind: format!("{:?}", s.kind),
                 symbol_type: s.symbol_type.to_string(),
                 value: format!("{:?}", s.value),
@@ -175,7 +284,80 @@ pub fn compile_code(code: String, verbose: bool) -> Result<SerializableCompilati
                 column: s.column,
             })
             .collect(),
+        optimized_quadruples: optimized.into(),
+        optimization_report,
+        optimization_level: options.optimization_level,
+        dataflow: (&cfg).into(),
+        control_flow_graph: cfg.into(),
+        dot,
         quadruples: quadruples.into(),
         errors: None, // No errors on success
+        warnings,
+        lints,
+    })
+}
+
+/// Runs the program after optimizing it at `level`
+pub fn execute_code(
+    code: String,
+    input: &mut dyn InputSource,
+    level: OptimizationLevel,
+) -> Result<SerializableExecutionResult, String> {
+    // Report compilation errors instead of running a broken program
+    let compilation = compile_code(code.clone(), false, &CompilerOptions::check_only())?;
+    if compilation.errors.is_some() {
+        return Ok(SerializableExecutionResult {
+            output: vec![],
+            memory: vec![],
+            steps: 0,
+            runtime_error: None,
+            compilation_errors: compilation.errors,
+        });
+    }
+
+    let (mut quadruples, analyzer) = generate_quadruples(&code)?;
+    PassManager::for_level(level, analyzer.get_symbol_table()).run(&mut quadruples);
+    let result = crate::runtime::execute_with_input(
+        &quadruples,
+        analyzer.get_symbol_table(),
+        &code,
+        input,
+    );
+
+    Ok(SerializableExecutionResult {
+        output: result.output,
+        memory: result.memory.into(),
+        steps: result.steps,
+        runtime_error: result.error.map(Into::into),
+        compilation_errors: None,
     })
 }
+
+pub fn create_debugger(code: String, inputs: Vec<String>) -> Result<Debugger, String> {
+    let compilation = compile_code(code.clone(), false, &CompilerOptions::check_only())?;
+    if compilation.errors.is_some() {
+        return Err("Cannot debug a program with compilation errors".to_string());
+    }
+
+    let (quadruples, analyzer) = generate_quadruples(&code)?;
+    Ok(Debugger::new(
+        &quadruples,
+        analyzer.get_symbol_table(),
+        &code,
+        inputs,
+    ))
+}
+
+// Runs the front end and code generation on a program already known to be valid
+fn generate_quadruples(code: &String) -> Result<(QuadrupleProgram, SemanticAnalyzer), String> {
+    let (tokens, _) = crate::lexer::lexer_core::tokenize(code);
+    let ast = crate::parser::parser_core::parse(tokens, code).map_err(|e| e.to_string())?;
+    let mut analyzer = SemanticAnalyzer::new(code);
+    analyzer.analyze(&ast);
+
+    let mut code_generator = crate::codegen::generator::CodeGenerator::new();
+    let quadruples = code_generator
+        .generate_code(&ast)
+        .ok_or_else(|| "Code generation failed".to_string())?;
+    Ok((quadruples, analyzer))
+}
@@ -0,0 +1,124 @@
+use crate::codegen::cfg::ControlFlowGraph;
+use crate::codegen::quadruple::Operation;
+use crate::parser::ast::Program;
+use crate::parser::pretty_print::TreeNode;
+use crate::semantics::symbol_table::{SymbolKind, SymbolTable, SymbolValue};
+use std::fmt::Write;
+
+/// Renders the syntax tree of a program as a Graphviz digraph
+pub fn ast_to_dot(program: &Program) -> String {
+    let mut dot = String::from("digraph AST {\n");
+    dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+    let mut next_id = 0;
+    write_tree_node(&mut dot, &program.to_tree(), &mut next_id);
+    dot.push_str("}\n");
+    dot
+}
+
+fn write_tree_node(dot: &mut String, node: &TreeNode, next_id: &mut usize) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    let _ = writeln!(dot, "    n{} [label=\"{}\"];", id, escape(&node.label));
+    for child in &node.children {
+        let child_id = write_tree_node(dot, child, next_id);
+        let _ = writeln!(dot, "    n{} -> n{};", id, child_id);
+    }
+    id
+}
+
+/// Renders the basic blocks of a control-flow graph with their quadruples
+pub fn cfg_to_dot(cfg: &ControlFlowGraph) -> String {
+    let mut dot = String::from("digraph CFG {\n");
+    dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+    dot.push_str("    entry [shape=point];\n");
+    if !cfg.blocks.is_empty() {
+        dot.push_str("    entry -> B0;\n");
+    }
+
+    for block in &cfg.blocks {
+        let mut label = format!("B{}\\l", block.id);
+        for (offset, quad) in block.quadruples.iter().enumerate() {
+            label.push_str(&format!("{}: {}\\l", block.start + offset, escape(&quad.to_string())));
+        }
+        let _ = writeln!(dot, "    B{} [label=\"{}\"];", block.id, label);
+    }
+
+    for block in &cfg.blocks {
+        let branch = block.quadruples.last().map(|quad| &quad.operation);
+        for &successor in &block.successors {
+            // Label conditional edges with the value of the tested condition
+            let jumps_to = |label: &usize| cfg.blocks[successor].label() == Some(*label);
+            let condition = match branch {
+                Some(Operation::JumpIfTrue(label)) => Some(jumps_to(label)),
+                Some(Operation::JumpIfFalse(label)) => Some(!jumps_to(label)),
+                _ => None,
+            };
+            match condition {
+                Some(value) => {
+                    let _ = writeln!(
+                        dot,
+                        "    B{} -> B{} [label=\"{}\"];",
+                        block.id, successor, value
+                    );
+                }
+                None => {
+                    let _ = writeln!(dot, "    B{} -> B{};", block.id, successor);
+                }
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Renders the symbol table as a single table node, sorted by scope and name
+pub fn symbol_table_to_dot(symbol_table: &SymbolTable) -> String {
+    let mut symbols = symbol_table.get_all();
+    symbols.sort_by(|a, b| (&a.scope, &a.name).cmp(&(&b.scope, &b.name)));
+
+    let mut dot = String::from("digraph SymbolTable {\n");
+    dot.push_str("    node [shape=plaintext, fontname=\"monospace\"];\n");
+    dot.push_str("    symbols [label=<\n");
+    dot.push_str("        <table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\n");
+    dot.push_str("            <tr><td><b>Name</b></td><td><b>Scope</b></td><td><b>Kind</b></td><td><b>Type</b></td><td><b>Value</b></td><td><b>Line</b></td></tr>\n");
+    for symbol in symbols {
+        let kind = match symbol.kind {
+            SymbolKind::Variable => "Variable".to_string(),
+            SymbolKind::Constant => "Constant".to_string(),
+            SymbolKind::Array(size) => format!("Array[{}]", size),
+        };
+        let value = match &symbol.value {
+            SymbolValue::Single(literal) => format!("{:?}", literal),
+            SymbolValue::Array(values) => format!("{:?}", values),
+            SymbolValue::Uninitialized => "-".to_string(),
+        };
+        let _ = writeln!(
+            dot,
+            "            <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&symbol.name),
+            escape_html(symbol.scope_name()),
+            escape_html(&kind),
+            escape_html(&symbol.symbol_type.to_string()),
+            escape_html(&value),
+            symbol.line
+        );
+    }
+    dot.push_str("        </table>\n");
+    dot.push_str("    >];\n");
+    dot.push_str("}\n");
+    dot
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
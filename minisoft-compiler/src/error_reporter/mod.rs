@@ -2,4 +2,5 @@ mod reporter;
 
 pub use reporter::ErrorReporter;
 pub use reporter::format_code_context;
-pub use reporter::ErrorReportFormatter;
\ No newline at end of file
+pub use reporter::ErrorReportFormatter;
+pub use reporter::Severity;
\ No newline at end of file
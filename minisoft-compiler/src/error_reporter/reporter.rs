@@ -1,4 +1,6 @@
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 pub fn format_code_context(source_line: &str, column: usize, token_length: usize) -> String {
     let mut result: String = String::new();
@@ -25,11 +27,54 @@ pub fn format_code_context(source_line: &str, column: usize, token_length: usize
     result
 }
 
+/// How serious a diagnostic is. Only errors stop the compilation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    /// Title used in report headers, such as "Semantic Warning"
+    pub fn title(&self) -> &'static str {
+        match self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Note => "Note",
+        }
+    }
+
+    /// Colors a header the way diagnostics of this severity are shown
+    pub fn paint(&self, text: &str) -> ColoredString {
+        match self {
+            Severity::Error => text.red().bold(),
+            Severity::Warning => text.yellow().bold(),
+            Severity::Note => text.cyan().bold(),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
 pub trait ErrorReporter {
     fn report(&self, source_code: Option<&str>) -> String;
     fn get_suggestion(&self) -> Option<String>;
     fn get_error_name(&self) -> String;
     fn get_location_info(&self) -> (usize, usize);
+
+    /// Lexical and syntax errors always stop the compilation
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
 }
 
 pub struct ErrorReportFormatter;
@@ -37,9 +82,15 @@ pub struct ErrorReportFormatter;
 impl ErrorReportFormatter {
 
     pub fn print_errors<E: ErrorReporter>(errors: &[E], source_code: Option<&str>) {
-        println!("{} {} error(s) found\n", 
-            "Error:".red().bold(), 
-            errors.len());
+        let severity = errors
+            .iter()
+            .map(|error| error.severity())
+            .min()
+            .unwrap_or(Severity::Error);
+        println!("{} {} {}(s) found\n", 
+            severity.paint(&format!("{}:", severity.title())), 
+            errors.len(),
+            severity);
         
         for (_i, error) in errors.iter().enumerate() {
             
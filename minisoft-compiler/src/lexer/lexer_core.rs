@@ -14,6 +14,13 @@ pub struct TokenWithMetaData {
 }
 
 pub fn tokenize(source: &str) -> (Vec<TokenWithMetaData>, Vec<LexicalError>) {
+    let (mut tokens, errors) = tokenize_with_comments(source);
+    tokens.retain(|token| token.kind != Token::Comment);
+    (tokens, errors)
+}
+
+/// Same as `tokenize`, with the comments left among the tokens where they appear
+pub fn tokenize_with_comments(source: &str) -> (Vec<TokenWithMetaData>, Vec<LexicalError>) {
     let mut lexer = Token::lexer(source);
     let mut valid_tokens = Vec::new();
     let mut errors = Vec::new();
@@ -21,7 +28,7 @@ pub fn tokenize(source: &str) -> (Vec<TokenWithMetaData>, Vec<LexicalError>) {
     while let Some(valid_result) = lexer.next() {
         let span = lexer.span();
         let value = lexer.slice().to_string();
-        let (line, column) = get_position(&lexer, span.start);
+        let (line, column) = get_position(&lexer, span.start, &value);
 
         match valid_result {
             Ok(kind) => {
@@ -49,7 +56,13 @@ pub fn tokenize(source: &str) -> (Vec<TokenWithMetaData>, Vec<LexicalError>) {
     (valid_tokens, errors)
 }
 
-fn get_position(lexer: &Lexer<Token>, byte_offset: usize) -> (usize, usize) {
+fn get_position(lexer: &Lexer<Token>, byte_offset: usize, value: &str) -> (usize, usize) {
+    // A block comment has already moved the lexer past its own newlines
+    let newlines = value.matches('\n').count();
+    if newlines > 0 {
+        let line_start = lexer.source()[..byte_offset].rfind('\n').map_or(0, |i| i + 1);
+        return (lexer.extras.line_number - newlines, byte_offset - line_start + 1);
+    }
     let line = lexer.extras.line_number;
     let col = byte_offset - lexer.extras.line_start + 1;
     (line, col)
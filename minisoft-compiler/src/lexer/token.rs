@@ -22,6 +22,8 @@ pub enum Token {
     Int,
     #[token("Float")]
     Float,
+    #[token("String")]
+    String,
 
     // Control flow
     #[token("if")]
@@ -53,6 +55,14 @@ pub enum Token {
     #[token("Const")]
     Const,
 
+    // Subprograms
+    #[token("Function")]
+    Function,
+    #[token("Procedure")]
+    Procedure,
+    #[token("return")]
+    Return,
+
     // Punctuation and symbols
     #[token(";")]
     Semicolon,
@@ -125,9 +135,9 @@ pub enum Token {
     #[regex("\"[^\"]*\"", parse_string_literal)]
     StringLiteral(String),
 
-    // Ignored tokens
-    #[regex("<\\!-([^-\n]|(-[^!\n]))*-\\!>", logos::skip)]
-    #[regex("\\{--([^-]|(-[^-]))*--\\}", logos::skip)]
+    // Comments, kept out of the token stream by `tokenize`
+    #[regex("<\\!-([^-\n]|(-[^!\n]))*-\\!>")]
+    #[regex("\\{--([^-]|(-[^-]))*--\\}", block_comment_callback)]
     Comment,
 
     Error,
@@ -202,3 +212,11 @@ fn newline_callback(lex: &mut logos::Lexer<Token>) -> logos::Skip {
     lex.extras.line_start = lex.span().end;
     logos::Skip
 }
+
+/// Block comments may span several lines, which the lexer has to count as well
+fn block_comment_callback(lex: &mut logos::Lexer<Token>) {
+    if let Some(last_newline) = lex.slice().rfind('\n') {
+        lex.extras.line_number += lex.slice().matches('\n').count();
+        lex.extras.line_start = lex.span().start + last_newline + 1;
+    }
+}
@@ -1,14 +1,25 @@
 pub mod codegen;
 pub mod compiler;
+pub mod dot;
 pub mod error_reporter;
 pub mod lexer;
+pub mod lint;
+pub mod optimizer;
 pub mod parser;
+pub mod repl;
+pub mod runtime;
 pub mod semantics;
 
+pub use compiler::CompilerOptions;
+pub use parser::formatter::FormatOptions;
+
 use parser::ast::{
-    Declaration, DeclarationKind, Expression, ExpressionKind, Literal, LiteralKind, Program,
-    Statement, StatementKind,
+    Declaration, DeclarationKind, Expression, ExpressionKind, Literal, LiteralKind, Located,
+    Parameter, Program, Statement, StatementKind, Subprogram,
 };
+use error_reporter::Severity;
+use lint::LintWarning;
+use optimizer::{OptimizationLevel, QuadrupleChange};
 use semantics::error::SemanticError;
 use serde::{Deserialize, Serialize};
 
@@ -19,7 +30,17 @@ pub struct SerializableCompilationResult {
     pub ast: SerializableProgram,
     pub symbol_table: Vec<SerializableSymbol>,
     pub quadruples: SerializableQuadrupleProgram,
+    /// Quadruples after optimization, for a before/after comparison
+    pub optimized_quadruples: SerializableQuadrupleProgram,
+    pub optimization_report: Vec<SerializablePassReport>,
+    pub optimization_level: OptimizationLevel,
+    pub control_flow_graph: SerializableControlFlowGraph,
+    pub dataflow: SerializableDataflow,
+    pub dot: SerializableDotGraphs,
     pub errors: Option<SerializableCompilationErrors>,
+    /// Diagnostics reported even when compilation succeeds
+    pub warnings: Vec<SerializableSemanticError>,
+    pub lints: Vec<SerializableLintWarning>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -34,6 +55,8 @@ pub struct SerializableToken {
 #[derive(Serialize, Deserialize)]
 pub struct SerializableSymbol {
     pub name: String,
+    /// Path of the declaring scope: `global`, a subprogram name, then block numbers
+    pub scope: String,
     pub kind: String,
     pub symbol_type: String,
     pub value: String,
@@ -46,9 +69,31 @@ pub struct SerializableSymbol {
 pub struct SerializableProgram {
     pub name: String,
     pub declarations: Vec<SerializableDeclaration>,
+    pub subprograms: Vec<SerializableSubprogram>,
     pub statements: Vec<SerializableStatement>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SerializableSubprogramKind {
+    pub name: String,
+    pub parameters: Vec<SerializableParameter>,
+    /// `None` for a procedure
+    pub return_type: Option<String>,
+    pub declarations: Vec<SerializableDeclaration>,
+    pub statements: Vec<SerializableStatement>,
+}
+
+pub type SerializableSubprogram = SerializableLocated<SerializableSubprogramKind>;
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableParameterKind {
+    pub name: String,
+    pub type_name: String,
+    pub size: Option<usize>,
+}
+
+pub type SerializableParameter = SerializableLocated<SerializableParameterKind>;
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableLocated<T> {
     pub node: T,
@@ -108,6 +153,10 @@ pub enum SerializableStatementKind {
         body: Vec<SerializableStatement>,
         condition: SerializableExpression,
     },
+    While {
+        condition: SerializableExpression,
+        body: Vec<SerializableStatement>,
+    },
     For {
         var: SerializableExpression,
         from: SerializableExpression,
@@ -122,8 +171,16 @@ pub enum SerializableStatementKind {
         values: Vec<SerializableExpression>,
     },
     Scope {
+        declarations: Vec<SerializableDeclaration>,
         statements: Vec<SerializableStatement>,
     },
+    Call {
+        name: String,
+        arguments: Vec<SerializableExpression>,
+    },
+    Return {
+        value: Option<SerializableExpression>,
+    },
     Empty,
 }
 
@@ -151,6 +208,10 @@ pub enum SerializableExpressionKind {
         operator: String,
         operand: Box<SerializableExpression>,
     },
+    Call {
+        name: String,
+        arguments: Vec<SerializableExpression>,
+    },
 }
 
 pub type SerializableExpression = SerializableLocated<SerializableExpressionKind>;
@@ -171,6 +232,8 @@ pub enum SerializableOperation {
     Subtract,
     Multiply,
     Divide,
+    Concat,
+    Length,
     Assign,
     ArrayStore,
     ArrayLoad,
@@ -178,6 +241,7 @@ pub enum SerializableOperation {
     Jump { target: usize },
     JumpIfTrue { target: usize },
     JumpIfFalse { target: usize },
+    CheckStep,
     Equal,
     NotEqual,
     LessThan,
@@ -189,8 +253,10 @@ pub enum SerializableOperation {
     Not,
     Input,
     Output,
+    Param,
     Call { name: String },
     Return,
+    Entry { name: String },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -224,15 +290,94 @@ pub struct SerializableQuadruple {
     pub operand1: SerializableOperand,
     pub operand2: SerializableOperand,
     pub result: SerializableOperand,
+    pub span: Option<(usize, usize)>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct SerializableQuadrupleProgram {
     pub quadruples: Vec<SerializableQuadruple>,
     pub next_temp: usize,
     pub next_label: usize,
 }
 
+#[derive(Serialize, Deserialize, Default)]
+pub struct SerializableBasicBlock {
+    pub id: usize,
+    pub start: usize,
+    pub quadruples: Vec<SerializableQuadruple>,
+    pub successors: Vec<usize>,
+    pub predecessors: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SerializableControlFlowGraph {
+    pub blocks: Vec<SerializableBasicBlock>,
+    pub reverse_post_order: Vec<usize>,
+}
+
+/// Dataflow facts around every generated quadruple, in program order
+#[derive(Serialize, Deserialize, Default)]
+pub struct SerializableDataflow {
+    pub points: Vec<SerializableProgramPoint>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableProgramPoint {
+    pub live_in: Vec<String>,
+    pub live_out: Vec<String>,
+    /// Indices of the quadruples whose definitions may reach this one
+    pub reaching_definitions: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializablePassReport {
+    pub pass: String,
+    pub changes: usize,
+    /// Per-rule breakdown of the changes, empty for single-rule passes
+    pub rule_hits: Vec<SerializableRuleHits>,
+    /// The program as the pass left it
+    pub quadruples: SerializableQuadrupleProgram,
+    /// What the pass kept, removed and added, by quadruple index, when requested
+    pub diff: Option<Vec<SerializableQuadrupleChange>>,
+}
+
+/// One line of a pass diff, `before` and `after` index the programs before and after the pass
+#[derive(Serialize, Deserialize)]
+pub struct SerializableQuadrupleChange {
+    pub kind: String,
+    pub before: Option<usize>,
+    pub after: Option<usize>,
+}
+
+impl From<QuadrupleChange> for SerializableQuadrupleChange {
+    fn from(change: QuadrupleChange) -> Self {
+        let (kind, before, after) = match change {
+            QuadrupleChange::Kept { before, after } => ("Kept", Some(before), Some(after)),
+            QuadrupleChange::Removed { before } => ("Removed", Some(before), None),
+            QuadrupleChange::Added { after } => ("Added", None, Some(after)),
+        };
+        SerializableQuadrupleChange {
+            kind: kind.to_string(),
+            before,
+            after,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableRuleHits {
+    pub rule: String,
+    pub hits: usize,
+}
+
+/// Graphviz sources for the compiler's intermediate structures, empty when unavailable
+#[derive(Serialize, Deserialize, Default)]
+pub struct SerializableDotGraphs {
+    pub ast: String,
+    pub control_flow_graph: String,
+    pub symbol_table: String,
+}
+
 // Serializable error types
 #[derive(Serialize, Deserialize)]
 pub struct SerializableErrorPosition {
@@ -315,6 +460,11 @@ pub enum SerializableSemanticError {
         position: SerializableErrorPosition,
         original_position: SerializableErrorPosition,
     },
+    ShadowedDeclaration {
+        name: String,
+        position: SerializableErrorPosition,
+        original_position: SerializableErrorPosition,
+    },
     TypeMismatch {
         expected: String,
         found: String,
@@ -342,12 +492,85 @@ pub enum SerializableSemanticError {
         var_name: String,
         position: SerializableErrorPosition,
     },
+    ZeroLoopStep {
+        position: SerializableErrorPosition,
+    },
     InvalidArraySize {
         name: String,
         size: i32,
         position: SerializableErrorPosition,
     },
     EmptyProgram,
+    InfiniteLoop {
+        constant: bool,
+        position: SerializableErrorPosition,
+    },
+    UnreachableCode {
+        context: String,
+        position: SerializableErrorPosition,
+    },
+    UseBeforeInitialization {
+        name: String,
+        index: Option<String>,
+        position: SerializableErrorPosition,
+    },
+    IndexRangeOutOfBounds {
+        name: String,
+        range: String,
+        size: usize,
+        definite: bool,
+        position: SerializableErrorPosition,
+    },
+    UndefinedSubprogram {
+        name: String,
+        position: SerializableErrorPosition,
+    },
+    ArgumentCountMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+        position: SerializableErrorPosition,
+    },
+    ProcedureValueUsed {
+        name: String,
+        position: SerializableErrorPosition,
+    },
+    MissingReturn {
+        name: String,
+        position: SerializableErrorPosition,
+    },
+    InvalidReturn {
+        message: String,
+        position: SerializableErrorPosition,
+    },
+    BuiltinRedefined {
+        name: String,
+        position: SerializableErrorPosition,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableLintWarning {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: Option<String>,
+    pub position: SerializableErrorPosition,
+}
+
+impl From<LintWarning> for SerializableLintWarning {
+    fn from(warning: LintWarning) -> Self {
+        SerializableLintWarning {
+            rule: warning.rule.to_string(),
+            severity: warning.severity,
+            message: warning.message,
+            suggestion: warning.suggestion,
+            position: SerializableErrorPosition {
+                line: warning.line,
+                column: warning.column,
+            },
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -357,9 +580,146 @@ pub struct SerializableCompilationErrors {
     pub semantic_errors: Vec<SerializableSemanticError>,
 }
 
-pub fn run_compiler(code: String, verbose: bool) -> Result<SerializableCompilationResult, String> {
+// Serializable result of running a compiled program
+#[derive(Serialize, Deserialize)]
+pub struct SerializableExecutionResult {
+    pub output: Vec<String>,
+    pub memory: Vec<SerializableMemoryEntry>,
+    pub steps: usize,
+    pub runtime_error: Option<SerializableRuntimeError>,
+    pub compilation_errors: Option<SerializableCompilationErrors>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableRuntimeError {
+    pub error_type: String,
+    pub position: SerializableErrorPosition,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+// Serializable state of a debugging session after each stop
+#[derive(Serialize, Deserialize)]
+pub struct SerializableDebugState {
+    pub reason: String,
+    pub breakpoint_line: Option<usize>,
+    pub pc: usize,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub quadruple: Option<String>,
+    pub memory: Vec<SerializableMemoryEntry>,
+    pub temps: Vec<SerializableMemoryEntry>,
+    pub output: Vec<String>,
+    pub runtime_error: Option<SerializableRuntimeError>,
+    pub finished: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SerializableMemoryEntry {
+    pub name: String,
+    pub kind: String,
+    pub values: Vec<String>,
+}
+
+pub fn run_compiler(
+    code: String,
+    verbose: bool,
+    options: CompilerOptions,
+) -> Result<SerializableCompilationResult, String> {
     // Simply delegate to the compiler module's compile_code function
-    crate::compiler::compile_code(code, verbose)
+    crate::compiler::compile_code(code, verbose, &options)
+}
+
+pub fn export_dot(code: String) -> Result<SerializableDotGraphs, String> {
+    crate::compiler::compile_code(code, false, &CompilerOptions::check_only()).map(|result| result.dot)
+}
+
+/// Source of the program in canonical layout, its comments kept. Fails on the first
+/// lexical or syntax error, since the layout comes from the syntax tree.
+pub fn format_code(code: String, options: FormatOptions) -> Result<String, String> {
+    let (tokens, lexical_errors) = lexer::lexer_core::tokenize_with_comments(&code);
+    if let Some(error) = lexical_errors.first() {
+        return Err(error.to_string());
+    }
+    let code_tokens = tokens
+        .iter()
+        .filter(|token| token.kind != lexer::token::Token::Comment)
+        .cloned()
+        .collect();
+    let program = parser::parser_core::parse(code_tokens, &code).map_err(|e| e.to_string())?;
+    Ok(parser::formatter::format_program_with(&program, &tokens, &options))
+}
+
+pub fn run_program(code: String, inputs: Vec<String>) -> Result<SerializableExecutionResult, String> {
+    let mut input = runtime::BufferedInput::new(inputs);
+    crate::compiler::execute_code(code, &mut input, OptimizationLevel::O0)
+}
+
+pub fn start_debugger(
+    code: String,
+    inputs: Vec<String>,
+    breakpoints: Vec<usize>,
+) -> Result<runtime::Debugger, String> {
+    let mut debugger = crate::compiler::create_debugger(code, inputs)?;
+    for line in breakpoints {
+        debugger.set_breakpoint(line);
+    }
+    Ok(debugger)
+}
+
+pub fn debug_state(
+    debugger: &runtime::Debugger,
+    reason: runtime::StopReason,
+) -> SerializableDebugState {
+    let snapshot = debugger.inspect();
+    let (reason_name, breakpoint_line, runtime_error) = match reason {
+        runtime::StopReason::Breakpoint(line) => ("Breakpoint", Some(line), None),
+        runtime::StopReason::Step => ("Step", None, None),
+        runtime::StopReason::Halted => ("Halted", None, None),
+        runtime::StopReason::Error(err) => ("Error", None, Some(err.into())),
+    };
+
+    let mut memory: Vec<SerializableMemoryEntry> = snapshot
+        .variables
+        .into_iter()
+        .map(|(name, value)| SerializableMemoryEntry {
+            name,
+            kind: "Variable".to_string(),
+            values: vec![value.to_string()],
+        })
+        .collect();
+    memory.extend(
+        snapshot
+            .arrays
+            .into_iter()
+            .map(|(name, values)| SerializableMemoryEntry {
+                name,
+                kind: "Array".to_string(),
+                values: values.iter().map(ToString::to_string).collect(),
+            }),
+    );
+
+    SerializableDebugState {
+        reason: reason_name.to_string(),
+        breakpoint_line,
+        pc: snapshot.pc,
+        line: snapshot.line,
+        column: snapshot.column,
+        quadruple: snapshot.quadruple.map(|q| q.to_string()),
+        memory,
+        temps: snapshot
+            .temps
+            .into_iter()
+            .map(|(name, value)| SerializableMemoryEntry {
+                name,
+                kind: "Temp".to_string(),
+                values: vec![value.to_string()],
+            })
+            .collect(),
+        output: debugger.output().clone().into_lines(),
+        runtime_error,
+        finished: debugger.is_finished(),
+    }
 }
 
 // Helper function to convert lexical error types
@@ -509,6 +869,23 @@ fn convert_semantic_error(
                 column: *original_column,
             },
         },
+        SemanticError::ShadowedDeclaration {
+            name,
+            line,
+            column,
+            original_line,
+            original_column,
+        } => SerializableSemanticError::ShadowedDeclaration {
+            name: name.clone(),
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+            original_position: SerializableErrorPosition {
+                line: *original_line,
+                column: *original_column,
+            },
+        },
         SemanticError::TypeMismatch {
             expected,
             found,
@@ -578,6 +955,12 @@ fn convert_semantic_error(
                 column: *column,
             },
         },
+        SemanticError::ZeroLoopStep { line, column } => SerializableSemanticError::ZeroLoopStep {
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
         SemanticError::InvalidArraySize {
             name,
             size,
@@ -592,6 +975,120 @@ fn convert_semantic_error(
             },
         },
         SemanticError::EmptyProgram => SerializableSemanticError::EmptyProgram,
+        SemanticError::InfiniteLoop {
+            constant,
+            line,
+            column,
+        } => SerializableSemanticError::InfiniteLoop {
+            constant: *constant,
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
+        SemanticError::UnreachableCode {
+            context,
+            line,
+            column,
+        } => SerializableSemanticError::UnreachableCode {
+            context: context.clone(),
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
+        SemanticError::UseBeforeInitialization {
+            name,
+            index,
+            line,
+            column,
+        } => SerializableSemanticError::UseBeforeInitialization {
+            name: name.clone(),
+            index: index.clone(),
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
+        SemanticError::IndexRangeOutOfBounds {
+            name,
+            range,
+            size,
+            definite,
+            line,
+            column,
+        } => SerializableSemanticError::IndexRangeOutOfBounds {
+            name: name.clone(),
+            range: range.clone(),
+            size: *size,
+            definite: *definite,
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
+        SemanticError::UndefinedSubprogram { name, line, column } => {
+            SerializableSemanticError::UndefinedSubprogram {
+                name: name.clone(),
+                position: SerializableErrorPosition {
+                    line: *line,
+                    column: *column,
+                },
+            }
+        }
+        SemanticError::ArgumentCountMismatch {
+            name,
+            expected,
+            found,
+            line,
+            column,
+        } => SerializableSemanticError::ArgumentCountMismatch {
+            name: name.clone(),
+            expected: *expected,
+            found: *found,
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
+        SemanticError::ProcedureValueUsed { name, line, column } => {
+            SerializableSemanticError::ProcedureValueUsed {
+                name: name.clone(),
+                position: SerializableErrorPosition {
+                    line: *line,
+                    column: *column,
+                },
+            }
+        }
+        SemanticError::MissingReturn { name, line, column } => {
+            SerializableSemanticError::MissingReturn {
+                name: name.clone(),
+                position: SerializableErrorPosition {
+                    line: *line,
+                    column: *column,
+                },
+            }
+        }
+        SemanticError::InvalidReturn {
+            message,
+            line,
+            column,
+        } => SerializableSemanticError::InvalidReturn {
+            message: message.clone(),
+            position: SerializableErrorPosition {
+                line: *line,
+                column: *column,
+            },
+        },
+        SemanticError::BuiltinRedefined { name, line, column } => {
+            SerializableSemanticError::BuiltinRedefined {
+                name: name.clone(),
+                position: SerializableErrorPosition {
+                    line: *line,
+                    column: *column,
+                },
+            }
+        }
     }
 }
 
@@ -600,11 +1097,43 @@ impl From<Program> for SerializableProgram {
         SerializableProgram {
             name: program.name,
             declarations: program.declarations.into_iter().map(Into::into).collect(),
+            subprograms: program.subprograms.into_iter().map(Into::into).collect(),
             statements: program.statements.into_iter().map(Into::into).collect(),
         }
     }
 }
 
+impl From<Located<Subprogram>> for SerializableSubprogram {
+    fn from(subprogram: Located<Subprogram>) -> Self {
+        let node = subprogram.node;
+        SerializableLocated {
+            node: SerializableSubprogramKind {
+                name: node.name,
+                parameters: node.parameters.into_iter().map(Into::into).collect(),
+                return_type: node.return_type.map(|typ| typ.to_string()),
+                declarations: node.declarations.into_iter().map(Into::into).collect(),
+                statements: node.statements.into_iter().map(Into::into).collect(),
+            },
+            start: subprogram.span.start,
+            end: subprogram.span.end,
+        }
+    }
+}
+
+impl From<Parameter> for SerializableParameter {
+    fn from(parameter: Parameter) -> Self {
+        SerializableLocated {
+            node: SerializableParameterKind {
+                name: parameter.node.name,
+                type_name: parameter.node.typ.to_string(),
+                size: parameter.node.size,
+            },
+            start: parameter.span.start,
+            end: parameter.span.end,
+        }
+    }
+}
+
 impl From<Declaration> for SerializableDeclaration {
     fn from(decl: Declaration) -> Self {
         SerializableLocated {
@@ -674,6 +1203,10 @@ impl From<Statement> for SerializableStatement {
                     body: body.into_iter().map(Into::into).collect(),
                     condition: condition.into(),
                 },
+                StatementKind::While(condition, body) => SerializableStatementKind::While {
+                    condition: condition.into(),
+                    body: body.into_iter().map(Into::into).collect(),
+                },
                 StatementKind::For(var, from, to, step, body) => SerializableStatementKind::For {
                     var: var.into(),
                     from: from.into(),
@@ -687,9 +1220,17 @@ impl From<Statement> for SerializableStatement {
                 StatementKind::Output(values) => SerializableStatementKind::Output {
                     values: values.into_iter().map(Into::into).collect(),
                 },
-                StatementKind::Scope(statements) => SerializableStatementKind::Scope {
+                StatementKind::Scope(declarations, statements) => SerializableStatementKind::Scope {
+                    declarations: declarations.into_iter().map(Into::into).collect(),
                     statements: statements.into_iter().map(Into::into).collect(),
                 },
+                StatementKind::Call(name, arguments) => SerializableStatementKind::Call {
+                    name,
+                    arguments: arguments.into_iter().map(Into::into).collect(),
+                },
+                StatementKind::Return(value) => SerializableStatementKind::Return {
+                    value: value.map(Into::into),
+                },
                 StatementKind::Empty => SerializableStatementKind::Empty,
             },
             start: stmt.span.start,
@@ -723,6 +1264,10 @@ impl From<Expression> for SerializableExpression {
                     operator: format!("{:?}", operator),
                     operand: Box::new((*operand).into()),
                 },
+                ExpressionKind::Call(name, arguments) => SerializableExpressionKind::Call {
+                    name,
+                    arguments: arguments.into_iter().map(Into::into).collect(),
+                },
             },
             start: expr.span.start,
             end: expr.span.end,
@@ -747,6 +1292,8 @@ impl From<codegen::Operation> for SerializableOperation {
             codegen::Operation::Subtract => SerializableOperation::Subtract,
             codegen::Operation::Multiply => SerializableOperation::Multiply,
             codegen::Operation::Divide => SerializableOperation::Divide,
+            codegen::Operation::Concat => SerializableOperation::Concat,
+            codegen::Operation::Length => SerializableOperation::Length,
             codegen::Operation::Assign => SerializableOperation::Assign,
             codegen::Operation::ArrayStore => SerializableOperation::ArrayStore,
             codegen::Operation::ArrayLoad => SerializableOperation::ArrayLoad,
@@ -756,6 +1303,7 @@ impl From<codegen::Operation> for SerializableOperation {
             codegen::Operation::JumpIfFalse(target) => {
                 SerializableOperation::JumpIfFalse { target }
             }
+            codegen::Operation::CheckStep => SerializableOperation::CheckStep,
             codegen::Operation::Equal => SerializableOperation::Equal,
             codegen::Operation::NotEqual => SerializableOperation::NotEqual,
             codegen::Operation::LessThan => SerializableOperation::LessThan,
@@ -767,8 +1315,10 @@ impl From<codegen::Operation> for SerializableOperation {
             codegen::Operation::Not => SerializableOperation::Not,
             codegen::Operation::Input => SerializableOperation::Input,
             codegen::Operation::Output => SerializableOperation::Output,
+            codegen::Operation::Param => SerializableOperation::Param,
             codegen::Operation::Call(name) => SerializableOperation::Call { name },
             codegen::Operation::Return => SerializableOperation::Return,
+            codegen::Operation::Entry(name) => SerializableOperation::Entry { name },
         }
     }
 }
@@ -797,6 +1347,7 @@ impl From<codegen::Quadruple> for SerializableQuadruple {
             operand1: q.operand1.into(),
             operand2: q.operand2.into(),
             result: q.result.into(),
+            span: q.span.map(|span| (span.start, span.end)),
         }
     }
 }
@@ -810,3 +1361,87 @@ impl From<codegen::QuadrupleProgram> for SerializableQuadrupleProgram {
         }
     }
 }
+
+impl From<codegen::ControlFlowGraph> for SerializableControlFlowGraph {
+    fn from(cfg: codegen::ControlFlowGraph) -> Self {
+        let reverse_post_order = cfg.reverse_post_order();
+        SerializableControlFlowGraph {
+            blocks: cfg
+                .blocks
+                .into_iter()
+                .map(|block| SerializableBasicBlock {
+                    id: block.id,
+                    start: block.start,
+                    quadruples: block.quadruples.into_iter().map(Into::into).collect(),
+                    successors: block.successors,
+                    predecessors: block.predecessors,
+                })
+                .collect(),
+            reverse_post_order,
+        }
+    }
+}
+
+impl From<&codegen::ControlFlowGraph> for SerializableDataflow {
+    fn from(cfg: &codegen::ControlFlowGraph) -> Self {
+        let liveness = optimizer::solve(cfg, &optimizer::Liveness);
+        let reaching = optimizer::solve(cfg, &optimizer::ReachingDefinitions::new(cfg));
+        let names = |slots: &std::collections::BTreeSet<optimizer::Slot>| {
+            slots.iter().map(ToString::to_string).collect()
+        };
+        SerializableDataflow {
+            points: (0..liveness.before.len())
+                .map(|index| SerializableProgramPoint {
+                    live_in: names(liveness.before(index)),
+                    live_out: names(liveness.after(index)),
+                    reaching_definitions: reaching.before(index).iter().copied().collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<runtime::RuntimeError> for SerializableRuntimeError {
+    fn from(err: runtime::RuntimeError) -> Self {
+        use error_reporter::ErrorReporter;
+
+        let (line, column) = err.get_location_info();
+        let debug = format!("{:?}", err);
+        SerializableRuntimeError {
+            error_type: debug
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            position: SerializableErrorPosition { line, column },
+            message: err.get_detailed_message(),
+            suggestion: err.get_suggestion(),
+        }
+    }
+}
+
+impl From<runtime::Memory> for Vec<SerializableMemoryEntry> {
+    fn from(memory: runtime::Memory) -> Self {
+        let mut entries: Vec<SerializableMemoryEntry> = memory
+            .variables
+            .into_iter()
+            .map(|(name, value)| SerializableMemoryEntry {
+                name,
+                kind: "Variable".to_string(),
+                values: vec![value.to_string()],
+            })
+            .chain(
+                memory
+                    .arrays
+                    .into_iter()
+                    .map(|(name, values)| SerializableMemoryEntry {
+                        name,
+                        kind: "Array".to_string(),
+                        values: values.iter().map(ToString::to_string).collect(),
+                    }),
+            )
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+}
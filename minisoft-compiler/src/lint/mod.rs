@@ -0,0 +1,438 @@
+mod rules;
+
+use crate::error_reporter::{ErrorReporter, Severity, format_code_context};
+use crate::parser::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, Program, Statement, StatementKind,
+};
+use crate::semantics::source_map::SourceMap;
+use crate::semantics::symbol_table::{ScopeNames, Symbol, SymbolTable};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+/// A check on a program that already passed semantic analysis
+pub struct LintRule {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub severity: Severity,
+    check: fn(&LintContext) -> Vec<Finding>,
+}
+
+/// Every lint the linter knows, in the order they run
+pub const RULES: &[LintRule] = &[
+    LintRule {
+        name: "unused-variable",
+        description: "Variables and arrays that are declared but never used",
+        severity: Severity::Warning,
+        check: rules::unused_variable,
+    },
+    LintRule {
+        name: "unused-constant",
+        description: "Constants that are declared but never read",
+        severity: Severity::Note,
+        check: rules::unused_constant,
+    },
+    LintRule {
+        name: "assigned-never-read",
+        description: "Variables and arrays that are written but never read",
+        severity: Severity::Warning,
+        check: rules::assigned_never_read,
+    },
+    LintRule {
+        name: "shadowed-iterator",
+        description: "For loops reusing the iterator of an enclosing for loop",
+        severity: Severity::Warning,
+        check: rules::shadowed_iterator,
+    },
+    LintRule {
+        name: "comparison-always-true",
+        description: "Comparisons whose result is known to be true at compile time",
+        severity: Severity::Warning,
+        check: rules::comparison_always_true,
+    },
+];
+
+/// A lint reported on the program
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+/// What a rule found, before the linter tags it with the rule
+struct Finding {
+    line: usize,
+    column: usize,
+    length: usize,
+    message: String,
+    suggestion: Option<String>,
+}
+
+/// Reads and writes of a name in the statements of the program
+#[derive(Default)]
+struct Usage {
+    reads: usize,
+    writes: Vec<Range<usize>>,
+}
+
+/// Everything the rules look at, computed once per program
+struct LintContext<'a> {
+    program: &'a Program,
+    symbol_table: &'a SymbolTable,
+    source: &'a str,
+    source_map: SourceMap,
+    usage: HashMap<String, Usage>,
+    /// Names in scope where usage is being counted, locals are known by their qualified names
+    names: ScopeNames,
+}
+
+/// Runs the enabled lint rules on a program
+pub struct Linter {
+    rules: Vec<&'static LintRule>,
+}
+
+impl Linter {
+    /// A linter running every rule
+    pub fn new() -> Self {
+        Linter {
+            rules: RULES.iter().collect(),
+        }
+    }
+
+    /// A linter running every rule except the named ones, unknown names are ignored
+    pub fn without_rules<S: AsRef<str>>(disabled: &[S]) -> Self {
+        Linter {
+            rules: RULES
+                .iter()
+                .filter(|rule| !disabled.iter().any(|name| name.as_ref() == rule.name))
+                .collect(),
+        }
+    }
+
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|rule| rule.name).collect()
+    }
+
+    /// Lints sorted by position in the source
+    pub fn run(
+        &self,
+        program: &Program,
+        symbol_table: &SymbolTable,
+        source: &str,
+    ) -> Vec<LintWarning> {
+        let context = LintContext::new(program, symbol_table, source);
+        let mut warnings: Vec<LintWarning> = self
+            .rules
+            .iter()
+            .flat_map(|rule| {
+                (rule.check)(&context)
+                    .into_iter()
+                    .map(|finding| LintWarning {
+                        rule: rule.name,
+                        severity: rule.severity,
+                        message: finding.message,
+                        suggestion: finding.suggestion,
+                        line: finding.line,
+                        column: finding.column,
+                        length: finding.length,
+                    })
+            })
+            .collect();
+        warnings.sort_by_key(|warning| (warning.line, warning.column));
+        warnings
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LintContext<'a> {
+    fn new(program: &'a Program, symbol_table: &'a SymbolTable, source: &'a str) -> Self {
+        let mut context = LintContext {
+            program,
+            symbol_table,
+            source,
+            source_map: SourceMap::new(&source.to_string()),
+            usage: HashMap::new(),
+            names: ScopeNames::new(),
+        };
+        context.count_body(&program.declarations, &program.statements);
+        for subprogram in &program.subprograms {
+            context.names.enter_subprogram(&subprogram.node);
+            context.count_body(&subprogram.node.declarations, &subprogram.node.statements);
+            context.names.exit();
+        }
+        context
+    }
+
+    fn count_body(&mut self, declarations: &[Declaration], statements: &[Statement]) {
+        for declaration in declarations {
+            match &declaration.node {
+                DeclarationKind::VariableWithInit(_, _, init) => self.count_reads(init),
+                DeclarationKind::ArrayWithInit(_, _, _, values) => {
+                    values.iter().for_each(|value| self.count_reads(value))
+                }
+                _ => {}
+            }
+            self.names.declare(declaration);
+        }
+        self.count_statements(statements);
+    }
+
+    /// Counts nested statements too, the declarations of a block only count inside it
+    fn count_statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.count_statement(statement);
+            match &statement.node {
+                StatementKind::Scope(declarations, body) => {
+                    self.names.enter_block();
+                    self.count_body(declarations, body);
+                    self.names.exit();
+                }
+                StatementKind::IfThen(_, body)
+                | StatementKind::DoWhile(body, _)
+                | StatementKind::While(_, body)
+                | StatementKind::For(.., body) => self.count_statements(body),
+                StatementKind::IfThenElse(_, then_block, else_block) => {
+                    self.count_statements(then_block);
+                    self.count_statements(else_block);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Statements of the main program and of every subprogram
+    fn bodies(&self) -> impl Iterator<Item = &'a [Statement]> {
+        std::iter::once(self.program.statements.as_slice()).chain(
+            self.program
+                .subprograms
+                .iter()
+                .map(|subprogram| subprogram.node.statements.as_slice()),
+        )
+    }
+
+    /// Name the symbol table knows a name by where it is counted
+    fn qualified(&self, name: &str) -> String {
+        self.names.resolve(name)
+    }
+
+    fn count_statement(&mut self, statement: &Statement) {
+        match &statement.node {
+            StatementKind::Assignment(target, value) => {
+                self.count_reads(value);
+                self.count_write(target);
+            }
+            StatementKind::Input(target) => self.count_write(target),
+            StatementKind::For(iterator, ..) => self.count_write(iterator),
+            _ => {}
+        }
+        for expression in read_expressions(statement) {
+            self.count_reads(expression);
+        }
+    }
+
+    fn count_write(&mut self, target: &Expression) {
+        match &target.node {
+            ExpressionKind::Identifier(name) => self.record_write(name, &target.span),
+            ExpressionKind::ArrayAccess(name, index) => {
+                self.count_reads(index);
+                self.record_write(name, &target.span);
+            }
+            _ => {}
+        }
+    }
+
+    fn record_write(&mut self, name: &str, span: &Range<usize>) {
+        let usage = self.usage.entry(self.qualified(name)).or_default();
+        usage.writes.push(span.clone());
+    }
+
+    fn count_reads(&mut self, expression: &Expression) {
+        let mut names = Vec::new();
+        walk_expression(expression, &mut |expression| match &expression.node {
+            ExpressionKind::Identifier(name) | ExpressionKind::ArrayAccess(name, _) => {
+                names.push(self.qualified(name));
+            }
+            _ => {}
+        });
+        for name in names {
+            self.usage.entry(name).or_default().reads += 1;
+        }
+    }
+
+    fn usage(&self, name: &str) -> Option<&Usage> {
+        self.usage.get(name)
+    }
+
+    /// Symbols in declaration order, so rules report them in a stable order
+    fn symbols(&self) -> Vec<&'a Symbol> {
+        let mut symbols = self.symbol_table.get_all();
+        symbols.sort_by_key(|symbol| (symbol.line, symbol.column, symbol.name.clone()));
+        symbols
+    }
+
+    /// Finding pointing at a span of the source
+    fn at_span(&self, span: &Range<usize>, message: String, suggestion: Option<String>) -> Finding {
+        Finding {
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+            length: span.len(),
+            message,
+            suggestion,
+        }
+    }
+
+    /// Finding pointing at the name of a symbol in its declaration
+    fn at_declaration(
+        &self,
+        symbol: &Symbol,
+        message: String,
+        suggestion: Option<String>,
+    ) -> Finding {
+        let column = self
+            .source
+            .lines()
+            .nth(symbol.line.saturating_sub(1))
+            .and_then(|line| {
+                let start = symbol.column.saturating_sub(1).min(line.len());
+                find_word(&line[start..], &symbol.name).map(|offset| start + offset + 1)
+            })
+            .unwrap_or(symbol.column);
+        Finding {
+            line: symbol.line,
+            column,
+            length: symbol.name.len(),
+            message,
+            suggestion,
+        }
+    }
+}
+
+/// Byte offset of `word` in `text` where it isn't part of a longer identifier
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    let is_identifier = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    text.match_indices(word)
+        .map(|(offset, _)| offset)
+        .find(|&offset| {
+            let before = text[..offset].chars().next_back();
+            let after = text[offset + word.len()..].chars().next();
+            !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier)
+        })
+}
+
+/// Calls `visit` on every statement, nested ones after the statement holding them
+fn walk_statements<'s>(statements: &'s [Statement], visit: &mut impl FnMut(&'s Statement)) {
+    for statement in statements {
+        visit(statement);
+        match &statement.node {
+            StatementKind::IfThen(_, body)
+            | StatementKind::DoWhile(body, _)
+            | StatementKind::While(_, body)
+            | StatementKind::For(.., body)
+            | StatementKind::Scope(_, body) => walk_statements(body, visit),
+            StatementKind::IfThenElse(_, then_block, else_block) => {
+                walk_statements(then_block, visit);
+                walk_statements(else_block, visit);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `visit` on an expression and all its subexpressions
+fn walk_expression<'e>(expression: &'e Expression, visit: &mut impl FnMut(&'e Expression)) {
+    visit(expression);
+    match &expression.node {
+        ExpressionKind::ArrayAccess(_, index) => walk_expression(index, visit),
+        ExpressionKind::BinaryOp(left, _, right) => {
+            walk_expression(left, visit);
+            walk_expression(right, visit);
+        }
+        ExpressionKind::UnaryOp(_, operand) => walk_expression(operand, visit),
+        ExpressionKind::Call(_, arguments) => {
+            for argument in arguments {
+                walk_expression(argument, visit);
+            }
+        }
+        ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => {}
+    }
+}
+
+/// Expressions a statement reads itself, without those of nested statements and targets
+fn read_expressions(statement: &Statement) -> Vec<&Expression> {
+    match &statement.node {
+        StatementKind::IfThen(condition, _)
+        | StatementKind::IfThenElse(condition, ..)
+        | StatementKind::DoWhile(_, condition)
+        | StatementKind::While(condition, _) => vec![condition],
+        StatementKind::For(_, init, end, step, _) => vec![init, end, step],
+        StatementKind::Output(expressions) | StatementKind::Call(_, expressions) => {
+            expressions.iter().collect()
+        }
+        StatementKind::Return(value) => value.iter().collect(),
+        _ => vec![],
+    }
+}
+
+impl ErrorReporter for LintWarning {
+    fn report(&self, source_code: Option<&str>) -> String {
+        let mut result = String::new();
+
+        result.push_str(&format!(
+            "{}: {} [{}]\n",
+            self.severity.paint(&self.get_error_name()),
+            self.message,
+            self.rule
+        ));
+        result.push_str(&format!(
+            "{} line {}, column {}\n",
+            "-->".blue(),
+            self.line,
+            self.column
+        ));
+
+        if let Some(line) =
+            source_code.and_then(|source| source.lines().nth(self.line.checked_sub(1)?))
+        {
+            result.push_str(&format_code_context(line, self.column, self.length));
+        }
+
+        if let Some(suggestion) = &self.suggestion {
+            result.push_str(&format!("{}: {}\n", "Suggestion".cyan().bold(), suggestion));
+        }
+
+        result
+    }
+
+    fn get_suggestion(&self) -> Option<String> {
+        self.suggestion.clone()
+    }
+
+    fn get_error_name(&self) -> String {
+        format!("Lint {}", self.severity.title())
+    }
+
+    fn get_location_info(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.report(None))
+    }
+}
@@ -0,0 +1,236 @@
+use crate::lint::{Finding, LintContext, walk_expression, walk_statements};
+use crate::parser::ast::{
+    Expression, ExpressionKind, LiteralKind, Operator, Statement, StatementKind,
+};
+use crate::semantics::symbol_table::{SymbolKind, SymbolValue};
+
+fn describe(kind: &SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Array(_) => "Array",
+        SymbolKind::Constant => "Constant",
+        SymbolKind::Variable => "Variable",
+    }
+}
+
+/// Variables and arrays neither read nor written by any statement
+pub(super) fn unused_variable(context: &LintContext) -> Vec<Finding> {
+    context
+        .symbols()
+        .into_iter()
+        .filter(|symbol| !symbol.is_constant)
+        .filter(|symbol| {
+            context
+                .usage(&symbol.qualified_name())
+                .is_none_or(|usage| usage.reads == 0 && usage.writes.is_empty())
+        })
+        .map(|symbol| {
+            context.at_declaration(
+                symbol,
+                format!("{} '{}' is never used", describe(&symbol.kind), symbol.name),
+                Some(format!("Remove the declaration of '{}'", symbol.name)),
+            )
+        })
+        .collect()
+}
+
+pub(super) fn unused_constant(context: &LintContext) -> Vec<Finding> {
+    context
+        .symbols()
+        .into_iter()
+        .filter(|symbol| symbol.is_constant)
+        .filter(|symbol| {
+            context
+                .usage(&symbol.qualified_name())
+                .is_none_or(|usage| usage.reads == 0)
+        })
+        .map(|symbol| {
+            context.at_declaration(
+                symbol,
+                format!("Constant '{}' is never used", symbol.name),
+                Some(format!("Remove the declaration of '{}'", symbol.name)),
+            )
+        })
+        .collect()
+}
+
+/// Reported at the first write, the value stored there is never looked at again
+pub(super) fn assigned_never_read(context: &LintContext) -> Vec<Finding> {
+    context
+        .symbols()
+        .into_iter()
+        .filter(|symbol| !symbol.is_constant)
+        .filter_map(|symbol| {
+            let usage = context.usage(&symbol.qualified_name())?;
+            let first = usage.writes.first().filter(|_| usage.reads == 0)?;
+            Some(context.at_span(
+                first,
+                format!(
+                    "{} '{}' is assigned but its value is never read",
+                    describe(&symbol.kind),
+                    symbol.name
+                ),
+                Some(format!(
+                    "Remove the assignments to '{}' or use its value",
+                    symbol.name
+                )),
+            ))
+        })
+        .collect()
+}
+
+/// An inner loop counting with the iterator of an outer one changes where the outer loop is
+pub(super) fn shadowed_iterator(context: &LintContext) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for body in context.bodies() {
+        check_iterators(context, body, &mut Vec::new(), &mut findings);
+    }
+    findings
+}
+
+fn check_iterators(
+    context: &LintContext,
+    statements: &[Statement],
+    enclosing: &mut Vec<String>,
+    findings: &mut Vec<Finding>,
+) {
+    for statement in statements {
+        match &statement.node {
+            StatementKind::For(iterator, .., body) => {
+                let ExpressionKind::Identifier(name) = &iterator.node else {
+                    continue;
+                };
+                if enclosing.contains(name) {
+                    findings.push(context.at_span(
+                        &iterator.span,
+                        format!(
+                            "Iterator '{}' is already used by an enclosing for loop",
+                            name
+                        ),
+                        Some("Use a different variable for the inner loop".to_string()),
+                    ));
+                }
+                enclosing.push(name.clone());
+                check_iterators(context, body, enclosing, findings);
+                enclosing.pop();
+            }
+            StatementKind::IfThen(_, body)
+            | StatementKind::DoWhile(body, _)
+            | StatementKind::While(_, body)
+            | StatementKind::Scope(_, body) => check_iterators(context, body, enclosing, findings),
+            StatementKind::IfThenElse(_, then_block, else_block) => {
+                check_iterators(context, then_block, enclosing, findings);
+                check_iterators(context, else_block, enclosing, findings);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Comparisons of an operand with itself, or of values known at compile time
+pub(super) fn comparison_always_true(context: &LintContext) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut check = |expression: &Expression| {
+        walk_expression(expression, &mut |expression| {
+            if always_true(context, expression) {
+                findings.push(context.at_span(
+                    &expression.span,
+                    "This comparison is always true".to_string(),
+                    Some("Remove the comparison or compare with another value".to_string()),
+                ));
+            }
+        });
+    };
+    for body in context.bodies() {
+        walk_statements(body, &mut |statement| {
+            match &statement.node {
+                StatementKind::Assignment(target, value) => {
+                    check(target);
+                    check(value);
+                }
+                StatementKind::Input(target) => check(target),
+                _ => {}
+            }
+            for expression in super::read_expressions(statement) {
+                check(expression);
+            }
+        });
+    }
+    findings
+}
+
+fn always_true(context: &LintContext, expression: &Expression) -> bool {
+    let ExpressionKind::BinaryOp(left, operator, right) = &expression.node else {
+        return false;
+    };
+    let reflexive = matches!(
+        operator,
+        Operator::Equal | Operator::LessEqual | Operator::GreaterEqual
+    );
+    if reflexive && same_value(left, right) {
+        return true;
+    }
+    let (Some(left), Some(right)) = (
+        constant_value(context, left),
+        constant_value(context, right),
+    ) else {
+        return false;
+    };
+    match operator {
+        Operator::Equal => left == right,
+        Operator::NotEqual => left != right,
+        Operator::LessThan => left < right,
+        Operator::LessEqual => left <= right,
+        Operator::GreaterThan => left > right,
+        Operator::GreaterEqual => left >= right,
+        _ => false,
+    }
+}
+
+/// Whether two expressions read the same storage, which can't change in between
+fn same_value(left: &Expression, right: &Expression) -> bool {
+    match (&left.node, &right.node) {
+        (ExpressionKind::Identifier(left), ExpressionKind::Identifier(right)) => left == right,
+        (
+            ExpressionKind::ArrayAccess(left_name, left_index),
+            ExpressionKind::ArrayAccess(right_name, right_index),
+        ) => left_name == right_name && same_value(left_index, right_index),
+        (ExpressionKind::Literal(left), ExpressionKind::Literal(right)) => left.node == right.node,
+        _ => false,
+    }
+}
+
+/// Value of literals, constants and arithmetic on them
+fn constant_value(context: &LintContext, expression: &Expression) -> Option<f64> {
+    match &expression.node {
+        ExpressionKind::Literal(literal) => literal_value(&literal.node),
+        ExpressionKind::Identifier(name) => {
+            let symbol = context
+                .symbol_table
+                .get(name)
+                .filter(|symbol| symbol.is_constant)?;
+            match &symbol.value {
+                SymbolValue::Single(literal) => literal_value(literal),
+                _ => None,
+            }
+        }
+        ExpressionKind::BinaryOp(left, operator, right) => {
+            let left = constant_value(context, left)?;
+            let right = constant_value(context, right)?;
+            match operator {
+                Operator::Add => Some(left + right),
+                Operator::Subtract => Some(left - right),
+                Operator::Multiply => Some(left * right),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn literal_value(literal: &LiteralKind) -> Option<f64> {
+    match literal {
+        LiteralKind::Int(value) => Some(*value as f64),
+        LiteralKind::Float(value) => Some(*value as f64),
+        LiteralKind::String(_) => None,
+    }
+}
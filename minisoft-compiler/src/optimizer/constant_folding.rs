@@ -0,0 +1,202 @@
+use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::optimizer::{Pass, Slot, literal_value, value_literal};
+use crate::parser::ast::Type;
+use crate::runtime::memory::Value;
+use crate::semantics::symbol_table::{SymbolKind, SymbolTable};
+use std::collections::HashMap;
+
+/// Folds operations on literals, propagates known values into later uses
+/// and resolves conditional jumps whose condition is constant
+pub struct ConstantFolding {
+    types: HashMap<String, Type>,
+}
+
+impl ConstantFolding {
+    /// Variable types are needed to apply the same Int to Float conversion as assignments
+    pub fn new(symbol_table: &SymbolTable) -> Self {
+        ConstantFolding {
+            types: symbol_table
+                .get_all()
+                .into_iter()
+                .filter(|symbol| !matches!(symbol.kind, SymbolKind::Array(_)))
+                .map(|symbol| (symbol.qualified_name(), symbol.symbol_type.clone()))
+                .collect(),
+        }
+    }
+
+    /// Replaces reads of variables and temporaries whose value is known
+    fn propagate(&self, program: &mut QuadrupleProgram) -> usize {
+        let globals = self.global_constants(program);
+        let mut known: HashMap<Slot, Operand> = HashMap::new();
+        let mut changes = 0;
+
+        for (index, quad) in program.quadruples.iter_mut().enumerate() {
+            // Values known on the fallthrough path may not hold on other paths into a label
+            if let Operation::Label(_) | Operation::Entry(_) = quad.operation {
+                known.clear();
+            }
+
+            for operand in quad.uses_mut() {
+                let Some(slot) = Slot::of(operand) else {
+                    continue;
+                };
+                let value = known.get(&slot).or_else(|| {
+                    globals
+                        .get(&slot)
+                        .filter(|(definition, _)| *definition < index)
+                        .map(|(_, value)| value)
+                });
+                if let Some(value) = value {
+                    *operand = value.clone();
+                    changes += 1;
+                }
+            }
+
+            // The called subprogram may write any variable, temporaries are restored on return
+            if let Operation::Call(_) = quad.operation {
+                known.retain(|slot, _| matches!(slot, Slot::Temp(_)));
+            }
+            if let Some(slot) = quad.definition().and_then(Slot::of) {
+                match self.assigned_literal(quad, &slot) {
+                    Some(value) => known.insert(slot, value),
+                    None => known.remove(&slot),
+                };
+            }
+        }
+
+        changes
+    }
+
+    /// Slots assigned a literal exactly once, where that value reaches every later use.
+    /// Variables qualify when the assignment runs before the first label, jump or call, so
+    /// it executes once ahead of everything after it. Temporaries are always defined before
+    /// their uses by the generator.
+    fn global_constants(&self, program: &QuadrupleProgram) -> HashMap<Slot, (usize, Operand)> {
+        let prefix_end = program
+            .quadruples
+            .iter()
+            .position(|quad| {
+                matches!(
+                    quad.operation,
+                    Operation::Label(_)
+                        | Operation::Jump(_)
+                        | Operation::JumpIfTrue(_)
+                        | Operation::JumpIfFalse(_)
+                        | Operation::Call(_)
+                        | Operation::Return
+                )
+            })
+            .unwrap_or(program.quadruples.len());
+
+        let mut definitions: HashMap<Slot, Vec<usize>> = HashMap::new();
+        for (index, quad) in program.quadruples.iter().enumerate() {
+            if let Some(slot) = quad.definition().and_then(Slot::of) {
+                definitions.entry(slot).or_default().push(index);
+            }
+        }
+
+        definitions
+            .into_iter()
+            .filter_map(|(slot, indices)| {
+                let [index] = indices[..] else {
+                    return None;
+                };
+                if matches!(slot, Slot::Variable(_)) && index >= prefix_end {
+                    return None;
+                }
+                let value = self.assigned_literal(&program.quadruples[index], &slot)?;
+                Some((slot, (index, value)))
+            })
+            .collect()
+    }
+
+    /// Literal stored by an `ASSIGN`, converted to the type of the target variable
+    fn assigned_literal(&self, quad: &Quadruple, slot: &Slot) -> Option<Operand> {
+        if quad.operation != Operation::Assign {
+            return None;
+        }
+        let value = literal_value(&quad.operand1)?;
+        let value = match slot {
+            Slot::Variable(name) => match self.types.get(name) {
+                Some(typ) => value.coerce_to(typ),
+                None => value,
+            },
+            Slot::Temp(_) => value,
+        };
+        value_literal(value)
+    }
+
+    /// Evaluates operations whose operands are all literals
+    fn fold(&self, program: &mut QuadrupleProgram) -> usize {
+        let mut changes = 0;
+        let quadruples = std::mem::take(&mut program.quadruples);
+
+        for mut quad in quadruples {
+            let left = literal_value(&quad.operand1);
+            let right = literal_value(&quad.operand2);
+
+            match (quad.operation.clone(), left, right) {
+                (operation, Some(left), Some(right)) if operation.is_binary() => {
+                    // Faulting operations are left for the virtual machine to report
+                    if let Some(value) = Value::binary(&operation, left, right)
+                        .ok()
+                        .and_then(value_literal)
+                    {
+                        quad.operation = Operation::Assign;
+                        quad.operand1 = value;
+                        quad.operand2 = Operand::Empty;
+                        changes += 1;
+                    }
+                }
+                (Operation::Not, Some(value), _) => {
+                    quad.operation = Operation::Assign;
+                    quad.operand1 = Operand::IntLiteral(!value.is_truthy() as i32);
+                    changes += 1;
+                }
+                (Operation::Length, Some(value), _) => {
+                    if let Some(length) = value.length().ok().and_then(value_literal) {
+                        quad.operation = Operation::Assign;
+                        quad.operand1 = length;
+                        changes += 1;
+                    }
+                }
+                (Operation::JumpIfTrue(label), Some(value), _)
+                | (Operation::JumpIfFalse(label), Some(value), _) => {
+                    changes += 1;
+                    let jumps_when = matches!(quad.operation, Operation::JumpIfTrue(_));
+                    if value.is_truthy() != jumps_when {
+                        continue;
+                    }
+                    quad.operation = Operation::Jump(label);
+                    quad.operand1 = Operand::Empty;
+                }
+                (Operation::CheckStep, Some(value), _) if value != Value::Int(0) => {
+                    changes += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            program.quadruples.push(quad);
+        }
+
+        changes
+    }
+}
+
+impl Pass for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "constant-folding"
+    }
+
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize {
+        let mut changes = 0;
+        loop {
+            let round = self.propagate(program) + self.fold(program);
+            if round == 0 {
+                return changes;
+            }
+            changes += round;
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::codegen::cfg::ControlFlowGraph;
+use crate::codegen::quadruple::Quadruple;
+use crate::optimizer::Slot;
+use std::collections::{BTreeSet, HashMap};
+
+/// Which way facts flow through the control-flow graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// A dataflow problem solved by `solve`
+pub trait DataflowAnalysis {
+    type Fact: Clone + PartialEq;
+
+    fn direction(&self) -> Direction;
+
+    /// Fact at the entry of the program and of its subprograms for forward analyses, at
+    /// their exits for backward ones
+    fn boundary(&self) -> Self::Fact;
+
+    /// Fact every other point starts from, left unchanged by `meet`
+    fn initial(&self) -> Self::Fact;
+
+    /// Combines the fact flowing in from another edge into `fact`
+    fn meet(&self, fact: &mut Self::Fact, other: &Self::Fact);
+
+    /// Applies the effect of the quadruple at `index` in program order to `fact`
+    fn transfer(&self, index: usize, quad: &Quadruple, fact: &mut Self::Fact);
+}
+
+/// Facts at every block boundary and around every quadruple, indexed in program order.
+/// `before` and `after` follow execution order whatever the direction of the analysis.
+#[derive(Debug, Clone)]
+pub struct DataflowResult<F> {
+    pub block_entry: Vec<F>,
+    pub block_exit: Vec<F>,
+    pub before: Vec<F>,
+    pub after: Vec<F>,
+}
+
+impl<F> DataflowResult<F> {
+    /// Fact holding right before the quadruple at `index` runs
+    pub fn before(&self, index: usize) -> &F {
+        &self.before[index]
+    }
+
+    /// Fact holding right after the quadruple at `index` ran
+    pub fn after(&self, index: usize) -> &F {
+        &self.after[index]
+    }
+}
+
+/// Iterates the analysis to a fixpoint. Blocks not reachable from the entry or a subprogram
+/// keep the initial fact.
+pub fn solve<A: DataflowAnalysis>(cfg: &ControlFlowGraph, analysis: &A) -> DataflowResult<A::Fact> {
+    let count = cfg.blocks.len();
+    let mut first_index = Vec::with_capacity(count);
+    let mut next = 0;
+    for block in &cfg.blocks {
+        first_index.push(next);
+        next += block.quadruples.len();
+    }
+
+    let mut block_entry = vec![analysis.initial(); count];
+    let mut block_exit = vec![analysis.initial(); count];
+    let roots = cfg.roots();
+    let mut order = cfg.reverse_post_order();
+    let forward = analysis.direction() == Direction::Forward;
+    if !forward {
+        order.reverse();
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &id in &order {
+            let block = &cfg.blocks[id];
+            if forward {
+                let mut fact = if roots.contains(&id) {
+                    analysis.boundary()
+                } else {
+                    analysis.initial()
+                };
+                for &predecessor in &block.predecessors {
+                    analysis.meet(&mut fact, &block_exit[predecessor]);
+                }
+                block_entry[id] = fact.clone();
+                for (offset, quad) in block.quadruples.iter().enumerate() {
+                    analysis.transfer(first_index[id] + offset, quad, &mut fact);
+                }
+                if fact != block_exit[id] {
+                    block_exit[id] = fact;
+                    changed = true;
+                }
+            } else {
+                let mut fact = if block.successors.is_empty() {
+                    analysis.boundary()
+                } else {
+                    analysis.initial()
+                };
+                for &successor in &block.successors {
+                    analysis.meet(&mut fact, &block_entry[successor]);
+                }
+                block_exit[id] = fact.clone();
+                for (offset, quad) in block.quadruples.iter().enumerate().rev() {
+                    analysis.transfer(first_index[id] + offset, quad, &mut fact);
+                }
+                if fact != block_entry[id] {
+                    block_entry[id] = fact;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    // Replays each block once more to record the facts around its quadruples
+    let mut before = vec![analysis.initial(); next];
+    let mut after = vec![analysis.initial(); next];
+    for block in &cfg.blocks {
+        let start = first_index[block.id];
+        if forward {
+            let mut fact = block_entry[block.id].clone();
+            for (offset, quad) in block.quadruples.iter().enumerate() {
+                before[start + offset] = fact.clone();
+                analysis.transfer(start + offset, quad, &mut fact);
+                after[start + offset] = fact.clone();
+            }
+        } else {
+            let mut fact = block_exit[block.id].clone();
+            for (offset, quad) in block.quadruples.iter().enumerate().rev() {
+                after[start + offset] = fact.clone();
+                analysis.transfer(start + offset, quad, &mut fact);
+                before[start + offset] = fact.clone();
+            }
+        }
+    }
+
+    DataflowResult {
+        block_entry,
+        block_exit,
+        before,
+        after,
+    }
+}
+
+/// Variables and temporaries whose current value may still be read.
+/// Array elements are not tracked, a store to one element doesn't kill the others.
+pub struct Liveness;
+
+impl DataflowAnalysis for Liveness {
+    type Fact = BTreeSet<Slot>;
+
+    fn direction(&self) -> Direction {
+        Direction::Backward
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        BTreeSet::new()
+    }
+
+    fn initial(&self) -> Self::Fact {
+        BTreeSet::new()
+    }
+
+    fn meet(&self, fact: &mut Self::Fact, other: &Self::Fact) {
+        fact.extend(other.iter().cloned());
+    }
+
+    fn transfer(&self, _: usize, quad: &Quadruple, fact: &mut Self::Fact) {
+        if let Some(slot) = quad.definition().and_then(Slot::of) {
+            fact.remove(&slot);
+        }
+        fact.extend(quad.uses().into_iter().filter_map(Slot::of));
+    }
+}
+
+/// Definitions, by quadruple index, that may have produced the current value of a slot
+pub struct ReachingDefinitions {
+    definitions: HashMap<Slot, Vec<usize>>,
+}
+
+impl ReachingDefinitions {
+    /// Collects every definition of the graph, in the same program order `solve` uses
+    pub fn new(cfg: &ControlFlowGraph) -> Self {
+        let mut definitions: HashMap<Slot, Vec<usize>> = HashMap::new();
+        let quadruples = cfg.blocks.iter().flat_map(|block| block.quadruples.iter());
+        for (index, quad) in quadruples.enumerate() {
+            if let Some(slot) = quad.definition().and_then(Slot::of) {
+                definitions.entry(slot).or_default().push(index);
+            }
+        }
+        ReachingDefinitions { definitions }
+    }
+}
+
+impl DataflowAnalysis for ReachingDefinitions {
+    type Fact = BTreeSet<usize>;
+
+    fn direction(&self) -> Direction {
+        Direction::Forward
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        BTreeSet::new()
+    }
+
+    fn initial(&self) -> Self::Fact {
+        BTreeSet::new()
+    }
+
+    fn meet(&self, fact: &mut Self::Fact, other: &Self::Fact) {
+        fact.extend(other.iter().copied());
+    }
+
+    fn transfer(&self, index: usize, quad: &Quadruple, fact: &mut Self::Fact) {
+        if let Some(slot) = quad.definition().and_then(Slot::of) {
+            if let Some(killed) = self.definitions.get(&slot) {
+                for definition in killed {
+                    fact.remove(definition);
+                }
+            }
+            fact.insert(index);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::codegen::cfg::ControlFlowGraph;
+use crate::codegen::quadruple::{Operation, QuadrupleProgram};
+use crate::optimizer::{Pass, Slot};
+use std::collections::{HashMap, HashSet};
+
+/// Removes blocks that can never execute, labels no jump refers to and
+/// computations whose result is never read.
+///
+/// Only side-effect free quadruples are removed as dead: `INPUT` still consumes
+/// a value, `OUTPUT` and array stores stay. A removed computation no longer raises
+/// the runtime error it might have raised, e.g. an overflow in an unused temporary.
+#[derive(Default)]
+pub struct DeadCodeElimination;
+
+impl DeadCodeElimination {
+    pub fn new() -> Self {
+        DeadCodeElimination
+    }
+
+    /// Drops every basic block not reachable from the entry
+    fn remove_unreachable_blocks(&self, program: &mut QuadrupleProgram) -> usize {
+        let mut cfg = ControlFlowGraph::build(program);
+        let reachable: HashSet<usize> = cfg.reverse_post_order().into_iter().collect();
+
+        let before = program.quadruples.len();
+        cfg.blocks.retain(|block| reachable.contains(&block.id));
+        *program = cfg.to_program();
+        before - program.quadruples.len()
+    }
+
+    /// Drops labels that no jump targets
+    fn remove_unused_labels(&self, program: &mut QuadrupleProgram) -> usize {
+        let targets: HashSet<usize> = program
+            .quadruples
+            .iter()
+            .filter_map(|quad| match quad.operation {
+                Operation::Jump(label)
+                | Operation::JumpIfTrue(label)
+                | Operation::JumpIfFalse(label) => Some(label),
+                _ => None,
+            })
+            .collect();
+
+        let before = program.quadruples.len();
+        program.quadruples.retain(|quad| match quad.operation {
+            Operation::Label(label) => targets.contains(&label),
+            _ => true,
+        });
+        before - program.quadruples.len()
+    }
+
+    /// Drops side-effect free quadruples writing a variable or temporary nobody reads
+    fn remove_dead_assignments(&self, program: &mut QuadrupleProgram) -> usize {
+        let mut reads: HashMap<Slot, usize> = HashMap::new();
+        for quad in &program.quadruples {
+            for operand in quad.uses() {
+                if let Some(slot) = Slot::of(operand) {
+                    *reads.entry(slot).or_default() += 1;
+                }
+            }
+        }
+
+        let before = program.quadruples.len();
+        program.quadruples.retain(|quad| {
+            let pure = quad.operation.is_binary()
+                || matches!(
+                    quad.operation,
+                    Operation::Assign | Operation::Not | Operation::Length | Operation::ArrayLoad
+                );
+            let unread = quad
+                .definition()
+                .and_then(Slot::of)
+                .is_some_and(|slot| !reads.contains_key(&slot));
+            !(pure && unread)
+        });
+        before - program.quadruples.len()
+    }
+}
+
+impl Pass for DeadCodeElimination {
+    fn name(&self) -> &'static str {
+        "dead-code-elimination"
+    }
+
+    /// Returns the number of quadruples removed
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize {
+        let mut removed = 0;
+        loop {
+            let round = self.remove_unreachable_blocks(program)
+                + self.remove_unused_labels(program)
+                + self.remove_dead_assignments(program);
+            if round == 0 {
+                return removed;
+            }
+            removed += round;
+        }
+    }
+}
@@ -0,0 +1,698 @@
+use crate::codegen::cfg::{BasicBlock, ControlFlowGraph, NaturalLoop};
+use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::optimizer::{Pass, Slot};
+use crate::parser::ast::Type;
+use crate::semantics::symbol_table::{SymbolKind, SymbolTable};
+use std::collections::{HashMap, HashSet};
+
+/// Moves computations whose operands don't change inside a loop into a
+/// preheader block that runs once before the loop is entered.
+///
+/// A computation that can fail only moves out of a block that runs on every
+/// iteration. When the loop tests its condition first, a copy of that test
+/// guards the preheader so a skipped loop evaluates nothing.
+#[derive(Default)]
+pub struct LoopInvariantCodeMotion;
+
+impl LoopInvariantCodeMotion {
+    pub fn new() -> Self {
+        LoopInvariantCodeMotion
+    }
+
+    /// Removes the invariant quadruples from the loop, in an order that keeps
+    /// every hoisted definition ahead of its hoisted uses. Also tells whether a
+    /// hoisted computation has to wait for the exit test of the header.
+    fn hoist(
+        &self,
+        cfg: &mut ControlFlowGraph,
+        natural: &NaturalLoop,
+        guarded: bool,
+    ) -> (Vec<Quadruple>, bool) {
+        let every_iteration = every_iteration(cfg, natural, guarded);
+        let definitions = definition_counts(cfg);
+        let mut writes = LoopWrites::of(cfg, natural);
+        let mut hoisted = Vec::new();
+        let mut needs_guard = false;
+
+        let mut moved = true;
+        while moved {
+            moved = false;
+            for &id in &natural.blocks {
+                let safe = every_iteration.contains(&id);
+                let block = &mut cfg.blocks[id];
+                let mut index = 0;
+                while index < block.quadruples.len() {
+                    let quad = &block.quadruples[index];
+                    let may_fail = may_fail(quad);
+                    let single_temp = match &quad.result {
+                        Operand::TempVariable(_) => {
+                            Slot::of(&quad.result).and_then(|slot| definitions.get(&slot).copied())
+                                == Some(1)
+                        }
+                        _ => false,
+                    };
+                    if single_temp && (safe || !may_fail) && writes.invariant(quad) {
+                        let quad = block.quadruples.remove(index);
+                        if let Some(slot) = Slot::of(&quad.result) {
+                            writes.slots.remove(&slot);
+                        }
+                        hoisted.push(quad);
+                        needs_guard |= may_fail && id != natural.header;
+                        moved = true;
+                    } else {
+                        index += 1;
+                    }
+                }
+            }
+        }
+
+        (hoisted, guarded && needs_guard)
+    }
+}
+
+impl Pass for LoopInvariantCodeMotion {
+    fn name(&self) -> &'static str {
+        "loop-invariant-code-motion"
+    }
+
+    /// Returns the number of quadruples moved out of loops
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize {
+        let mut moved = 0;
+        for label in loop_headers(program) {
+            let mut cfg = ControlFlowGraph::build(program);
+            let Some(natural) = find_loop(&cfg, label) else {
+                continue;
+            };
+            if !preheader_possible(&cfg, &natural) {
+                continue;
+            }
+
+            let test = exit_test(&cfg, &natural);
+            let (hoisted, needs_guard) = self.hoist(&mut cfg, &natural, test.is_some());
+            if hoisted.is_empty() {
+                continue;
+            }
+            moved += hoisted.len();
+            let mut preheader = match test {
+                Some(test) if needs_guard => guard(&mut cfg, &test),
+                _ => Vec::new(),
+            };
+            preheader.extend(hoisted);
+            insert_preheader(&mut cfg, &natural, preheader);
+            *program = cfg.to_program();
+        }
+        moved
+    }
+}
+
+/// Replaces multiplications of an induction variable by a constant with a
+/// temporary that is advanced by an addition once per iteration.
+///
+/// An induction variable is an `Int` variable the loop only changes by adding or
+/// subtracting a constant, once per iteration. The product is computed in a
+/// preheader that jumps straight into the first iteration, and advanced only
+/// when another iteration starts, so it takes exactly the values the replaced
+/// multiplications would have computed.
+pub struct StrengthReduction {
+    integers: HashSet<String>,
+}
+
+/// Block and index of a quadruple
+type Position = (usize, usize);
+
+/// A write of an induction variable inside the loop
+struct Increment {
+    block: usize,
+    index: usize,
+    step: i32,
+}
+
+impl StrengthReduction {
+    /// Only `Int` variables are reduced, `Float` products would round differently
+    pub fn new(symbol_table: &SymbolTable) -> Self {
+        StrengthReduction {
+            integers: symbol_table
+                .get_all()
+                .into_iter()
+                .filter(|symbol| {
+                    matches!(symbol.kind, SymbolKind::Variable) && symbol.symbol_type == Type::Int
+                })
+                .map(|symbol| symbol.qualified_name())
+                .collect(),
+        }
+    }
+
+    /// Variables whose every write in the loop adds a constant to them
+    fn induction_variables(
+        &self,
+        cfg: &ControlFlowGraph,
+        natural: &NaturalLoop,
+    ) -> HashMap<String, Vec<Increment>> {
+        let mut variables: HashMap<String, Vec<Increment>> = HashMap::new();
+        let mut rejected: HashSet<String> = HashSet::new();
+
+        for &id in &natural.blocks {
+            let quadruples = &cfg.blocks[id].quadruples;
+            for (index, quad) in quadruples.iter().enumerate() {
+                let Some(Operand::Variable(name)) = quad.definition() else {
+                    continue;
+                };
+                // Either `v := v + c` directly or `t := v + c` followed by `v := t`
+                let step = match quad.operation {
+                    Operation::Assign if index > 0 => {
+                        let previous = &quadruples[index - 1];
+                        (previous.result == quad.operand1)
+                            .then(|| constant_step(previous, name))
+                            .flatten()
+                    }
+                    _ => constant_step(quad, name),
+                };
+                match step {
+                    Some(step) if self.integers.contains(name) => {
+                        variables.entry(name.clone()).or_default().push(Increment {
+                            block: id,
+                            index,
+                            step,
+                        })
+                    }
+                    _ => {
+                        rejected.insert(name.clone());
+                    }
+                }
+            }
+        }
+
+        variables.retain(|name, _| !rejected.contains(name));
+        variables
+    }
+
+    /// Rewrites the multiplications of the loop, or returns `None` if nothing
+    /// could be reduced
+    fn reduce(
+        &self,
+        cfg: &mut ControlFlowGraph,
+        natural: &NaturalLoop,
+        guarded: bool,
+    ) -> Option<Reduction> {
+        let has_call = natural.blocks.iter().any(|&id| {
+            cfg.blocks[id]
+                .quadruples
+                .iter()
+                .any(|quad| matches!(quad.operation, Operation::Call(_)))
+        });
+        let [latch] = natural.latches[..] else {
+            return None;
+        };
+        if has_call {
+            return None;
+        }
+
+        // The product is advanced between iterations, so the variable has to
+        // step exactly once per iteration, after every use
+        let mut variables = self.induction_variables(cfg, natural);
+        variables.retain(
+            |_, increments| matches!(&increments[..], [increment] if increment.block == latch),
+        );
+        let every_iteration = every_iteration(cfg, natural, guarded);
+        let definitions = definition_counts(cfg);
+
+        // Multiplications grouped by variable and factor, in program order
+        let mut products: Vec<((String, i32), Vec<Position>)> = Vec::new();
+        for &id in &natural.blocks {
+            for (index, quad) in cfg.blocks[id].quadruples.iter().enumerate() {
+                let single_temp = matches!(quad.result, Operand::TempVariable(_))
+                    && Slot::of(&quad.result).and_then(|slot| definitions.get(&slot).copied())
+                        == Some(1);
+                if quad.operation != Operation::Multiply || !single_temp {
+                    continue;
+                }
+                let factor = match (&quad.operand1, &quad.operand2) {
+                    (Operand::Variable(name), Operand::IntLiteral(factor))
+                    | (Operand::IntLiteral(factor), Operand::Variable(name))
+                        if variables.contains_key(name) =>
+                    {
+                        (name.clone(), *factor)
+                    }
+                    _ => continue,
+                };
+                match products.iter_mut().find(|(key, _)| *key == factor) {
+                    Some((_, sites)) => sites.push((id, index)),
+                    None => products.push((factor, vec![(id, index)])),
+                }
+            }
+        }
+
+        let mut reduction = Reduction {
+            initializers: Vec::new(),
+            updates: Vec::new(),
+            replaced: 0,
+        };
+        for ((name, factor), sites) in products {
+            let increment = &variables[&name][0];
+            let Some(amount) = increment.step.checked_mul(factor) else {
+                continue;
+            };
+            // Every multiplication has to run on every iteration before the
+            // step, otherwise the product computes values the loop never did
+            let before_step = |&(block, index): &Position| {
+                every_iteration.contains(&block)
+                    && (block != increment.block || index < increment.index)
+            };
+            if !sites.iter().all(before_step) {
+                continue;
+            }
+
+            let product = Operand::TempVariable(format!("t{}", cfg.next_temp));
+            cfg.next_temp += 1;
+
+            let (first_block, first_index) = sites[0];
+            let span = cfg.blocks[first_block].quadruples[first_index].span.clone();
+            reduction.initializers.push(Quadruple {
+                operation: Operation::Multiply,
+                operand1: Operand::Variable(name.clone()),
+                operand2: Operand::IntLiteral(factor),
+                result: product.clone(),
+                span,
+                statement_span: None,
+            });
+
+            for (block, index) in sites {
+                let quad = &mut cfg.blocks[block].quadruples[index];
+                quad.operation = Operation::Assign;
+                quad.operand1 = product.clone();
+                quad.operand2 = Operand::Empty;
+                reduction.replaced += 1;
+            }
+
+            let write = &cfg.blocks[increment.block].quadruples[increment.index];
+            reduction.updates.push(Quadruple {
+                operation: Operation::Add,
+                operand1: product.clone(),
+                operand2: Operand::IntLiteral(amount),
+                result: product,
+                span: write.span.clone(),
+                statement_span: write.statement_span.clone(),
+            });
+        }
+
+        (reduction.replaced > 0).then_some(reduction)
+    }
+}
+
+/// Quadruples computing and advancing the reduced products of a loop
+struct Reduction {
+    initializers: Vec<Quadruple>,
+    updates: Vec<Quadruple>,
+    replaced: usize,
+}
+
+impl Pass for StrengthReduction {
+    fn name(&self) -> &'static str {
+        "strength-reduction"
+    }
+
+    /// Returns the number of multiplications replaced
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize {
+        let mut replaced = 0;
+        for label in loop_headers(program) {
+            let mut cfg = ControlFlowGraph::build(program);
+            let Some(natural) = find_loop(&cfg, label) else {
+                continue;
+            };
+            if !preheader_possible(&cfg, &natural) {
+                continue;
+            }
+
+            let test = exit_test(&cfg, &natural);
+            let Some(reduction) = self.reduce(&mut cfg, &natural, test.is_some()) else {
+                continue;
+            };
+            replaced += reduction.replaced;
+
+            // The first iteration starts right after the test of the header, or
+            // at the header itself if it doesn't test. Later iterations advance
+            // the products on their way there.
+            let header = natural.header;
+            let body_label = match cfg.blocks[header + 1].label() {
+                Some(label) if test.is_some() => label,
+                _ => {
+                    let label = cfg.next_label;
+                    cfg.next_label += 1;
+                    label
+                }
+            };
+
+            let mut preheader = match &test {
+                Some(test) => guard(&mut cfg, test),
+                None => Vec::new(),
+            };
+            preheader.extend(reduction.initializers);
+            preheader.push(control(Operation::Jump(body_label)));
+
+            let mut updates = reduction.updates;
+            if test.is_some() {
+                let body = &mut cfg.blocks[header + 1].quadruples;
+                if body.first().map(|quad| &quad.operation) != Some(&Operation::Label(body_label)) {
+                    body.insert(0, control(Operation::Label(body_label)));
+                }
+                insert_preheader(&mut cfg, &natural, preheader);
+                insert_block(&mut cfg, header + 2, updates);
+            } else {
+                // The updates take over the header label the latch jumps to
+                insert_preheader(&mut cfg, &natural, preheader);
+                let first = &mut cfg.blocks[header + 1].quadruples[0];
+                updates.insert(0, first.clone());
+                first.operation = Operation::Label(body_label);
+                insert_block(&mut cfg, header + 1, updates);
+            }
+            *program = cfg.to_program();
+        }
+        replaced
+    }
+}
+
+/// Slots and arrays written inside a loop
+struct LoopWrites {
+    slots: HashSet<Slot>,
+    arrays: HashSet<String>,
+    /// A call may write any variable or array
+    has_call: bool,
+}
+
+impl LoopWrites {
+    fn of(cfg: &ControlFlowGraph, natural: &NaturalLoop) -> Self {
+        let mut writes = LoopWrites {
+            slots: HashSet::new(),
+            arrays: HashSet::new(),
+            has_call: false,
+        };
+        for &id in &natural.blocks {
+            for quad in &cfg.blocks[id].quadruples {
+                if let Some(slot) = quad.definition().and_then(Slot::of) {
+                    writes.slots.insert(slot);
+                }
+                // Stores name the array they write, input into an element names the element
+                match (&quad.operation, &quad.result) {
+                    (Operation::ArrayStore, Operand::Variable(array))
+                    | (_, Operand::ArrayElement(array, _)) => {
+                        writes.arrays.insert(array.clone());
+                    }
+                    _ => {}
+                }
+                if let Operation::Call(_) = quad.operation {
+                    writes.has_call = true;
+                }
+            }
+        }
+        writes
+    }
+
+    /// Whether the quadruple is a computation producing the same value on every iteration
+    fn invariant(&self, quad: &Quadruple) -> bool {
+        let computation = quad.operation.is_binary()
+            || matches!(
+                quad.operation,
+                Operation::Assign | Operation::Not | Operation::Length | Operation::ArrayLoad
+            );
+        if !computation {
+            return false;
+        }
+        if let (Operation::ArrayLoad, Operand::Variable(array)) = (&quad.operation, &quad.operand1)
+            && (self.has_call || self.arrays.contains(array))
+        {
+            return false;
+        }
+        quad.uses()
+            .into_iter()
+            .all(|operand| match Slot::of(operand) {
+                Some(Slot::Variable(_)) if self.has_call => false,
+                Some(slot) => !self.slots.contains(&slot),
+                None => true,
+            })
+    }
+}
+
+/// Step `c` of a quadruple computing `v + c`, `c + v` or `v - c` for a constant `c`
+fn constant_step(quad: &Quadruple, name: &str) -> Option<i32> {
+    let variable = |operand: &Operand| matches!(operand, Operand::Variable(v) if v == name);
+    match (&quad.operation, &quad.operand1, &quad.operand2) {
+        (Operation::Add, operand, Operand::IntLiteral(step))
+        | (Operation::Add, Operand::IntLiteral(step), operand)
+            if variable(operand) =>
+        {
+            Some(*step)
+        }
+        (Operation::Subtract, operand, Operand::IntLiteral(step)) if variable(operand) => {
+            step.checked_neg()
+        }
+        _ => None,
+    }
+}
+
+/// Whether running the quadruple can stop the program: a division by zero, an
+/// index out of bounds, an `Int` overflow or a read of a variable never written
+fn may_fail(quad: &Quadruple) -> bool {
+    matches!(
+        quad.operation,
+        Operation::Add
+            | Operation::Subtract
+            | Operation::Multiply
+            | Operation::Divide
+            | Operation::ArrayLoad
+            | Operation::Length
+    ) || quad
+        .uses()
+        .into_iter()
+        .any(|operand| matches!(operand, Operand::Variable(_)))
+}
+
+/// Blocks of the loop control can leave it from
+fn exits(cfg: &ControlFlowGraph, natural: &NaturalLoop) -> Vec<usize> {
+    natural
+        .blocks
+        .iter()
+        .copied()
+        .filter(|&block| {
+            let block = &cfg.blocks[block];
+            block.successors.is_empty()
+                || block
+                    .successors
+                    .iter()
+                    .any(|&successor| !natural.contains(successor))
+        })
+        .collect()
+}
+
+/// Blocks that run on every iteration of the loop, besides the header: they
+/// dominate every latch and every exit. With a guarded header, the exit of the
+/// header only leaves before the first iteration, so it doesn't count.
+fn every_iteration(cfg: &ControlFlowGraph, natural: &NaturalLoop, guarded: bool) -> HashSet<usize> {
+    let dominators = cfg.dominators();
+    let required: Vec<usize> = exits(cfg, natural)
+        .into_iter()
+        .filter(|&exit| !(guarded && exit == natural.header))
+        .chain(natural.latches.iter().copied())
+        .collect();
+    natural
+        .blocks
+        .iter()
+        .copied()
+        .filter(|&block| {
+            block == natural.header
+                || required
+                    .iter()
+                    .all(|&other| dominators[other].contains(&block))
+        })
+        .collect()
+}
+
+/// The quadruples of the header after its label when they only test whether
+/// to leave the loop: computations of temporaries used nowhere else, then a
+/// conditional jump out with the body right below
+fn exit_test(cfg: &ControlFlowGraph, natural: &NaturalLoop) -> Option<Vec<Quadruple>> {
+    let header = &cfg.blocks[natural.header];
+    let (terminator, computations) = header.quadruples[1..].split_last()?;
+    let (Operation::JumpIfTrue(exit) | Operation::JumpIfFalse(exit)) = terminator.operation else {
+        return None;
+    };
+    if cfg
+        .block_of_label(exit)
+        .is_none_or(|exit| natural.contains(exit))
+        || !natural.contains(natural.header + 1)
+    {
+        return None;
+    }
+
+    let temps: HashSet<Slot> = computations
+        .iter()
+        .filter_map(|quad| Slot::of(&quad.result))
+        .collect();
+    let local = computations.iter().all(|quad| {
+        let computation = quad.operation.is_binary()
+            || matches!(
+                quad.operation,
+                Operation::Assign | Operation::Not | Operation::Length | Operation::ArrayLoad
+            );
+        computation && matches!(quad.result, Operand::TempVariable(_))
+    });
+    let used_elsewhere = cfg
+        .blocks
+        .iter()
+        .filter(|block| block.id != natural.header)
+        .flat_map(|block| block.quadruples.iter())
+        .flat_map(|quad| quad.uses())
+        .any(|operand| Slot::of(operand).is_some_and(|slot| temps.contains(&slot)));
+    (local && !used_elsewhere).then(|| header.quadruples[1..].to_vec())
+}
+
+/// A copy of the exit test of a header, writing fresh temporaries so every
+/// temporary keeps a single definition
+fn guard(cfg: &mut ControlFlowGraph, test: &[Quadruple]) -> Vec<Quadruple> {
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let mut guard = Vec::new();
+    for quad in test {
+        let mut quad = quad.clone();
+        for operand in quad.uses_mut() {
+            if let Operand::TempVariable(name) = operand
+                && let Some(temp) = renamed.get(name)
+            {
+                *name = temp.clone();
+            }
+        }
+        if let Operand::TempVariable(name) = &mut quad.result {
+            let temp = format!("t{}", cfg.next_temp);
+            cfg.next_temp += 1;
+            renamed.insert(name.clone(), temp.clone());
+            *name = temp;
+        }
+        guard.push(quad);
+    }
+    guard
+}
+
+/// How many quadruples of the whole program write each slot
+fn definition_counts(cfg: &ControlFlowGraph) -> HashMap<Slot, usize> {
+    let mut counts = HashMap::new();
+    for block in &cfg.blocks {
+        for quad in &block.quadruples {
+            if let Some(slot) = quad.definition().and_then(Slot::of) {
+                *counts.entry(slot).or_default() += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Labels of the loop headers, innermost loops first. Loops are looked up again
+/// by label because every transformation rebuilds the graph.
+fn loop_headers(program: &QuadrupleProgram) -> Vec<usize> {
+    let cfg = ControlFlowGraph::build(program);
+    cfg.natural_loops()
+        .iter()
+        .filter_map(|natural| cfg.blocks[natural.header].label())
+        .collect()
+}
+
+fn find_loop(cfg: &ControlFlowGraph, label: usize) -> Option<NaturalLoop> {
+    cfg.natural_loops()
+        .into_iter()
+        .find(|natural| cfg.blocks[natural.header].label() == Some(label))
+}
+
+/// The preheader is placed right above the header, so the block above must not
+/// be part of the loop and fall into the header
+fn preheader_possible(cfg: &ControlFlowGraph, natural: &NaturalLoop) -> bool {
+    let header = natural.header;
+    if header == 0 || !natural.contains(header - 1) {
+        return true;
+    }
+    matches!(
+        cfg.blocks[header - 1]
+            .quadruples
+            .last()
+            .map(|quad| &quad.operation),
+        Some(Operation::Jump(_) | Operation::Return)
+    )
+}
+
+/// Adds a block running the given quadruples on every entry into the loop,
+/// redirecting jumps from outside the loop to it
+fn insert_preheader(cfg: &mut ControlFlowGraph, natural: &NaturalLoop, quadruples: Vec<Quadruple>) {
+    let header = natural.header;
+    let Some(header_label) = cfg.blocks[header].label() else {
+        return;
+    };
+
+    let outside: Vec<usize> = cfg.blocks[header]
+        .predecessors
+        .iter()
+        .copied()
+        .filter(|&predecessor| !natural.contains(predecessor))
+        .collect();
+    let jumps_in = |block: &BasicBlock| {
+        block.terminator().is_some_and(|quad| {
+            matches!(
+                quad.operation,
+                Operation::Jump(target) | Operation::JumpIfTrue(target) | Operation::JumpIfFalse(target)
+                    if target == header_label
+            )
+        })
+    };
+
+    let mut preheader = Vec::new();
+    if outside
+        .iter()
+        .any(|&predecessor| jumps_in(&cfg.blocks[predecessor]))
+    {
+        let label = cfg.next_label;
+        cfg.next_label += 1;
+        for &predecessor in &outside {
+            if let Some(quad) = cfg.blocks[predecessor].quadruples.last_mut() {
+                match &mut quad.operation {
+                    Operation::Jump(target)
+                    | Operation::JumpIfTrue(target)
+                    | Operation::JumpIfFalse(target)
+                        if *target == header_label =>
+                    {
+                        *target = label
+                    }
+                    _ => {}
+                }
+            }
+        }
+        preheader.push(control(Operation::Label(label)));
+    }
+    preheader.extend(quadruples);
+    insert_block(cfg, header, preheader);
+}
+
+/// Adds a block with the given quadruples in front of the block at `position`
+fn insert_block(cfg: &mut ControlFlowGraph, position: usize, quadruples: Vec<Quadruple>) {
+    let start = cfg.blocks[position].start;
+    cfg.blocks.insert(
+        position,
+        BasicBlock {
+            id: position,
+            start,
+            quadruples,
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+        },
+    );
+    for (id, block) in cfg.blocks.iter_mut().enumerate() {
+        block.id = id;
+    }
+    cfg.link();
+}
+
+/// A label or jump quadruple
+fn control(operation: Operation) -> Quadruple {
+    Quadruple {
+        operation,
+        operand1: Operand::Empty,
+        operand2: Operand::Empty,
+        result: Operand::Empty,
+        span: None,
+        statement_span: None,
+    }
+}
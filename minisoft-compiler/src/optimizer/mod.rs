@@ -0,0 +1,80 @@
+pub mod constant_folding;
+pub mod dataflow;
+pub mod dead_code;
+pub mod loops;
+pub mod pass_manager;
+pub mod peephole;
+pub mod value_numbering;
+
+use crate::codegen::quadruple::{Operand, QuadrupleProgram};
+use crate::runtime::memory::Value;
+use std::fmt;
+
+pub use constant_folding::ConstantFolding;
+pub use dataflow::{
+    DataflowAnalysis, DataflowResult, Direction, Liveness, ReachingDefinitions, solve,
+};
+pub use dead_code::DeadCodeElimination;
+pub use loops::{LoopInvariantCodeMotion, StrengthReduction};
+pub use pass_manager::{OptimizationLevel, PassManager, PassSnapshot, QuadrupleChange};
+pub use peephole::Peephole;
+pub use value_numbering::LocalValueNumbering;
+
+/// A transformation over a quadruple program
+pub trait Pass {
+    /// Short name shown in optimization reports
+    fn name(&self) -> &'static str;
+
+    /// Rewrites the program in place and returns how many changes were made
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize;
+
+    /// Changes of the last run broken down by rewrite rule, for passes made of several rules
+    fn rule_hits(&self) -> Vec<(&'static str, usize)> {
+        Vec::new()
+    }
+}
+
+/// Value of a literal operand
+pub(crate) fn literal_value(operand: &Operand) -> Option<Value> {
+    match operand {
+        Operand::IntLiteral(i) => Some(Value::Int(*i)),
+        Operand::FloatLiteral(f) => Some(Value::Float(*f)),
+        Operand::StringLiteral(s) => Some(Value::String(s.clone())),
+        _ => None,
+    }
+}
+
+/// Literal operand holding a value
+pub(crate) fn value_literal(value: Value) -> Option<Operand> {
+    match value {
+        Value::Int(i) => Some(Operand::IntLiteral(i)),
+        Value::Float(f) => Some(Operand::FloatLiteral(f)),
+        Value::String(s) => Some(Operand::StringLiteral(s)),
+        Value::Uninitialized => None,
+    }
+}
+
+/// A scalar storage location: a program variable or a compiler temporary
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Slot {
+    Variable(String),
+    Temp(String),
+}
+
+impl Slot {
+    pub fn of(operand: &Operand) -> Option<Slot> {
+        match operand {
+            Operand::Variable(name) => Some(Slot::Variable(name.clone())),
+            Operand::TempVariable(name) => Some(Slot::Temp(name.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Slot::Variable(name) | Slot::Temp(name) => write!(f, "{}", name),
+        }
+    }
+}
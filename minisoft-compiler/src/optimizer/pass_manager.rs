@@ -0,0 +1,225 @@
+use crate::codegen::quadruple::QuadrupleProgram;
+use crate::optimizer::{
+    ConstantFolding, DeadCodeElimination, LocalValueNumbering, LoopInvariantCodeMotion, Pass,
+    Peephole, StrengthReduction,
+};
+use crate::semantics::symbol_table::SymbolTable;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How much optimization is applied, following the usual `-O` flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OptimizationLevel {
+    /// Generated quadruples are kept as they are
+    O0,
+    /// Constant folding, peephole rewrites and dead code elimination
+    O1,
+    /// Everything in `O1` plus value numbering and the loop optimizations
+    #[default]
+    O2,
+}
+
+impl fmt::Display for OptimizationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptimizationLevel::O0 => write!(f, "O0"),
+            OptimizationLevel::O1 => write!(f, "O1"),
+            OptimizationLevel::O2 => write!(f, "O2"),
+        }
+    }
+}
+
+impl FromStr for OptimizationLevel {
+    type Err = String;
+
+    /// Accepts `-O1`, `O1` or `1`
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        let digit = level.trim_start_matches('-').trim_start_matches(['O', 'o']);
+        match digit {
+            "0" => Ok(OptimizationLevel::O0),
+            "1" => Ok(OptimizationLevel::O1),
+            "2" => Ok(OptimizationLevel::O2),
+            _ => Err(format!(
+                "Unknown optimization level '{}', expected O0, O1 or O2",
+                level
+            )),
+        }
+    }
+}
+
+/// How a quadruple of the program before a pass relates to the program after it.
+/// Quadruples are matched by their text, a rewritten one is removed and added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuadrupleChange {
+    Kept { before: usize, after: usize },
+    Removed { before: usize },
+    Added { after: usize },
+}
+
+/// The program as a pass left it
+#[derive(Debug, Clone)]
+pub struct PassSnapshot {
+    pub pass: &'static str,
+    pub changes: usize,
+    pub rule_hits: Vec<(&'static str, usize)>,
+    pub program: QuadrupleProgram,
+    /// Changes against the program the pass started from, when the manager records them
+    pub diff: Option<Vec<QuadrupleChange>>,
+}
+
+/// Runs a list of passes in order, recording the program after each of them
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+    /// Whether snapshots carry a diff against the program before the pass
+    diffs: bool,
+}
+
+impl PassManager {
+    pub fn new() -> Self {
+        PassManager {
+            passes: Vec::new(),
+            diffs: false,
+        }
+    }
+
+    /// The standard pipeline for an optimization level
+    pub fn for_level(level: OptimizationLevel, symbol_table: &SymbolTable) -> Self {
+        let manager = PassManager::new();
+        match level {
+            OptimizationLevel::O0 => manager,
+            OptimizationLevel::O1 => manager
+                .with_pass(ConstantFolding::new(symbol_table))
+                .with_pass(Peephole::new())
+                .with_pass(DeadCodeElimination::new()),
+            OptimizationLevel::O2 => manager
+                .with_pass(ConstantFolding::new(symbol_table))
+                .with_pass(LocalValueNumbering::new())
+                .with_pass(LoopInvariantCodeMotion::new())
+                .with_pass(StrengthReduction::new(symbol_table))
+                .with_pass(Peephole::new())
+                .with_pass(DeadCodeElimination::new()),
+        }
+    }
+
+    /// Appends a pass to the pipeline
+    pub fn with_pass(mut self, pass: impl Pass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Makes every snapshot record what its pass changed
+    pub fn with_diffs(mut self) -> Self {
+        self.diffs = true;
+        self
+    }
+
+    pub fn pass_names(&self) -> Vec<&'static str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Optimizes the program in place and returns one snapshot per pass
+    pub fn run(&mut self, program: &mut QuadrupleProgram) -> Vec<PassSnapshot> {
+        let mut snapshots = Vec::with_capacity(self.passes.len());
+        for pass in &mut self.passes {
+            let before = self.diffs.then(|| program.clone());
+            let changes = pass.run(program);
+            snapshots.push(PassSnapshot {
+                pass: pass.name(),
+                changes,
+                rule_hits: pass.rule_hits(),
+                program: program.clone(),
+                diff: before.map(|before| diff(&before, program)),
+            });
+        }
+        snapshots
+    }
+}
+
+/// Largest table the diff fills in, in cells. Past it the changed middle of the
+/// programs is shown as removed then added.
+const MAX_DIFF_CELLS: usize = 1 << 20;
+
+/// Lines up two programs along their longest common subsequence of quadruples.
+/// The unchanged start and end are matched directly, only the middle is searched.
+pub fn diff(before: &QuadrupleProgram, after: &QuadrupleProgram) -> Vec<QuadrupleChange> {
+    let old: Vec<String> = before.quadruples.iter().map(ToString::to_string).collect();
+    let new: Vec<String> = after.quadruples.iter().map(ToString::to_string).collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+    changes.extend((0..prefix).map(|i| QuadrupleChange::Kept {
+        before: i,
+        after: i,
+    }));
+    for change in diff_middle(old_middle, new_middle) {
+        changes.push(match change {
+            QuadrupleChange::Kept { before, after } => QuadrupleChange::Kept {
+                before: before + prefix,
+                after: after + prefix,
+            },
+            QuadrupleChange::Removed { before } => QuadrupleChange::Removed {
+                before: before + prefix,
+            },
+            QuadrupleChange::Added { after } => QuadrupleChange::Added {
+                after: after + prefix,
+            },
+        });
+    }
+    changes.extend((0..suffix).map(|i| QuadrupleChange::Kept {
+        before: old.len() - suffix + i,
+        after: new.len() - suffix + i,
+    }));
+    changes
+}
+
+fn diff_middle(old: &[String], new: &[String]) -> Vec<QuadrupleChange> {
+    if (old.len() + 1).saturating_mul(new.len() + 1) > MAX_DIFF_CELLS {
+        return (0..old.len())
+            .map(|before| QuadrupleChange::Removed { before })
+            .chain((0..new.len()).map(|after| QuadrupleChange::Added { after }))
+            .collect();
+    }
+
+    // common[i][j] is the length of the common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push(QuadrupleChange::Kept {
+                before: i,
+                after: j,
+            });
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+            changes.push(QuadrupleChange::Removed { before: i });
+            i += 1;
+        } else {
+            changes.push(QuadrupleChange::Added { after: j });
+            j += 1;
+        }
+    }
+    changes
+}
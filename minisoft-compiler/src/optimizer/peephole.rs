@@ -0,0 +1,329 @@
+use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::optimizer::{Pass, Slot};
+use std::collections::{HashMap, HashSet};
+
+/// A local rewrite looking at a few consecutive quadruples
+pub struct PeepholeRule {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Rewrites the quadruples starting at the given index, returns whether it changed anything
+    apply: fn(&mut Vec<Quadruple>, usize, &Context) -> bool,
+}
+
+/// Every rule the peephole pass knows, in the order they are tried
+pub const RULES: &[PeepholeRule] = &[
+    PeepholeRule {
+        name: "copy-coalescing",
+        description: "Writes a result straight into the variable a temporary is copied to",
+        apply: coalesce_copy,
+    },
+    PeepholeRule {
+        name: "jump-to-next",
+        description: "Removes jumps to a label that directly follows them",
+        apply: remove_jump_to_next,
+    },
+    PeepholeRule {
+        name: "jump-chaining",
+        description: "Retargets jumps to a label followed by another jump",
+        apply: chain_jump,
+    },
+    PeepholeRule {
+        name: "algebraic-identity",
+        description: "Turns x*1, 1*x, x/1, x+0, 0+x and x-0 into copies",
+        apply: simplify_identity,
+    },
+    PeepholeRule {
+        name: "inverted-comparison",
+        description: "Replaces NOT of a comparison by the opposite comparison",
+        apply: invert_comparison,
+    },
+    PeepholeRule {
+        name: "negated-branch",
+        description: "Branches on the condition itself instead of its NOT",
+        apply: invert_branch,
+    },
+];
+
+/// Applies the enabled rules until none matches anywhere, counting hits per rule
+pub struct Peephole {
+    rules: Vec<&'static PeepholeRule>,
+    hits: Vec<(&'static str, usize)>,
+}
+
+/// Facts about the whole program the rules need to stay correct
+struct Context {
+    uses: HashMap<Slot, usize>,
+    definitions: HashMap<Slot, usize>,
+    labels: HashMap<usize, usize>,
+}
+
+impl Context {
+    fn of(quadruples: &[Quadruple]) -> Self {
+        let mut context = Context {
+            uses: HashMap::new(),
+            definitions: HashMap::new(),
+            labels: HashMap::new(),
+        };
+        for (index, quad) in quadruples.iter().enumerate() {
+            for operand in quad.uses() {
+                if let Some(slot) = Slot::of(operand) {
+                    *context.uses.entry(slot).or_default() += 1;
+                }
+            }
+            if let Some(slot) = quad.definition().and_then(Slot::of) {
+                *context.definitions.entry(slot).or_default() += 1;
+            }
+            if let Operation::Label(label) = quad.operation {
+                context.labels.insert(label, index);
+            }
+        }
+        context
+    }
+
+    /// A temporary written once and read once, so its single read can be folded away
+    fn single_use_temp(&self, operand: &Operand) -> bool {
+        match operand {
+            Operand::TempVariable(_) => Slot::of(operand).is_some_and(|slot| {
+                self.uses.get(&slot) == Some(&1) && self.definitions.get(&slot) == Some(&1)
+            }),
+            _ => false,
+        }
+    }
+}
+
+impl Peephole {
+    /// A pass running every rule
+    pub fn new() -> Self {
+        Self::build(RULES.iter().collect())
+    }
+
+    /// A pass running only the named rules, unknown names are ignored
+    pub fn with_rules(names: &[&str]) -> Self {
+        Self::build(
+            RULES
+                .iter()
+                .filter(|rule| names.contains(&rule.name))
+                .collect(),
+        )
+    }
+
+    fn build(rules: Vec<&'static PeepholeRule>) -> Self {
+        let hits = rules.iter().map(|rule| (rule.name, 0)).collect();
+        Peephole { rules, hits }
+    }
+
+    /// How many times each enabled rule fired during the last run
+    pub fn hits(&self) -> &[(&'static str, usize)] {
+        &self.hits
+    }
+}
+
+impl Default for Peephole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for Peephole {
+    fn name(&self) -> &'static str {
+        "peephole"
+    }
+
+    /// Returns the total number of rule applications
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize {
+        for (_, hits) in &mut self.hits {
+            *hits = 0;
+        }
+
+        let mut context = Context::of(&program.quadruples);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut index = 0;
+            while index < program.quadruples.len() {
+                let fired = self
+                    .rules
+                    .iter()
+                    .position(|rule| (rule.apply)(&mut program.quadruples, index, &context));
+                match fired {
+                    // Try again at the same place, the rewrite may enable another rule
+                    Some(rule) => {
+                        self.hits[rule].1 += 1;
+                        context = Context::of(&program.quadruples);
+                        changed = true;
+                    }
+                    None => index += 1,
+                }
+            }
+        }
+
+        self.hits.iter().map(|(_, hits)| hits).sum()
+    }
+
+    fn rule_hits(&self) -> Vec<(&'static str, usize)> {
+        self.hits.clone()
+    }
+}
+
+/// `t := a op b; x := t` becomes `x := a op b` when `t` is read nowhere else
+fn coalesce_copy(quadruples: &mut Vec<Quadruple>, index: usize, context: &Context) -> bool {
+    let Some([first, copy]) = quadruples.get(index..index + 2) else {
+        return false;
+    };
+    let computes = first.operation.is_binary()
+        || matches!(
+            first.operation,
+            Operation::Assign | Operation::Not | Operation::Length | Operation::ArrayLoad
+        );
+    let copies_result = copy.operation == Operation::Assign
+        && copy.operand1 == first.result
+        && matches!(copy.result, Operand::Variable(_) | Operand::TempVariable(_));
+    if !computes || !copies_result || !context.single_use_temp(&first.result) {
+        return false;
+    }
+
+    let copy = quadruples.remove(index + 1);
+    quadruples[index].result = copy.result;
+    true
+}
+
+/// Jumps to a label reached anyway by falling through, possibly past other labels
+fn remove_jump_to_next(quadruples: &mut Vec<Quadruple>, index: usize, _: &Context) -> bool {
+    let target = match quadruples[index].operation {
+        Operation::Jump(label) | Operation::JumpIfTrue(label) | Operation::JumpIfFalse(label) => {
+            label
+        }
+        _ => return false,
+    };
+    let falls_to_target = quadruples[index + 1..]
+        .iter()
+        .map_while(|quad| match quad.operation {
+            Operation::Label(label) => Some(label),
+            _ => None,
+        })
+        .any(|label| label == target);
+    if falls_to_target {
+        quadruples.remove(index);
+    }
+    falls_to_target
+}
+
+/// A jump to a label whose code starts with `JUMP L` goes to `L` directly
+// Rules share one signature, some of them only rewrite in place
+#[allow(clippy::ptr_arg)]
+fn chain_jump(quadruples: &mut Vec<Quadruple>, index: usize, context: &Context) -> bool {
+    let target = match quadruples[index].operation {
+        Operation::Jump(label) | Operation::JumpIfTrue(label) | Operation::JumpIfFalse(label) => {
+            label
+        }
+        _ => return false,
+    };
+
+    // Follows the chain to its end, giving up on jumps that loop among themselves
+    let mut visited = HashSet::from([target]);
+    let mut last = target;
+    while let Some(next) = forwarded_label(quadruples, last, context) {
+        if !visited.insert(next) {
+            return false;
+        }
+        last = next;
+    }
+    if last == target {
+        return false;
+    }
+
+    match &mut quadruples[index].operation {
+        Operation::Jump(label) | Operation::JumpIfTrue(label) | Operation::JumpIfFalse(label) => {
+            *label = last
+        }
+        _ => unreachable!(),
+    }
+    true
+}
+
+/// Label an unconditional jump right after `label` continues to
+fn forwarded_label(quadruples: &[Quadruple], label: usize, context: &Context) -> Option<usize> {
+    let position = *context.labels.get(&label)?;
+    quadruples[position..]
+        .iter()
+        .find(|quad| !matches!(quad.operation, Operation::Label(_)))
+        .and_then(|quad| match quad.operation {
+            Operation::Jump(next) => Some(next),
+            _ => None,
+        })
+}
+
+/// Operations with an `Int` literal that leave the other operand unchanged.
+/// A `Float` literal is left alone: it would turn an `Int` operand into a `Float`.
+// Rules share one signature, some of them only rewrite in place
+#[allow(clippy::ptr_arg)]
+fn simplify_identity(quadruples: &mut Vec<Quadruple>, index: usize, _: &Context) -> bool {
+    let quad = &mut quadruples[index];
+    let one = Operand::IntLiteral(1);
+    let zero = Operand::IntLiteral(0);
+    let kept = match quad.operation {
+        Operation::Multiply if quad.operand2 == one => quad.operand1.clone(),
+        Operation::Multiply if quad.operand1 == one => quad.operand2.clone(),
+        Operation::Divide if quad.operand2 == one => quad.operand1.clone(),
+        Operation::Add if quad.operand2 == zero => quad.operand1.clone(),
+        Operation::Add if quad.operand1 == zero => quad.operand2.clone(),
+        Operation::Subtract if quad.operand2 == zero => quad.operand1.clone(),
+        _ => return false,
+    };
+    quad.operation = Operation::Assign;
+    quad.operand1 = kept;
+    quad.operand2 = Operand::Empty;
+    true
+}
+
+/// `t := a < b; u := NOT t` becomes `u := a >= b` when `t` is read nowhere else
+fn invert_comparison(quadruples: &mut Vec<Quadruple>, index: usize, context: &Context) -> bool {
+    let Some([comparison, not]) = quadruples.get(index..index + 2) else {
+        return false;
+    };
+    let inverse = match comparison.operation {
+        Operation::Equal => Operation::NotEqual,
+        Operation::NotEqual => Operation::Equal,
+        Operation::LessThan => Operation::GreaterEqual,
+        Operation::GreaterEqual => Operation::LessThan,
+        Operation::GreaterThan => Operation::LessEqual,
+        Operation::LessEqual => Operation::GreaterThan,
+        _ => return false,
+    };
+    if not.operation != Operation::Not
+        || not.operand1 != comparison.result
+        || !context.single_use_temp(&comparison.result)
+    {
+        return false;
+    }
+
+    let not = quadruples.remove(index + 1);
+    let comparison = &mut quadruples[index];
+    comparison.operation = inverse;
+    comparison.result = not.result;
+    true
+}
+
+/// `u := NOT t; JMPF u` becomes `JMPT t` when `u` is read nowhere else, and the other way round
+fn invert_branch(quadruples: &mut Vec<Quadruple>, index: usize, context: &Context) -> bool {
+    let Some([not, branch]) = quadruples.get(index..index + 2) else {
+        return false;
+    };
+    let inverse = match branch.operation {
+        Operation::JumpIfFalse(label) => Operation::JumpIfTrue(label),
+        Operation::JumpIfTrue(label) => Operation::JumpIfFalse(label),
+        _ => return false,
+    };
+    if not.operation != Operation::Not
+        || branch.operand1 != not.result
+        || !context.single_use_temp(&not.result)
+    {
+        return false;
+    }
+
+    let not = quadruples.remove(index);
+    let branch = &mut quadruples[index];
+    branch.operation = inverse;
+    branch.operand1 = not.operand1;
+    true
+}
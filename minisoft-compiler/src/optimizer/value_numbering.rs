@@ -0,0 +1,192 @@
+use crate::codegen::cfg::ControlFlowGraph;
+use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::optimizer::{Pass, Slot};
+use std::collections::HashMap;
+
+/// Local common subexpression elimination by value numbering.
+///
+/// Within each basic block, a computation whose operands have the same value
+/// numbers as an earlier one still held by a temporary is replaced by a copy of
+/// that temporary. Writes to a variable give it a new number, array stores
+/// or inputs into an array invalidate earlier loads from it, and calls invalidate everything.
+#[derive(Default)]
+pub struct LocalValueNumbering;
+
+/// Value numbers of one basic block
+#[derive(Default)]
+struct BlockNumbering {
+    next: usize,
+    slots: HashMap<Slot, usize>,
+    literals: HashMap<String, usize>,
+    expressions: HashMap<(String, Vec<usize>), usize>,
+    holders: HashMap<usize, Vec<Operand>>,
+    array_versions: HashMap<String, usize>,
+}
+
+impl BlockNumbering {
+    fn fresh(&mut self) -> usize {
+        self.next += 1;
+        self.next
+    }
+
+    fn number(&mut self, operand: &Operand) -> usize {
+        if let Some(slot) = Slot::of(operand) {
+            if let Some(&number) = self.slots.get(&slot) {
+                return number;
+            }
+            let number = self.fresh();
+            self.slots.insert(slot, number);
+            return number;
+        }
+        // Literals with the same type and value share a number
+        let key = format!("{:?}", operand);
+        if let Some(&number) = self.literals.get(&key) {
+            return number;
+        }
+        let number = self.fresh();
+        self.literals.insert(key, number);
+        number
+    }
+
+    fn define(&mut self, operand: &Operand, number: usize) {
+        if let Some(slot) = Slot::of(operand) {
+            self.slots.insert(slot, number);
+            // Only temporaries hold values unchanged, variables may convert Int to Float
+            if let Operand::TempVariable(_) = operand {
+                self.holders
+                    .entry(number)
+                    .or_default()
+                    .push(operand.clone());
+            }
+        }
+    }
+
+    /// A temporary that still holds the given value number
+    fn holder(&self, number: usize) -> Option<&Operand> {
+        self.holders.get(&number)?.iter().find(|operand| {
+            Slot::of(operand).and_then(|slot| self.slots.get(&slot).copied()) == Some(number)
+        })
+    }
+
+    fn expression_key(&mut self, quad: &Quadruple) -> Option<(String, Vec<usize>)> {
+        let operation = &quad.operation;
+        if operation.is_binary() {
+            let mut operands = vec![self.number(&quad.operand1), self.number(&quad.operand2)];
+            if is_commutative(operation) {
+                operands.sort_unstable();
+            }
+            return Some((operation.to_string(), operands));
+        }
+        match (operation, &quad.operand1) {
+            (Operation::Not | Operation::Length, operand) => {
+                Some((operation.to_string(), vec![self.number(operand)]))
+            }
+            (Operation::ArrayLoad, Operand::Variable(array)) => {
+                let version = self.array_versions.get(array).copied().unwrap_or_default();
+                let index = self.number(&quad.operand2);
+                Some((format!("{} {}", operation, array), vec![version, index]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Drops every value number, a called subprogram may change any variable or array
+    fn forget(&mut self) {
+        *self = BlockNumbering {
+            next: self.next,
+            ..BlockNumbering::default()
+        };
+    }
+
+    /// Numbers one quadruple, rewriting it into a copy when its value is already available
+    fn visit(&mut self, quad: &mut Quadruple) -> bool {
+        if let Operation::Call(_) = quad.operation {
+            self.forget();
+        }
+        match (&quad.operation, &quad.result) {
+            (Operation::ArrayStore, Operand::Variable(array))
+            | (_, Operand::ArrayElement(array, _)) => {
+                *self.array_versions.entry(array.clone()).or_default() += 1;
+                return false;
+            }
+            (Operation::Assign, Operand::TempVariable(_)) => {
+                let number = self.number(&quad.operand1);
+                let result = quad.result.clone();
+                self.define(&result, number);
+                return false;
+            }
+            _ => {}
+        }
+
+        let Some(key) = self.expression_key(quad) else {
+            // Assignments to variables and inputs produce a value nothing else shares
+            if let Some(result) = quad.definition().cloned() {
+                let number = self.fresh();
+                self.define(&result, number);
+            }
+            return false;
+        };
+
+        let result = quad.result.clone();
+        let available = self
+            .expressions
+            .get(&key)
+            .and_then(|&number| Some((number, self.holder(number)?.clone())));
+        if let Some((number, holder)) = available {
+            quad.operation = Operation::Assign;
+            quad.operand1 = holder;
+            quad.operand2 = Operand::Empty;
+            let number = match result {
+                Operand::TempVariable(_) => number,
+                _ => self.fresh(),
+            };
+            self.define(&result, number);
+            return true;
+        }
+
+        let number = self.fresh();
+        self.expressions.insert(key, number);
+        self.define(&result, number);
+        false
+    }
+}
+
+impl LocalValueNumbering {
+    pub fn new() -> Self {
+        LocalValueNumbering
+    }
+}
+
+impl Pass for LocalValueNumbering {
+    fn name(&self) -> &'static str {
+        "local-value-numbering"
+    }
+
+    /// Returns the number of computations replaced by copies
+    fn run(&mut self, program: &mut QuadrupleProgram) -> usize {
+        let mut cfg = ControlFlowGraph::build(program);
+        let mut replaced = 0;
+        for block in &mut cfg.blocks {
+            let mut numbering = BlockNumbering::default();
+            for quad in &mut block.quadruples {
+                if numbering.visit(quad) {
+                    replaced += 1;
+                }
+            }
+        }
+        *program = cfg.to_program();
+        replaced
+    }
+}
+
+fn is_commutative(operation: &Operation) -> bool {
+    matches!(
+        operation,
+        Operation::Add
+            | Operation::Multiply
+            | Operation::Equal
+            | Operation::NotEqual
+            | Operation::And
+            | Operation::Or
+    )
+}
@@ -16,9 +16,59 @@ impl<T> Located<T> {
 pub struct Program {
     pub name: String,
     pub declarations: Vec<Declaration>,
+    pub subprograms: Vec<Located<Subprogram>>,
     pub statements: Vec<Statement>,
 }
 
+/// A function or procedure declared between the global declarations and `BeginPg`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subprogram {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    /// Type of the value a function returns, `None` for a procedure
+    pub return_type: Option<Type>,
+    pub declarations: Vec<Declaration>,
+    pub statements: Vec<Statement>,
+}
+
+impl Subprogram {
+    /// Names of the parameters and local declarations
+    pub fn local_names(&self) -> Vec<&str> {
+        let parameters = self.parameters.iter().map(|parameter| parameter.node.name.as_str());
+        let declared = self
+            .declarations
+            .iter()
+            .flat_map(|declaration| declaration.node.names());
+        parameters.chain(declared).collect()
+    }
+
+    /// Name a local is stored under once the program is compiled, it can't clash with
+    /// globals or with the locals of other subprograms since `.` isn't allowed in identifiers
+    pub fn qualified_name(&self, name: &str) -> String {
+        format!("{}.{}", self.name, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterKind {
+    pub name: String,
+    pub typ: Type,
+    /// Size of an array parameter, `None` for a scalar
+    pub size: Option<usize>,
+}
+
+impl ParameterKind {
+    /// Type as written in a parameter list, `Int` or `[Int; 3]`
+    pub fn type_name(&self) -> String {
+        match self.size {
+            Some(size) => format!("[{}; {}]", self.typ, size),
+            None => self.typ.to_string(),
+        }
+    }
+}
+
+pub type Parameter = Located<ParameterKind>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeclarationKind {
     Variable(Vec<String>, Type),
@@ -28,6 +78,21 @@ pub enum DeclarationKind {
     Constant(String, Type, Literal),
 }
 
+impl DeclarationKind {
+    /// Names the declaration introduces, in order
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            DeclarationKind::Variable(names, _)
+            | DeclarationKind::Array(names, _, _)
+            | DeclarationKind::VariableWithInit(names, _, _)
+            | DeclarationKind::ArrayWithInit(names, _, _, _) => {
+                names.iter().map(String::as_str).collect()
+            }
+            DeclarationKind::Constant(name, _, _) => vec![name.as_str()],
+        }
+    }
+}
+
 pub type Declaration = Located<DeclarationKind>;
 
 /// Data types in MiniSoft
@@ -79,10 +144,15 @@ pub enum StatementKind {
     IfThen(Expression, Vec<Statement>),
     IfThenElse(Expression, Vec<Statement>, Vec<Statement>),
     DoWhile(Vec<Statement>, Expression),
+    While(Expression, Vec<Statement>),
     For(Expression, Expression, Expression, Expression, Vec<Statement>),
     Input(Expression),
     Output(Vec<Expression>),
-    Scope(Vec<Statement>),
+    /// Block declaring names that are only visible inside it
+    Scope(Vec<Declaration>, Vec<Statement>),
+    /// Call of a procedure, or of a function whose value is discarded
+    Call(String, Vec<Expression>),
+    Return(Option<Expression>),
     Empty,
 }
 
@@ -95,6 +165,20 @@ pub enum ExpressionKind {
     Literal(Literal),
     BinaryOp(Box<Expression>, Operator, Box<Expression>),
     UnaryOp(UnaryOperator, Box<Expression>),
+    Call(String, Vec<Expression>),
+}
+
+/// Name of the builtin string length, which no subprogram may take
+pub const LENGTH_BUILTIN: &str = "len";
+
+impl ExpressionKind {
+    /// A call of `name`, `len` with a single argument being the builtin string length
+    pub fn call(name: String, mut args: Vec<Expression>) -> Self {
+        if name == LENGTH_BUILTIN && args.len() == 1 {
+            return ExpressionKind::UnaryOp(UnaryOperator::Length, Box::new(args.remove(0)));
+        }
+        ExpressionKind::Call(name, args)
+    }
 }
 
 pub type Expression = Located<ExpressionKind>;
@@ -151,6 +235,8 @@ pub enum Operator {
 #[derive(Debug, Clone, PartialEq)]
 pub enum UnaryOperator {
     Not,
+    /// `len(s)`, the number of characters of a string
+    Length,
 }
 
 impl LiteralKind {
@@ -0,0 +1,461 @@
+use super::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, LiteralKind, Located, Operator,
+    Program, Statement, StatementKind, Subprogram, UnaryOperator,
+};
+use crate::lexer::lexer_core::TokenWithMetaData;
+use crate::lexer::token::Token;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const INDENT: &str = "    ";
+
+/// Layout choices left to whoever formats a program
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    /// Writes `let a, b: Int;` as `let a: Int;` followed by `let b: Int;`
+    pub one_declaration_per_line: bool,
+}
+
+/// MiniSoft source for a program, laid out the canonical way: one statement per line,
+/// blocks indented by four spaces and only the parentheses the grammar needs
+pub fn format_program(program: &Program) -> String {
+    format_program_with(program, &[], &FormatOptions::default())
+}
+
+/// Same as `format_program`, keeping the comments found in `tokens`, the output of
+/// `tokenize_with_comments` for the source the program was parsed from.
+/// Comments that followed code on the same line stay at the end of its line, the
+/// others get a line of their own before whatever came after them.
+pub fn format_program_with(
+    program: &Program,
+    tokens: &[TokenWithMetaData],
+    options: &FormatOptions,
+) -> String {
+    let mut formatter = Formatter {
+        tokens,
+        comments: (0..tokens.len())
+            .filter(|&i| tokens[i].kind == Token::Comment)
+            .collect(),
+        options,
+        out: String::new(),
+    };
+    formatter.program(program);
+    formatter.out
+}
+
+struct Formatter<'a> {
+    tokens: &'a [TokenWithMetaData],
+    /// Positions in `tokens` of the comments not written yet
+    comments: VecDeque<usize>,
+    options: &'a FormatOptions,
+    out: String,
+}
+
+impl Formatter<'_> {
+    fn program(&mut self, program: &Program) {
+        let main = self.first(Token::MainPrgm, 0);
+        self.comments_before(main, 0);
+        self.out.push_str(&format!("MainPrgm {};\n", program.name));
+        self.comments_before(self.first(Token::Var, main), 0);
+        self.out.push_str("Var\n");
+        self.declarations(&program.declarations, 0);
+        for subprogram in &program.subprograms {
+            self.subprogram(subprogram);
+        }
+        let begin = self.first(Token::BeginPg, main);
+        self.comments_before(begin, 0);
+        self.out.push_str("BeginPg\n");
+        self.comments_before(self.first(Token::OpenBrace, begin), 0);
+        let end = self.first(Token::EndPg, begin);
+        self.block(&program.statements, 0, self.last(Token::CloseBrace, end));
+        self.out.push('\n');
+        self.comments_before(end, 0);
+        self.out.push_str("EndPg;\n");
+        self.comments_before(usize::MAX, 0);
+    }
+
+    fn declarations(&mut self, declarations: &[Declaration], depth: usize) {
+        for declaration in declarations {
+            self.comments_before(declaration.span.start, depth);
+            let declarations = if self.options.one_declaration_per_line {
+                split_declaration(&declaration.node)
+            } else {
+                vec![declaration.node.clone()]
+            };
+            for declaration in &declarations {
+                self.out.push_str(&INDENT.repeat(depth));
+                self.out.push_str(&format_declaration(declaration));
+                self.out.push('\n');
+            }
+        }
+    }
+
+    /// Header on its own line, then the local `Var` section and the body
+    fn subprogram(&mut self, subprogram: &Located<Subprogram>) {
+        let start = subprogram.span.start;
+        self.comments_before(start, 0);
+        let node = &subprogram.node;
+        let parameters: Vec<String> = node
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}: {}", parameter.node.name, parameter.node.type_name()))
+            .collect();
+        match &node.return_type {
+            Some(ty) => self.out.push_str(&format!(
+                "Function {}({}): {}\n",
+                node.name,
+                parameters.join(", "),
+                ty
+            )),
+            None => self.out.push_str(&format!(
+                "Procedure {}({})\n",
+                node.name,
+                parameters.join(", ")
+            )),
+        }
+        let var = self.first(Token::Var, start);
+        self.comments_before(var, 0);
+        self.out.push_str("Var\n");
+        self.declarations(&node.declarations, 0);
+        // Array initializers have braces too, the body starts after the declarations
+        let body = node
+            .declarations
+            .last()
+            .map_or(var, |declaration| declaration.span.end);
+        self.comments_before(self.first(Token::OpenBrace, body), 0);
+        self.block(
+            &node.statements,
+            0,
+            self.last(Token::CloseBrace, subprogram.span.end),
+        );
+        self.out.push('\n');
+    }
+
+    /// A `{ }` block whose braces sit at `depth` and whose `}` starts at `end` in the
+    /// source, without a trailing newline
+    fn block(&mut self, statements: &[Statement], depth: usize, end: usize) {
+        match statements {
+            // A block starting with declarations parses to a single scope statement
+            [
+                Located {
+                    node: StatementKind::Scope(declarations, body),
+                    ..
+                },
+            ] if !declarations.is_empty() => self.scope(declarations, body, depth, end),
+            _ => self.scope(&[], statements, depth, end),
+        }
+    }
+
+    /// Same as `block` for the declarations and statements of a scope
+    fn scope(
+        &mut self,
+        declarations: &[Declaration],
+        statements: &[Statement],
+        depth: usize,
+        end: usize,
+    ) {
+        self.out.push_str("{\n");
+        self.declarations(declarations, depth + 1);
+        for statement in statements {
+            self.statement(statement, depth + 1);
+        }
+        self.comments_before(end, depth + 1);
+        self.out.push_str(&INDENT.repeat(depth));
+        self.out.push('}');
+    }
+
+    fn statement(&mut self, statement: &Statement, depth: usize) {
+        if let StatementKind::Empty = statement.node {
+            return;
+        }
+        self.comments_before(statement.span.start, depth);
+        self.out.push_str(&INDENT.repeat(depth));
+        match &statement.node {
+            StatementKind::Assignment(target, value) => self.out.push_str(&format!(
+                "{} := {};",
+                format_expression(target),
+                format_expression(value)
+            )),
+            StatementKind::IfThen(condition, then_block) => {
+                self.out
+                    .push_str(&format!("if ({}) then ", format_expression(condition)));
+                self.block(then_block, depth, self.closing_brace(statement));
+            }
+            StatementKind::IfThenElse(condition, then_block, else_block) => {
+                self.out
+                    .push_str(&format!("if ({}) then ", format_expression(condition)));
+                // Nested `else` keywords all come before the last statement of the block
+                let then_end = then_block
+                    .last()
+                    .map_or(condition.span.end, |statement| statement.span.end);
+                let else_keyword = self.first(Token::Else, then_end);
+                self.block(
+                    then_block,
+                    depth,
+                    self.last(Token::CloseBrace, else_keyword),
+                );
+                self.inline_comments_before(else_keyword);
+                self.out.push_str(" else ");
+                self.block(else_block, depth, self.closing_brace(statement));
+            }
+            StatementKind::DoWhile(body, condition) => {
+                self.out.push_str("do ");
+                self.block(
+                    body,
+                    depth,
+                    self.last(Token::CloseBrace, condition.span.start),
+                );
+                self.inline_comments_before(self.last(Token::While, condition.span.start));
+                self.out
+                    .push_str(&format!(" while ({});", format_expression(condition)));
+            }
+            StatementKind::While(condition, body) => {
+                self.out
+                    .push_str(&format!("while ({}) ", format_expression(condition)));
+                self.block(body, depth, self.closing_brace(statement));
+            }
+            StatementKind::For(iterator, init, end, step, body) => {
+                self.out.push_str(&format!(
+                    "for {} from {} to {} step {} ",
+                    format_expression(iterator),
+                    format_expression(init),
+                    format_expression(end),
+                    format_expression(step)
+                ));
+                self.block(body, depth, self.closing_brace(statement));
+            }
+            StatementKind::Input(target) => self
+                .out
+                .push_str(&format!("input({});", format_expression(target))),
+            StatementKind::Output(expressions) => self
+                .out
+                .push_str(&format!("output({});", format_list(expressions))),
+            StatementKind::Scope(declarations, statements) => self.scope(
+                declarations,
+                statements,
+                depth,
+                self.closing_brace(statement),
+            ),
+            StatementKind::Call(name, arguments) => {
+                self.out
+                    .push_str(&format!("{}({});", name, format_list(arguments)))
+            }
+            StatementKind::Return(Some(value)) => self
+                .out
+                .push_str(&format!("return {};", format_expression(value))),
+            StatementKind::Return(None) => self.out.push_str("return;"),
+            StatementKind::Empty => {}
+        }
+        self.out.push('\n');
+    }
+
+    /// Where the `}` ending a statement starts
+    fn closing_brace(&self, statement: &Statement) -> usize {
+        self.last(Token::CloseBrace, statement.span.end)
+    }
+
+    /// Writes the comments starting before `position` in the source
+    fn comments_before(&mut self, position: usize, depth: usize) {
+        while let Some(&index) = self.comments.front() {
+            let comment = &self.tokens[index];
+            if comment.span.start >= position {
+                break;
+            }
+            self.comments.pop_front();
+            let trailing = index > 0 && end_line(&self.tokens[index - 1]) == comment.line;
+            if trailing && self.out.ends_with('\n') {
+                self.out.pop();
+                self.out.push(' ');
+            } else {
+                self.out.push_str(&INDENT.repeat(depth));
+            }
+            self.out.push_str(&comment.value);
+            self.out.push('\n');
+        }
+    }
+
+    /// Writes the comments starting before `position` where the line goes on after them
+    fn inline_comments_before(&mut self, position: usize) {
+        while let Some(&index) = self.comments.front() {
+            if self.tokens[index].span.start >= position {
+                break;
+            }
+            self.comments.pop_front();
+            self.out.push(' ');
+            self.out.push_str(&self.tokens[index].value);
+        }
+    }
+
+    /// Where the first `kind` token at or after `position` starts
+    fn first(&self, kind: Token, position: usize) -> usize {
+        self.tokens
+            .iter()
+            .find(|token| token.kind == kind && token.span.start >= position)
+            .map_or(usize::MAX, |token| token.span.start)
+    }
+
+    /// Where the last `kind` token before `position` starts
+    fn last(&self, kind: Token, position: usize) -> usize {
+        self.tokens
+            .iter()
+            .rev()
+            .find(|token| token.kind == kind && token.span.start < position)
+            .map_or(usize::MAX, |token| token.span.start)
+    }
+}
+
+/// Line on which a token ends, block comments may cover several
+fn end_line(token: &TokenWithMetaData) -> usize {
+    token.line + token.value.matches('\n').count()
+}
+
+/// One declaration for each of the names a declaration lists
+fn split_declaration(declaration: &DeclarationKind) -> Vec<DeclarationKind> {
+    let names = match declaration {
+        DeclarationKind::Variable(names, _)
+        | DeclarationKind::Array(names, ..)
+        | DeclarationKind::VariableWithInit(names, ..)
+        | DeclarationKind::ArrayWithInit(names, ..) => names,
+        DeclarationKind::Constant(..) => return vec![declaration.clone()],
+    };
+    names
+        .iter()
+        .map(|name| {
+            let mut single = declaration.clone();
+            if let DeclarationKind::Variable(names, _)
+            | DeclarationKind::Array(names, ..)
+            | DeclarationKind::VariableWithInit(names, ..)
+            | DeclarationKind::ArrayWithInit(names, ..) = &mut single
+            {
+                *names = vec![name.clone()];
+            }
+            single
+        })
+        .collect()
+}
+
+fn format_declaration(declaration: &DeclarationKind) -> String {
+    match declaration {
+        DeclarationKind::Variable(names, ty) => format!("let {}: {};", names.join(", "), ty),
+        DeclarationKind::Array(names, ty, size) => {
+            format!("let {}: [{}; {}];", names.join(", "), ty, size)
+        }
+        DeclarationKind::VariableWithInit(names, ty, value) => format!(
+            "let {}: {} = {};",
+            names.join(", "),
+            ty,
+            format_expression(value)
+        ),
+        DeclarationKind::ArrayWithInit(names, ty, size, values) => format!(
+            "let {}: [{}; {}] = {{{}}};",
+            names.join(", "),
+            ty,
+            size,
+            format_list(values)
+        ),
+        DeclarationKind::Constant(name, ty, value) => format!(
+            "@define Const {}: {} = {};",
+            name,
+            ty,
+            format_literal(&value.node)
+        ),
+    }
+}
+
+fn format_list(expressions: &[Expression]) -> String {
+    expressions
+        .iter()
+        .map(format_expression)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Binding strength of each grammar level, from `LogicalExpr` up to `PrimaryExpr`
+fn precedence(expression: &Expression) -> u8 {
+    match &expression.node {
+        ExpressionKind::BinaryOp(_, operator, _) => match operator {
+            Operator::And | Operator::Or => 1,
+            Operator::GreaterThan
+            | Operator::LessThan
+            | Operator::GreaterEqual
+            | Operator::LessEqual
+            | Operator::Equal
+            | Operator::NotEqual => 3,
+            Operator::Add | Operator::Subtract => 4,
+            Operator::Multiply | Operator::Divide => 5,
+        },
+        ExpressionKind::UnaryOp(UnaryOperator::Not, _) => 2,
+        _ => 6,
+    }
+}
+
+/// An operand wrapped in parentheses when it binds less tightly than `minimum`
+fn format_operand(expression: &Expression, minimum: u8) -> String {
+    let text = format_expression(expression);
+    if precedence(expression) < minimum {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+pub fn format_expression(expression: &Expression) -> String {
+    match &expression.node {
+        ExpressionKind::Identifier(name) => name.clone(),
+        ExpressionKind::ArrayAccess(name, index) => {
+            format!("{}[{}]", name, format_expression(index))
+        }
+        ExpressionKind::Literal(literal) => format_literal(&literal.node),
+        ExpressionKind::BinaryOp(left, operator, right) => {
+            let level = precedence(expression);
+            // Every level is left associative, a right operand at the same level needs parentheses
+            format!(
+                "{} {} {}",
+                format_operand(left, level),
+                operator_symbol(operator),
+                format_operand(right, level + 1)
+            )
+        }
+        // `!a > b` already negates the whole comparison, the parentheses only make it obvious
+        ExpressionKind::UnaryOp(UnaryOperator::Not, operand) => {
+            format!("!{}", format_operand(operand, 6))
+        }
+        ExpressionKind::UnaryOp(UnaryOperator::Length, operand) => {
+            format!("len({})", format_expression(operand))
+        }
+        ExpressionKind::Call(name, arguments) => format!("{}({})", name, format_list(arguments)),
+    }
+}
+
+fn operator_symbol(operator: &Operator) -> &'static str {
+    match operator {
+        Operator::Add => "+",
+        Operator::Subtract => "-",
+        Operator::Multiply => "*",
+        Operator::Divide => "/",
+        Operator::GreaterThan => ">",
+        Operator::LessThan => "<",
+        Operator::GreaterEqual => ">=",
+        Operator::LessEqual => "<=",
+        Operator::Equal => "==",
+        Operator::NotEqual => "!=",
+        Operator::And => "AND",
+        Operator::Or => "OR",
+    }
+}
+
+/// Negative numbers only lex inside parentheses, and floats need their decimal point
+fn format_literal(literal: &LiteralKind) -> String {
+    let text = match literal {
+        LiteralKind::Int(value) => value.to_string(),
+        LiteralKind::Float(value) if value.fract() == 0.0 => format!("{:.1}", value),
+        LiteralKind::Float(value) => value.to_string(),
+        LiteralKind::String(_) => return LiteralKind::format_literal(literal),
+    };
+    if text.starts_with('-') {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
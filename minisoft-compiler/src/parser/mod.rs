@@ -1,4 +1,5 @@
 pub mod ast;
 pub mod error;
+pub mod formatter;
 pub mod parser_core;
 pub mod pretty_print;
@@ -5,7 +5,7 @@ mod grammar_parser {
 
 use crate::lexer::lexer_core::TokenWithMetaData;
 use crate::lexer::token::Token;
-use crate::parser::ast::Program;
+use crate::parser::ast::{Declaration, Program, Statement};
 use crate::parser::error::{
     SyntaxError,
     convert_lalrpop_error,
@@ -34,4 +34,24 @@ pub fn parse(tokens: Vec<TokenWithMetaData>, source: &str) -> Result<Program, Sy
         Ok(located_program) => Ok(located_program.into_inner()),
         Err(e) => Err(convert_lalrpop_error(e, Some(source))),
     }
-}
\ No newline at end of file
+}
+
+/// Parses the tokens of a single declaration, such as a line typed in the REPL
+pub fn parse_declaration(
+    tokens: Vec<TokenWithMetaData>,
+    source: &str,
+) -> Result<Declaration, SyntaxError> {
+    grammar_parser::DeclarationParser::new()
+        .parse(tokenize_for_lalrpop(tokens))
+        .map_err(|e| convert_lalrpop_error(e, Some(source)))
+}
+
+/// Parses the tokens of a single statement, such as a line typed in the REPL
+pub fn parse_statement(
+    tokens: Vec<TokenWithMetaData>,
+    source: &str,
+) -> Result<Statement, SyntaxError> {
+    grammar_parser::StatementParser::new()
+        .parse(tokenize_for_lalrpop(tokens))
+        .map_err(|e| convert_lalrpop_error(e, Some(source)))
+}
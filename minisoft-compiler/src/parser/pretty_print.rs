@@ -1,154 +1,199 @@
-use super::ast::{Declaration, DeclarationKind, Expression, ExpressionKind, Program, Statement, StatementKind};
+use super::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, Located, Program, Statement,
+    StatementKind, Subprogram,
+};
 
-impl Program {
+/// One node of the printed syntax tree: what is shown for it and its children
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn leaf(label: String) -> Self {
+        TreeNode { label, children: vec![] }
+    }
+
+    fn new(label: String, children: Vec<TreeNode>) -> Self {
+        TreeNode { label, children }
+    }
+
+    fn print(&self, prefix: &str, is_last: bool) {
+        let branch = if is_last { "└──" } else { "├──" };
+        let new_prefix = if is_last { format!("{}    ", prefix) } else { format!("{}│   ", prefix) };
+        println!("{}{} {}", prefix, branch, self.label);
+        self.print_children(&new_prefix);
+    }
+
+    /// Prints the node and the tree below it to stdout
     pub fn pretty_print(&self) {
-        println!("Program: {}", self.name);
-        println!("├── Declarations:");
-        for (i, decl) in self.declarations.iter().enumerate() {
-            let is_last = i == self.declarations.len() - 1 && self.statements.is_empty();
-            decl.pretty_print("│   ", is_last);
+        println!("{}", self.label);
+        self.print_children("");
+    }
+
+    fn print_children(&self, prefix: &str) {
+        for (i, child) in self.children.iter().enumerate() {
+            child.print(prefix, i == self.children.len() - 1);
         }
-        println!("└── Statements:");
-        for (i, stmt) in self.statements.iter().enumerate() {
-            let is_last = i == self.statements.len() - 1;
-            stmt.pretty_print("    ", is_last);
+    }
+}
+
+impl Program {
+    pub fn pretty_print(&self) {
+        self.to_tree().pretty_print();
+    }
+
+    /// Builds the tree shared by the console printer and the graph exporters
+    pub fn to_tree(&self) -> TreeNode {
+        let mut children = vec![TreeNode::new(
+            "Declarations:".to_string(),
+            self.declarations.iter().map(Declaration::to_tree).collect(),
+        )];
+        if !self.subprograms.is_empty() {
+            children.push(TreeNode::new(
+                "Subprograms:".to_string(),
+                self.subprograms.iter().map(Located::<Subprogram>::to_tree).collect(),
+            ));
         }
+        children.push(TreeNode::new(
+            "Statements:".to_string(),
+            self.statements.iter().map(Statement::to_tree).collect(),
+        ));
+        TreeNode::new(format!("Program: {}", self.name), children)
+    }
+}
+
+impl Located<Subprogram> {
+    pub fn to_tree(&self) -> TreeNode {
+        let subprogram = &self.node;
+        let parameters: Vec<String> = subprogram
+            .parameters
+            .iter()
+            .map(|parameter| format!("{}: {}", parameter.node.name, parameter.node.type_name()))
+            .collect();
+        let label = match &subprogram.return_type {
+            Some(ty) => format!("Function: {}({}): {}", subprogram.name, parameters.join(", "), ty),
+            None => format!("Procedure: {}({})", subprogram.name, parameters.join(", ")),
+        };
+        TreeNode::new(
+            label,
+            vec![
+                TreeNode::new(
+                    "Declarations:".to_string(),
+                    subprogram.declarations.iter().map(Declaration::to_tree).collect(),
+                ),
+                TreeNode::new(
+                    "Statements:".to_string(),
+                    subprogram.statements.iter().map(Statement::to_tree).collect(),
+                ),
+            ],
+        )
     }
 }
 
 impl Declaration {
-    fn pretty_print(&self, prefix: &str, is_last: bool) {
-        let branch = if is_last { "└──" } else { "├──" };
-        let new_prefix = if is_last { format!("{}    ", prefix) } else { format!("{}│   ", prefix) };
+    pub fn to_tree(&self) -> TreeNode {
         match &self.node {
             DeclarationKind::Variable(names, ty) => {
-                println!("{}{} Variable: {:?} : {}", prefix, branch, names, ty);
+                TreeNode::leaf(format!("Variable: {:?} : {}", names, ty))
             }
             DeclarationKind::Array(names, ty, size) => {
-                println!("{}{} Array: {:?} : {} [{}]", prefix, branch, names, ty, size);
-            }
-            DeclarationKind::VariableWithInit(names, ty, expr) => {
-                println!("{}{} VariableWithInit: {:?} : {}", prefix, branch, names, ty);
-                expr.pretty_print(&new_prefix, true);
-            }
-            DeclarationKind::ArrayWithInit(names, ty, size, exprs) => {
-                println!("{}{} ArrayWithInit: {:?} : {} [{}]", prefix, branch, names, ty, size);
-                for (i, expr) in exprs.iter().enumerate() {
-                    expr.pretty_print(&new_prefix, i == exprs.len() - 1);
-                }
-            }
+                TreeNode::leaf(format!("Array: {:?} : {} [{}]", names, ty, size))
+            }
+            DeclarationKind::VariableWithInit(names, ty, expr) => TreeNode::new(
+                format!("VariableWithInit: {:?} : {}", names, ty),
+                vec![expr.to_tree()],
+            ),
+            DeclarationKind::ArrayWithInit(names, ty, size, exprs) => TreeNode::new(
+                format!("ArrayWithInit: {:?} : {} [{}]", names, ty, size),
+                exprs.iter().map(Expression::to_tree).collect(),
+            ),
             DeclarationKind::Constant(name, ty, lit) => {
-                println!("{}{} Constant: {} : {} = {:?}", prefix, branch, name, ty, lit.node);
+                TreeNode::leaf(format!("Constant: {} : {} = {:?}", name, ty, lit.node))
             }
         }
     }
 }
 
 impl Statement {
-    fn pretty_print(&self, prefix: &str, is_last: bool) {
-        let branch = if is_last { "└──" } else { "├──" };
-        let new_prefix = if is_last { format!("{}    ", prefix) } else { format!("{}│   ", prefix) };
+    pub fn to_tree(&self) -> TreeNode {
+        let block = |stmts: &Vec<Statement>| stmts.iter().map(Statement::to_tree).collect::<Vec<_>>();
         match &self.node {
-            StatementKind::Assignment(lhs, rhs) => {
-                println!("{}{} Assignment:", prefix, branch);
-                lhs.pretty_print(&new_prefix, false);
-                rhs.pretty_print(&new_prefix, true);
-            }
+            StatementKind::Assignment(lhs, rhs) => TreeNode::new(
+                "Assignment:".to_string(),
+                vec![lhs.to_tree(), rhs.to_tree()],
+            ),
             StatementKind::IfThen(cond, stmts) => {
-                println!("{}{} IfThen:", prefix, branch);
-                cond.pretty_print(&new_prefix, false);
-                for (i, stmt) in stmts.iter().enumerate() {
-                    stmt.pretty_print(&new_prefix, i == stmts.len() - 1);
-                }
-            }
-            StatementKind::IfThenElse(cond, then_stmts, else_stmts) => {
-                println!("{}{} IfThenElse:", prefix, branch);
-                cond.pretty_print(&new_prefix, false);
-
-                // Then branch
-                let then_prefix = format!("{}{}", new_prefix, "├── Then:");
-                println!("{}", then_prefix);
-                let then_child_prefix = if else_stmts.is_empty() && then_stmts.len() > 0 {
-                    format!("{}    ", new_prefix)
-                } else {
-                    format!("{}│   ", new_prefix)
-                };
-                for (i, stmt) in then_stmts.iter().enumerate() {
-                    stmt.pretty_print(&then_child_prefix, i == then_stmts.len() - 1);
-                }
-
-                // Else branch
-                let else_prefix = format!("{}{}", new_prefix, "└── Else:");
-                println!("{}", else_prefix);
-                let else_child_prefix = format!("{}    ", new_prefix);
-                for (i, stmt) in else_stmts.iter().enumerate() {
-                    stmt.pretty_print(&else_child_prefix, i == else_stmts.len() - 1);
-                }
-            }
+                let mut children = vec![cond.to_tree()];
+                children.extend(block(stmts));
+                TreeNode::new("IfThen:".to_string(), children)
+            }
+            StatementKind::IfThenElse(cond, then_stmts, else_stmts) => TreeNode::new(
+                "IfThenElse:".to_string(),
+                vec![
+                    cond.to_tree(),
+                    TreeNode::new("Then:".to_string(), block(then_stmts)),
+                    TreeNode::new("Else:".to_string(), block(else_stmts)),
+                ],
+            ),
             StatementKind::DoWhile(stmts, cond) => {
-                println!("{}{} DoWhile:", prefix, branch);
-                for (_i, stmt) in stmts.iter().enumerate() {
-                    stmt.pretty_print(&new_prefix, false);
-                }
-                cond.pretty_print(&new_prefix, true);
-            }
-            StatementKind::For(init, cond, step, end, stmts) => {
-                println!("{}{} For:", prefix, branch);
-                init.pretty_print(&new_prefix, false);
-                cond.pretty_print(&new_prefix, false);
-                step.pretty_print(&new_prefix, false);
-                end.pretty_print(&new_prefix, false);
-                for (i, stmt) in stmts.iter().enumerate() {
-                    stmt.pretty_print(&new_prefix, i == stmts.len() - 1);
-                }
-            }
-            StatementKind::Input(expr) => {
-                println!("{}{} Input:", prefix, branch);
-                expr.pretty_print(&new_prefix, true);
-            }
-            StatementKind::Output(exprs) => {
-                println!("{}{} Output:", prefix, branch);
-                for (i, expr) in exprs.iter().enumerate() {
-                    expr.pretty_print(&new_prefix, i == exprs.len() - 1);
-                }
-            }
-            StatementKind::Scope(stmts) => {
-                println!("{}{} Scope:", prefix, branch);
-                for (i, stmt) in stmts.iter().enumerate() {
-                    stmt.pretty_print(&new_prefix, i == stmts.len() - 1);
-                }
-            }
-            StatementKind::Empty => {
-                println!("{}{} Empty", prefix, branch);
-            }
+                let mut children = block(stmts);
+                children.push(cond.to_tree());
+                TreeNode::new("DoWhile:".to_string(), children)
+            }
+            StatementKind::While(cond, stmts) => {
+                let mut children = vec![cond.to_tree()];
+                children.extend(block(stmts));
+                TreeNode::new("While:".to_string(), children)
+            }
+            StatementKind::For(iterator, from, to, step, stmts) => {
+                let mut children = vec![iterator.to_tree(), from.to_tree(), to.to_tree(), step.to_tree()];
+                children.extend(block(stmts));
+                TreeNode::new("For:".to_string(), children)
+            }
+            StatementKind::Input(expr) => TreeNode::new("Input:".to_string(), vec![expr.to_tree()]),
+            StatementKind::Output(exprs) => TreeNode::new(
+                "Output:".to_string(),
+                exprs.iter().map(Expression::to_tree).collect(),
+            ),
+            StatementKind::Scope(decls, stmts) => {
+                let mut children: Vec<TreeNode> = decls.iter().map(Declaration::to_tree).collect();
+                children.extend(block(stmts));
+                TreeNode::new("Scope:".to_string(), children)
+            }
+            StatementKind::Call(name, args) => TreeNode::new(
+                format!("Call: {}", name),
+                args.iter().map(Expression::to_tree).collect(),
+            ),
+            StatementKind::Return(value) => TreeNode::new(
+                "Return:".to_string(),
+                value.iter().map(Expression::to_tree).collect(),
+            ),
+            StatementKind::Empty => TreeNode::leaf("Empty".to_string()),
         }
     }
 }
 
 impl Expression {
-    fn pretty_print(&self, prefix: &str, is_last: bool) {
-        let branch = if is_last { "└──" } else { "├──" };
-        let new_prefix = if is_last { format!("{}    ", prefix) } else { format!("{}│   ", prefix) };
+    pub fn to_tree(&self) -> TreeNode {
         match &self.node {
-            ExpressionKind::Identifier(name) => {
-                println!("{}{} Identifier: {}", prefix, branch, name);
-            }
+            ExpressionKind::Identifier(name) => TreeNode::leaf(format!("Identifier: {}", name)),
             ExpressionKind::ArrayAccess(name, idx) => {
-                println!("{}{} ArrayAccess: {}", prefix, branch, name);
-                idx.pretty_print(&new_prefix, true);
-            }
-            ExpressionKind::Literal(lit) => {
-                println!("{}{} Literal: {:?}", prefix, branch, lit.node);
-            }
-            ExpressionKind::BinaryOp(lhs, op, rhs) => {
-                println!("{}{} BinaryOp: {:?}", prefix, branch, op);
-                lhs.pretty_print(&new_prefix, false);
-                rhs.pretty_print(&new_prefix, true);
+                TreeNode::new(format!("ArrayAccess: {}", name), vec![idx.to_tree()])
             }
+            ExpressionKind::Literal(lit) => TreeNode::leaf(format!("Literal: {:?}", lit.node)),
+            ExpressionKind::BinaryOp(lhs, op, rhs) => TreeNode::new(
+                format!("BinaryOp: {:?}", op),
+                vec![lhs.to_tree(), rhs.to_tree()],
+            ),
             ExpressionKind::UnaryOp(op, expr) => {
-                println!("{}{} UnaryOp: {:?}", prefix, branch, op);
-                expr.pretty_print(&new_prefix, true);
+                TreeNode::new(format!("UnaryOp: {:?}", op), vec![expr.to_tree()])
             }
+            ExpressionKind::Call(name, args) => TreeNode::new(
+                format!("Call: {}", name),
+                args.iter().map(Expression::to_tree).collect(),
+            ),
         }
     }
-}
\ No newline at end of file
+}
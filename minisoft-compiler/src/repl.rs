@@ -0,0 +1,172 @@
+use crate::codegen::generator::CodeGenerator;
+use crate::codegen::quadruple::QuadrupleProgram;
+use crate::lexer::error::LexicalError;
+use crate::lexer::lexer_core::{TokenWithMetaData, tokenize};
+use crate::lexer::token::Token;
+use crate::parser::ast::{Declaration, Statement};
+use crate::parser::error::SyntaxError;
+use crate::parser::parser_core::{parse_declaration, parse_statement};
+use crate::runtime::vm::VirtualMachine;
+use crate::runtime::{InputSource, Memory, OutputSink, RuntimeError};
+use crate::semantics::SemanticAnalyzer;
+use crate::semantics::error::SemanticError;
+use crate::semantics::symbol_table::{SymbolKind, SymbolTable};
+
+/// What a line of the REPL holds
+#[derive(Debug, Clone)]
+pub enum FragmentKind {
+    Declaration(Declaration),
+    Statement(Statement),
+}
+
+/// A declaration or statement accepted by the REPL, ready to execute
+#[derive(Debug)]
+pub struct Fragment {
+    pub source: String,
+    pub tokens: Vec<TokenWithMetaData>,
+    pub kind: FragmentKind,
+    /// Quadruples generated for this fragment alone
+    pub quadruples: QuadrupleProgram,
+    /// Position of the first of them among everything generated in the session
+    pub first_index: usize,
+    pub warnings: Vec<SemanticError>,
+}
+
+/// Why a fragment was rejected, the session is left as it was before it
+#[derive(Debug)]
+pub enum ReplError {
+    Lexical(Vec<LexicalError>),
+    Syntax(SyntaxError),
+    Semantic(Vec<SemanticError>),
+}
+
+/// A program built one declaration or statement at a time. Declarations, the symbol
+/// table and the values in memory carry over from one fragment to the next.
+pub struct Repl {
+    analyzer: SemanticAnalyzer,
+    generator: CodeGenerator,
+    memory: Memory,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl {
+            analyzer: SemanticAnalyzer::new(&String::new()),
+            generator: CodeGenerator::new(),
+            memory: Memory::new(),
+        }
+    }
+
+    pub fn symbol_table(&self) -> &SymbolTable {
+        self.analyzer.get_symbol_table()
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    /// Quadruples of every fragment accepted so far
+    pub fn quadruples(&self) -> &QuadrupleProgram {
+        &self.generator.program
+    }
+
+    /// Parses, checks and generates code for one `let`/`@define` declaration or one statement.
+    /// Storage for declared names is reserved right away, their initial values are only
+    /// stored once the fragment is executed.
+    pub fn compile(&mut self, source: &str) -> Result<Fragment, ReplError> {
+        let (tokens, lexical_errors) = tokenize(source);
+        if !lexical_errors.is_empty() {
+            return Err(ReplError::Lexical(lexical_errors));
+        }
+
+        let is_declaration = matches!(
+            tokens.first().map(|token| &token.kind),
+            Some(Token::Let | Token::Define)
+        );
+        let kind = if is_declaration {
+            parse_declaration(tokens.clone(), source).map(FragmentKind::Declaration)
+        } else {
+            parse_statement(tokens.clone(), source).map(FragmentKind::Statement)
+        }
+        .map_err(ReplError::Syntax)?;
+
+        let symbols = self.analyzer.get_symbol_table().clone();
+        self.analyzer.begin_fragment(source);
+        match &kind {
+            FragmentKind::Declaration(declaration) => {
+                self.analyzer.analyze_declaration(declaration)
+            }
+            FragmentKind::Statement(statement) => self.analyzer.analyze_statement(statement),
+        }
+        let (errors, warnings) = self.analyzer.take_diagnostics();
+        if !errors.is_empty() {
+            self.analyzer.restore_symbol_table(symbols);
+            return Err(ReplError::Semantic(errors));
+        }
+
+        self.declare_new_symbols();
+        let first_index = self.generator.program.quadruples.len();
+        match &kind {
+            FragmentKind::Declaration(declaration) => {
+                self.generator.generate_declaration(declaration)
+            }
+            FragmentKind::Statement(statement) => self.generator.generate_statement(statement),
+        }
+        let quadruples = QuadrupleProgram {
+            quadruples: self.generator.program.quadruples[first_index..].to_vec(),
+            next_temp: self.generator.program.next_temp,
+            next_label: self.generator.program.next_label,
+        };
+
+        Ok(Fragment {
+            source: source.to_string(),
+            tokens,
+            kind,
+            quadruples,
+            first_index,
+            warnings,
+        })
+    }
+
+    /// Runs the quadruples of a compiled fragment against the memory of the session
+    pub fn execute(
+        &mut self,
+        fragment: &Fragment,
+        input: &mut dyn InputSource,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), RuntimeError> {
+        let memory = std::mem::take(&mut self.memory);
+        let mut vm = VirtualMachine::new(&fragment.quadruples)
+            .with_source(&fragment.source)
+            .with_memory(memory);
+        let result = vm.run(input, output);
+        // Whatever ran before an error keeps its effect
+        self.memory = vm.into_memory();
+        result
+    }
+
+    /// Reserves storage for the symbols the fragment added, those declared by the blocks
+    /// of a statement included
+    fn declare_new_symbols(&mut self) {
+        for symbol in self.analyzer.get_symbol_table().get_all() {
+            let name = symbol.qualified_name();
+            if self.memory.get_type(&name).is_some() {
+                continue;
+            }
+            match symbol.kind {
+                SymbolKind::Array(size) => {
+                    self.memory.declare_array(&name, &symbol.symbol_type, size)
+                }
+                SymbolKind::Variable | SymbolKind::Constant => {
+                    self.memory.declare_variable(&name, &symbol.symbol_type)
+                }
+            }
+        }
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,251 @@
+use crate::codegen::quadruple::{Quadruple, QuadrupleProgram};
+use crate::runtime::error::RuntimeError;
+use crate::runtime::io::{BufferedInput, BufferedOutput, InputSource, OutputSink};
+use crate::runtime::memory::Value;
+use crate::runtime::vm::VirtualMachine;
+use crate::semantics::source_map::SourceMap;
+use crate::semantics::symbol_table::SymbolTable;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// Why the debugger handed control back to the caller
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+    /// Execution reached a statement on a line with a breakpoint
+    Breakpoint(usize),
+    /// The requested step completed
+    Step,
+    /// The program ran to completion
+    Halted,
+    /// Execution failed, the machine can't make further progress
+    Error(RuntimeError),
+}
+
+/// Snapshot of the machine at a stop, for display in the editor
+#[derive(Debug, Clone)]
+pub struct DebugSnapshot {
+    pub pc: usize,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub quadruple: Option<Quadruple>,
+    pub variables: Vec<(String, Value)>,
+    pub temps: Vec<(String, Value)>,
+    pub arrays: Vec<(String, Vec<Value>)>,
+}
+
+/// Source-level debugger driving a virtual machine one quadruple at a time
+pub struct Debugger<I: InputSource = BufferedInput, O: OutputSink = BufferedOutput> {
+    vm: VirtualMachine,
+    input: I,
+    output: O,
+    source_map: SourceMap,
+    breakpoints: BTreeSet<usize>,
+    error: Option<RuntimeError>,
+    at_entry: bool,
+}
+
+impl Debugger {
+    /// Creates a debugger over buffered input and output
+    pub fn new(
+        program: &QuadrupleProgram,
+        symbol_table: &SymbolTable,
+        source_code: &String,
+        inputs: Vec<String>,
+    ) -> Self {
+        Debugger::with_io(
+            program,
+            symbol_table,
+            source_code,
+            BufferedInput::new(inputs),
+            BufferedOutput::new(),
+        )
+    }
+}
+
+impl<I: InputSource, O: OutputSink> Debugger<I, O> {
+    pub fn with_io(
+        program: &QuadrupleProgram,
+        symbol_table: &SymbolTable,
+        source_code: &String,
+        input: I,
+        output: O,
+    ) -> Self {
+        let mut vm = VirtualMachine::new(program).with_source(source_code);
+        vm.load_symbols(symbol_table);
+
+        Debugger {
+            vm,
+            input,
+            output,
+            source_map: SourceMap::new(source_code),
+            breakpoints: BTreeSet::new(),
+            error: None,
+            at_entry: true,
+        }
+    }
+
+    pub fn set_breakpoint(&mut self, line: usize) {
+        self.breakpoints.insert(line);
+    }
+
+    pub fn clear_breakpoint(&mut self, line: usize) {
+        self.breakpoints.remove(&line);
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = &usize> {
+        self.breakpoints.iter()
+    }
+
+    pub fn output(&self) -> &O {
+        &self.output
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.vm.is_halted() || self.error.is_some()
+    }
+
+    /// Source line of the statement that will execute next
+    pub fn current_line(&self) -> Option<usize> {
+        self.statement_span(self.vm.pc())
+            .map(|span| self.source_map.get_line(&span))
+    }
+
+    /// Executes exactly one quadruple
+    pub fn step_quadruple(&mut self) -> StopReason {
+        if let Some(reason) = self.finished_reason() {
+            return reason;
+        }
+        match self.execute_one() {
+            Some(reason) => reason,
+            None if self.vm.is_halted() => StopReason::Halted,
+            None => StopReason::Step,
+        }
+    }
+
+    /// Executes quadruples until the next source statement begins
+    pub fn step_statement(&mut self) -> StopReason {
+        if let Some(reason) = self.finished_reason() {
+            return reason;
+        }
+        let start = self.statement_span(self.vm.pc());
+        loop {
+            if let Some(reason) = self.execute_one() {
+                return reason;
+            }
+            if self.vm.is_halted() {
+                return StopReason::Halted;
+            }
+            let current = self.statement_span(self.vm.pc());
+            if current.is_some() && current != start {
+                return StopReason::Step;
+            }
+        }
+    }
+
+    /// Runs until a breakpoint is hit, the program halts or an error occurs
+    pub fn continue_execution(&mut self) -> StopReason {
+        if let Some(reason) = self.finished_reason() {
+            return reason;
+        }
+        // A breakpoint on the very first statement stops before anything runs
+        if self.at_entry {
+            self.at_entry = false;
+            if let Some(line) = self.breakpoint_at(self.vm.pc()) {
+                return StopReason::Breakpoint(line);
+            }
+        }
+        let mut previous = self.statement_span(self.vm.pc());
+        loop {
+            if let Some(reason) = self.execute_one() {
+                return reason;
+            }
+            if self.vm.is_halted() {
+                return StopReason::Halted;
+            }
+            let current = self.statement_span(self.vm.pc());
+            if current.is_some()
+                && current != previous
+                && let Some(line) = self.breakpoint_at(self.vm.pc())
+            {
+                return StopReason::Breakpoint(line);
+            }
+            previous = current;
+        }
+    }
+
+    /// Captures the current program counter, source position and memory
+    pub fn inspect(&self) -> DebugSnapshot {
+        let pc = self.vm.pc();
+        let position = self
+            .vm
+            .quadruples()
+            .get(pc)
+            .and_then(|quad| quad.span.as_ref())
+            .map(|span| self.source_map.get_line_column(span));
+
+        let memory = self.vm.memory();
+        let mut variables: Vec<(String, Value)> = memory
+            .variables
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let mut temps: Vec<(String, Value)> = memory
+            .temps
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let mut arrays: Vec<(String, Vec<Value>)> = memory
+            .arrays
+            .iter()
+            .map(|(name, values)| (name.clone(), values.clone()))
+            .collect();
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        temps.sort_by(|a, b| a.0.cmp(&b.0));
+        arrays.sort_by(|a, b| a.0.cmp(&b.0));
+
+        DebugSnapshot {
+            pc,
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            quadruple: self.vm.quadruples().get(pc).cloned(),
+            variables,
+            temps,
+            arrays,
+        }
+    }
+
+    fn finished_reason(&self) -> Option<StopReason> {
+        if let Some(error) = &self.error {
+            return Some(StopReason::Error(error.clone()));
+        }
+        if self.vm.is_halted() {
+            return Some(StopReason::Halted);
+        }
+        None
+    }
+
+    fn execute_one(&mut self) -> Option<StopReason> {
+        self.at_entry = false;
+        match self.vm.step(&mut self.input, &mut self.output) {
+            Ok(()) => None,
+            Err(error) => {
+                self.error = Some(error.clone());
+                Some(StopReason::Error(error))
+            }
+        }
+    }
+
+    fn statement_span(&self, pc: usize) -> Option<Range<usize>> {
+        self.vm
+            .quadruples()
+            .get(pc)
+            .and_then(|quad| quad.statement_span.clone())
+    }
+
+    fn breakpoint_at(&self, pc: usize) -> Option<usize> {
+        let line = self
+            .statement_span(pc)
+            .map(|span| self.source_map.get_line(&span))?;
+        self.breakpoints.contains(&line).then_some(line)
+    }
+}
@@ -0,0 +1,224 @@
+use crate::error_reporter::format_code_context;
+use crate::error_reporter::ErrorReporter;
+use colored::Colorize;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    /// Division whose divisor evaluated to zero at runtime
+    DivisionByZero {
+        line: usize,
+        column: usize,
+    },
+
+    /// Array access with an index outside of the declared size
+    ArrayIndexOutOfBounds {
+        name: String,
+        index: i32,
+        size: usize,
+        line: usize,
+        column: usize,
+    },
+
+    /// Read of a variable or array element that was never assigned
+    UninitializedRead {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// `for` loop whose step evaluated to zero, the loop would never end
+    ZeroLoopStep {
+        line: usize,
+        column: usize,
+    },
+
+    /// Integer result outside of the 16-bit range accepted by the lexer
+    IntegerOverflow {
+        value: i64,
+        line: usize,
+        column: usize,
+    },
+
+    /// Input value that cannot be read as the target's type
+    InvalidInput {
+        value: String,
+        expected: String,
+        line: usize,
+        column: usize,
+    },
+
+    /// `input` executed after every supplied value was consumed
+    MissingInput {
+        line: usize,
+        column: usize,
+    },
+
+    /// Execution exceeded the configured step budget
+    StepLimitExceeded {
+        steps: usize,
+    },
+
+    /// Too many subprogram calls active at once, usually a recursion without a base case
+    CallDepthExceeded {
+        depth: usize,
+        line: usize,
+        column: usize,
+    },
+
+    /// Malformed quadruple program, e.g. a jump to an undefined label
+    InvalidProgram {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl ErrorReporter for RuntimeError {
+    fn report(&self, source_code: Option<&str>) -> String {
+        let mut result = String::new();
+
+        result.push_str(&format!(
+            "{}: {}\n",
+            "Runtime Error".red().bold(),
+            self.get_detailed_message()
+        ));
+
+        let (line, column) = self.get_location_info();
+        if line > 0 {
+            result.push_str(&format!(
+                "{} line {}, column {}\n",
+                "-->".blue(),
+                line,
+                column
+            ));
+
+            if let Some(source) = source_code {
+                let lines: Vec<&str> = source.lines().collect();
+                if line <= lines.len() {
+                    result.push_str(&format_code_context(
+                        lines[line - 1],
+                        column,
+                        self.get_token_length(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(suggestion) = self.get_suggestion() {
+            result.push_str(&format!("{}: {}\n", "Suggestion".cyan().bold(), suggestion));
+        }
+
+        result
+    }
+
+    fn get_suggestion(&self) -> Option<String> {
+        match self {
+            RuntimeError::DivisionByZero { .. } => {
+                Some("Check that the divisor is non-zero before dividing".to_string())
+            }
+            RuntimeError::ArrayIndexOutOfBounds { name, size, .. } => Some(format!(
+                "Array '{}' has size {}. Use indices from 0 to {}",
+                name,
+                size,
+                size.saturating_sub(1)
+            )),
+            RuntimeError::UninitializedRead { name, .. } => {
+                Some(format!("Assign a value to '{}' before reading it", name))
+            }
+            RuntimeError::ZeroLoopStep { .. } => {
+                Some("Use a positive step to count up or a negative step to count down".to_string())
+            }
+            RuntimeError::IntegerOverflow { .. } => Some(
+                "Int values must stay within -32768 to 32767. Consider using Float".to_string(),
+            ),
+            RuntimeError::InvalidInput { expected, .. } => {
+                Some(format!("Enter a valid {} value", expected))
+            }
+            RuntimeError::MissingInput { .. } => {
+                Some("Provide one input value for every input statement executed".to_string())
+            }
+            RuntimeError::StepLimitExceeded { .. } => {
+                Some("Check that every loop condition eventually becomes false".to_string())
+            }
+            RuntimeError::CallDepthExceeded { .. } => Some(
+                "Check that every recursive call eventually reaches a case that returns without calling again"
+                    .to_string(),
+            ),
+            RuntimeError::InvalidProgram { .. } => None,
+        }
+    }
+
+    fn get_error_name(&self) -> String {
+        "Runtime Error".to_string()
+    }
+
+    fn get_location_info(&self) -> (usize, usize) {
+        match self {
+            RuntimeError::DivisionByZero { line, column } => (*line, *column),
+            RuntimeError::ArrayIndexOutOfBounds { line, column, .. } => (*line, *column),
+            RuntimeError::UninitializedRead { line, column, .. } => (*line, *column),
+            RuntimeError::ZeroLoopStep { line, column } => (*line, *column),
+            RuntimeError::IntegerOverflow { line, column, .. } => (*line, *column),
+            RuntimeError::InvalidInput { line, column, .. } => (*line, *column),
+            RuntimeError::MissingInput { line, column } => (*line, *column),
+            RuntimeError::StepLimitExceeded { .. } => (0, 0),
+            RuntimeError::CallDepthExceeded { line, column, .. } => (*line, *column),
+            RuntimeError::InvalidProgram { line, column, .. } => (*line, *column),
+        }
+    }
+}
+
+impl RuntimeError {
+    pub fn get_detailed_message(&self) -> String {
+        match self {
+            RuntimeError::DivisionByZero { .. } => "Division by zero".to_string(),
+            RuntimeError::ArrayIndexOutOfBounds {
+                name, index, size, ..
+            } => format!(
+                "Array index out of bounds: index {} is outside of array '{}' of size {}",
+                index, name, size
+            ),
+            RuntimeError::UninitializedRead { name, .. } => {
+                format!("Read of uninitialized value '{}'", name)
+            }
+            RuntimeError::ZeroLoopStep { .. } => "For loop step is zero".to_string(),
+            RuntimeError::IntegerOverflow { value, .. } => {
+                format!("Integer overflow: result {} does not fit in Int", value)
+            }
+            RuntimeError::InvalidInput {
+                value, expected, ..
+            } => format!("Invalid input '{}': expected a {} value", value, expected),
+            RuntimeError::MissingInput { .. } => {
+                "Input requested but no more input is available".to_string()
+            }
+            RuntimeError::StepLimitExceeded { steps } => format!(
+                "Execution stopped after {} steps, the program may contain an infinite loop",
+                steps
+            ),
+            RuntimeError::CallDepthExceeded { depth, .. } => format!(
+                "Call depth exceeded: more than {} subprogram calls active at once",
+                depth
+            ),
+            RuntimeError::InvalidProgram { message, .. } => {
+                format!("Invalid program: {}", message)
+            }
+        }
+    }
+
+    fn get_token_length(&self) -> usize {
+        match self {
+            RuntimeError::ArrayIndexOutOfBounds { name, .. } => name.len(),
+            RuntimeError::UninitializedRead { name, .. } => name.len(),
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.report(None))
+    }
+}
+
+impl std::error::Error for RuntimeError {}
@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+/// Source of the values consumed by `input(...)` statements
+pub trait InputSource {
+    /// Reads the next raw input value, or `None` when the input is exhausted
+    fn read_line(&mut self) -> Option<String>;
+}
+
+/// Destination of the values produced by `output(...)` statements
+pub trait OutputSink {
+    /// Writes a single output value on the current line
+    fn write(&mut self, text: &str);
+
+    /// Terminates the current output line
+    fn end_line(&mut self);
+}
+
+/// Input source backed by a queue of pre-supplied values
+#[derive(Debug, Default, Clone)]
+pub struct BufferedInput {
+    lines: VecDeque<String>,
+}
+
+impl BufferedInput {
+    pub fn new<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        BufferedInput {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl InputSource for BufferedInput {
+    fn read_line(&mut self) -> Option<String> {
+        self.lines.pop_front()
+    }
+}
+
+/// Output sink that collects every printed line in memory
+#[derive(Debug, Default, Clone)]
+pub struct BufferedOutput {
+    lines: Vec<String>,
+    current: String,
+}
+
+impl BufferedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the completed lines, including a trailing unterminated line if any
+    pub fn into_lines(mut self) -> Vec<String> {
+        if !self.current.is_empty() {
+            self.lines.push(std::mem::take(&mut self.current));
+        }
+        self.lines
+    }
+}
+
+impl OutputSink for BufferedOutput {
+    fn write(&mut self, text: &str) {
+        self.current.push_str(text);
+    }
+
+    fn end_line(&mut self) {
+        self.lines.push(std::mem::take(&mut self.current));
+    }
+}
+
+/// Input source reading one value per line from standard input
+pub struct StdinInput;
+
+impl InputSource for StdinInput {
+    fn read_line(&mut self) -> Option<String> {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+        }
+    }
+}
+
+/// Output sink printing directly to standard output
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write(&mut self, text: &str) {
+        print!("{}", text);
+        let _ = io::stdout().flush();
+    }
+
+    fn end_line(&mut self) {
+        println!();
+    }
+}
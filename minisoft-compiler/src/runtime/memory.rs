@@ -0,0 +1,236 @@
+use crate::codegen::quadruple::Operation;
+use crate::parser::ast::{LiteralKind, Type};
+use crate::semantics::symbol_table::{SymbolKind, SymbolTable, SymbolValue};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Range of the Int type, matching the literals accepted by the lexer
+pub const INT_MIN: i64 = -32768;
+pub const INT_MAX: i64 = 32767;
+
+/// Why a binary operation has no result
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationFault {
+    DivisionByZero,
+    Overflow(i64),
+    Unsupported,
+}
+
+/// A runtime value held by a variable, temporary or array element
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Float(f32),
+    String(String),
+    Uninitialized,
+}
+
+impl Value {
+    /// Returns the MiniSoft type of the value, if it has one
+    pub fn get_type(&self) -> Option<Type> {
+        match self {
+            Value::Int(_) => Some(Type::Int),
+            Value::Float(_) => Some(Type::Float),
+            Value::String(_) => Some(Type::String),
+            Value::Uninitialized => None,
+        }
+    }
+
+    /// Converts the value to the given declared type when an implicit conversion exists
+    pub fn coerce_to(self, typ: &Type) -> Value {
+        match (self, typ) {
+            (Value::Int(i), Type::Float) => Value::Float(i as f32),
+            (value, _) => value,
+        }
+    }
+
+    /// Conditions treat every non-zero number as true
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Int(i) => *i != 0,
+            Value::Float(f) => *f != 0.0,
+            Value::String(s) => !s.is_empty(),
+            Value::Uninitialized => false,
+        }
+    }
+
+    /// Parses raw user input as a value of the given type, or infers the type when unknown
+    pub fn parse(text: &str, typ: Option<&Type>) -> Option<Value> {
+        let text = text.trim();
+        match typ {
+            Some(Type::Int) => text.parse().ok().map(Value::Int),
+            Some(Type::Float) => text.parse().ok().map(Value::Float),
+            Some(Type::String) => Some(Value::String(text.to_string())),
+            None => text
+                .parse()
+                .map(Value::Int)
+                .or_else(|_| text.parse().map(Value::Float))
+                .ok(),
+        }
+    }
+}
+
+impl Value {
+    /// Applies a binary quadruple operation, shared by the virtual machine and constant folding
+    pub fn binary(operation: &Operation, left: Value, right: Value) -> Result<Value, OperationFault> {
+        let result = match (left, right) {
+            (Value::String(l), Value::String(r)) => match operation {
+                Operation::Concat => Value::String(l + &r),
+                Operation::Equal => Value::Int((l == r) as i32),
+                Operation::NotEqual => Value::Int((l != r) as i32),
+                _ => return Err(OperationFault::Unsupported),
+            },
+            // Only strings are joined
+            _ if *operation == Operation::Concat => return Err(OperationFault::Unsupported),
+            (Value::Int(l), Value::Int(r)) => {
+                let (l, r) = (l as i64, r as i64);
+                let value = match operation {
+                    Operation::Add => l + r,
+                    Operation::Subtract => l - r,
+                    Operation::Multiply => l * r,
+                    Operation::Divide => {
+                        if r == 0 {
+                            return Err(OperationFault::DivisionByZero);
+                        }
+                        l / r
+                    }
+                    _ => return Ok(Self::compare(operation, l.cmp(&r), l != 0, r != 0)),
+                };
+                if !(INT_MIN..=INT_MAX).contains(&value) {
+                    return Err(OperationFault::Overflow(value));
+                }
+                Value::Int(value as i32)
+            }
+            (left, right) => {
+                let (l, r) = match (left.as_float(), right.as_float()) {
+                    (Some(l), Some(r)) => (l, r),
+                    _ => return Err(OperationFault::Unsupported),
+                };
+                match operation {
+                    Operation::Add => Value::Float(l + r),
+                    Operation::Subtract => Value::Float(l - r),
+                    Operation::Multiply => Value::Float(l * r),
+                    Operation::Divide => {
+                        if r == 0.0 {
+                            return Err(OperationFault::DivisionByZero);
+                        }
+                        Value::Float(l / r)
+                    }
+                    _ => {
+                        let ordering = l.partial_cmp(&r).unwrap_or(Ordering::Equal);
+                        Self::compare(operation, ordering, l != 0.0, r != 0.0)
+                    }
+                }
+            }
+        };
+        Ok(result)
+    }
+
+    /// Applies `LEN`, shared by the virtual machine and constant folding
+    pub fn length(&self) -> Result<Value, OperationFault> {
+        let Value::String(s) = self else {
+            return Err(OperationFault::Unsupported);
+        };
+        let length = s.chars().count() as i64;
+        if length > INT_MAX {
+            return Err(OperationFault::Overflow(length));
+        }
+        Ok(Value::Int(length as i32))
+    }
+
+    fn compare(operation: &Operation, ordering: Ordering, l: bool, r: bool) -> Value {
+        let result = match operation {
+            Operation::Equal => ordering == Ordering::Equal,
+            Operation::NotEqual => ordering != Ordering::Equal,
+            Operation::LessThan => ordering == Ordering::Less,
+            Operation::GreaterThan => ordering == Ordering::Greater,
+            Operation::LessEqual => ordering != Ordering::Greater,
+            Operation::GreaterEqual => ordering != Ordering::Less,
+            Operation::And => l && r,
+            Operation::Or => l || r,
+            _ => false,
+        };
+        Value::Int(result as i32)
+    }
+
+    fn as_float(&self) -> Option<f32> {
+        match self {
+            Value::Int(i) => Some(*i as f32),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
+impl From<&LiteralKind> for Value {
+    fn from(literal: &LiteralKind) -> Self {
+        match literal {
+            LiteralKind::Int(i) => Value::Int(*i),
+            LiteralKind::Float(f) => Value::Float(*f),
+            LiteralKind::String(s) => Value::String(s.clone()),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Uninitialized => write!(f, "<uninitialized>"),
+        }
+    }
+}
+
+/// Storage for every name a quadruple program can read or write
+#[derive(Debug, Default, Clone)]
+pub struct Memory {
+    pub variables: HashMap<String, Value>,
+    pub temps: HashMap<String, Value>,
+    pub arrays: HashMap<String, Vec<Value>>,
+    pub types: HashMap<String, Type>,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserves storage for every variable, constant and array of the symbol table
+    pub fn from_symbol_table(symbol_table: &SymbolTable) -> Self {
+        let mut memory = Memory::new();
+        for symbol in symbol_table.get_all() {
+            let name = symbol.qualified_name();
+            match symbol.kind {
+                SymbolKind::Array(size) => memory.declare_array(&name, &symbol.symbol_type, size),
+                SymbolKind::Variable | SymbolKind::Constant => {
+                    memory.declare_variable(&name, &symbol.symbol_type);
+                    // Constants are known before the first quadruple executes
+                    if let (true, SymbolValue::Single(literal)) = (symbol.is_constant, &symbol.value) {
+                        memory.variables.insert(name, Value::from(literal));
+                    }
+                }
+            }
+        }
+        memory
+    }
+
+    pub fn declare_variable(&mut self, name: &str, typ: &Type) {
+        self.types.insert(name.to_string(), typ.clone());
+        self.variables
+            .insert(name.to_string(), Value::Uninitialized);
+    }
+
+    pub fn declare_array(&mut self, name: &str, typ: &Type, size: usize) {
+        self.types.insert(name.to_string(), typ.clone());
+        self.arrays
+            .insert(name.to_string(), vec![Value::Uninitialized; size]);
+    }
+
+    /// Returns the declared type of a variable or array, if known
+    pub fn get_type(&self, name: &str) -> Option<&Type> {
+        self.types.get(name)
+    }
+}
@@ -0,0 +1,11 @@
+pub mod debugger;
+pub mod error;
+pub mod io;
+pub mod memory;
+pub mod vm;
+
+pub use debugger::{DebugSnapshot, Debugger, StopReason};
+pub use error::RuntimeError;
+pub use io::{BufferedInput, BufferedOutput, InputSource, OutputSink};
+pub use memory::{Memory, OperationFault, Value};
+pub use vm::{ExecutionResult, VirtualMachine, execute, execute_with_input};
@@ -0,0 +1,590 @@
+use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::parser::ast::Type;
+use crate::runtime::error::RuntimeError;
+use crate::runtime::io::{BufferedInput, BufferedOutput, InputSource, OutputSink};
+use crate::runtime::memory::{INT_MAX, INT_MIN, Memory, OperationFault, Value};
+use crate::semantics::source_map::SourceMap;
+use crate::semantics::symbol_table::{SymbolTable, source_name};
+use std::collections::HashMap;
+
+/// Upper bound on executed quadruples, protects the editor from infinite loops
+pub const DEFAULT_MAX_STEPS: usize = 1_000_000;
+
+/// Upper bound on subprogram calls active at once, protects against runaway recursion
+pub const MAX_CALL_DEPTH: usize = 1_000;
+
+/// Output and final memory state of an execution, with the error that stopped it if any
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    pub output: Vec<String>,
+    pub memory: Memory,
+    pub steps: usize,
+    pub error: Option<RuntimeError>,
+}
+
+/// State of a caller saved while a subprogram runs
+#[derive(Debug, Clone)]
+struct Frame {
+    return_pc: usize,
+    /// Where the value of a function goes, `Empty` for a procedure
+    result: Operand,
+    /// Parameters and locals of the callee as they were before the call, a recursive
+    /// call must not clobber the ones of the activation it returns to
+    variables: Vec<(String, Value)>,
+    arrays: Vec<(String, Vec<Value>)>,
+    temps: HashMap<String, Value>,
+}
+
+/// Value passed to a parameter, waiting for the call that binds it
+#[derive(Debug, Clone)]
+enum Argument {
+    Value(Value),
+    Array(Vec<Value>),
+}
+
+/// Interpreter executing a quadruple program one instruction at a time
+pub struct VirtualMachine {
+    quadruples: Vec<Quadruple>,
+    labels: HashMap<usize, usize>,
+    entries: HashMap<String, usize>,
+    frames: Vec<Frame>,
+    arguments: Vec<(String, Argument)>,
+    memory: Memory,
+    source_map: Option<SourceMap>,
+    pc: usize,
+    steps: usize,
+    max_steps: usize,
+}
+
+impl VirtualMachine {
+    pub fn new(program: &QuadrupleProgram) -> Self {
+        let labels = program
+            .quadruples
+            .iter()
+            .enumerate()
+            .filter_map(|(index, quad)| match quad.operation {
+                Operation::Label(id) => Some((id, index)),
+                _ => None,
+            })
+            .collect();
+        let entries = program
+            .quadruples
+            .iter()
+            .enumerate()
+            .filter_map(|(index, quad)| match &quad.operation {
+                Operation::Entry(name) => Some((name.clone(), index)),
+                _ => None,
+            })
+            .collect();
+
+        VirtualMachine {
+            quadruples: program.quadruples.clone(),
+            labels,
+            entries,
+            frames: Vec::new(),
+            arguments: Vec::new(),
+            memory: Memory::new(),
+            source_map: None,
+            pc: 0,
+            steps: 0,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+
+    /// Declares the variables, constants and arrays known to the semantic analyzer
+    pub fn load_symbols(&mut self, symbol_table: &SymbolTable) {
+        self.memory = Memory::from_symbol_table(symbol_table);
+    }
+
+    /// Maps runtime errors back to line and column in the given source code
+    pub fn with_source(mut self, source_code: &String) -> Self {
+        self.source_map = Some(SourceMap::new(source_code));
+        self
+    }
+
+    /// Starts from the memory left by an earlier execution instead of the declared symbols
+    pub fn with_memory(mut self, memory: Memory) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    pub fn into_memory(self) -> Memory {
+        self.memory
+    }
+
+    /// Index of the next quadruple to execute
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn quadruples(&self) -> &[Quadruple] {
+        &self.quadruples
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.quadruples.len()
+    }
+
+    /// Runs until the program halts
+    pub fn run(
+        &mut self,
+        input: &mut dyn InputSource,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), RuntimeError> {
+        while !self.is_halted() {
+            self.step(input, output)?;
+        }
+        Ok(())
+    }
+
+    /// Executes the quadruple at the program counter
+    pub fn step(
+        &mut self,
+        input: &mut dyn InputSource,
+        output: &mut dyn OutputSink,
+    ) -> Result<(), RuntimeError> {
+        if self.is_halted() {
+            return Ok(());
+        }
+        if self.steps >= self.max_steps {
+            return Err(RuntimeError::StepLimitExceeded {
+                steps: self.max_steps,
+            });
+        }
+        self.steps += 1;
+
+        let quad = self.quadruples[self.pc].clone();
+        let mut next_pc = self.pc + 1;
+
+        match &quad.operation {
+            Operation::Add
+            | Operation::Subtract
+            | Operation::Multiply
+            | Operation::Divide
+            | Operation::Concat
+            | Operation::Equal
+            | Operation::NotEqual
+            | Operation::LessThan
+            | Operation::GreaterThan
+            | Operation::LessEqual
+            | Operation::GreaterEqual
+            | Operation::And
+            | Operation::Or => {
+                let left = self.read(&quad.operand1)?;
+                let right = self.read(&quad.operand2)?;
+                let value = self.binary_operation(&quad.operation, left, right)?;
+                self.write(&quad.result, value)?;
+            }
+            Operation::Not => {
+                let value = self.read(&quad.operand1)?;
+                self.write(&quad.result, Value::Int(!value.is_truthy() as i32))?;
+            }
+            Operation::Length => {
+                let value = self.read(&quad.operand1)?;
+                let description = format!("invalid operand '{}' for '{}'", value, quad.operation);
+                let length = value
+                    .length()
+                    .map_err(|fault| self.operation_error(fault, description))?;
+                self.write(&quad.result, length)?;
+            }
+            Operation::Assign => {
+                let value = self.read(&quad.operand1)?;
+                self.write(&quad.result, value)?;
+            }
+            Operation::ArrayStore => {
+                let value = self.read(&quad.operand1)?;
+                let index = self.read_index(&quad.operand2)?;
+                let name = self.operand_name(&quad.result)?;
+                self.store_element(&name, index, value)?;
+            }
+            Operation::ArrayLoad => {
+                let name = self.operand_name(&quad.operand1)?;
+                let index = self.read_index(&quad.operand2)?;
+                let value = self.load_element(&name, index)?;
+                self.write(&quad.result, value)?;
+            }
+            Operation::Label(_) => {}
+            Operation::Jump(label) => {
+                next_pc = self.resolve_label(*label)?;
+            }
+            Operation::JumpIfTrue(label) => {
+                if self.read(&quad.operand1)?.is_truthy() {
+                    next_pc = self.resolve_label(*label)?;
+                }
+            }
+            Operation::JumpIfFalse(label) => {
+                if !self.read(&quad.operand1)?.is_truthy() {
+                    next_pc = self.resolve_label(*label)?;
+                }
+            }
+            Operation::CheckStep => {
+                if self.read(&quad.operand1)? == Value::Int(0) {
+                    let (line, column) = self.location();
+                    return Err(RuntimeError::ZeroLoopStep { line, column });
+                }
+            }
+            Operation::Input => {
+                let target_type = self.target_type(&quad.result).cloned();
+                let (line, column) = self.location();
+                let raw = input
+                    .read_line()
+                    .ok_or(RuntimeError::MissingInput { line, column })?;
+                let value = Value::parse(&raw, target_type.as_ref())
+                    .filter(|value| match value {
+                        Value::Int(i) => (INT_MIN..=INT_MAX).contains(&(*i as i64)),
+                        _ => true,
+                    })
+                    .ok_or_else(|| RuntimeError::InvalidInput {
+                        value: raw.clone(),
+                        expected: target_type
+                            .map(|typ| typ.to_string())
+                            .unwrap_or_else(|| "numeric".to_string()),
+                        line,
+                        column,
+                    })?;
+                self.write(&quad.result, value)?;
+            }
+            Operation::Output => {
+                if quad.operand1 == Operand::Empty {
+                    output.end_line();
+                } else {
+                    let value = self.read(&quad.operand1)?;
+                    output.write(&value.to_string());
+                }
+            }
+            Operation::Param => {
+                let target = self.operand_name(&quad.result)?;
+                let argument = match &quad.operand1 {
+                    Operand::Variable(name) if self.memory.arrays.contains_key(name) => {
+                        Argument::Array(self.memory.arrays[name].clone())
+                    }
+                    operand => Argument::Value(self.read(operand)?),
+                };
+                self.arguments.push((target, argument));
+            }
+            Operation::Call(name) => {
+                next_pc = self.call(name, &quad.result)?;
+            }
+            Operation::Return => {
+                next_pc = match self.frames.pop() {
+                    // Returning from the program body halts
+                    None => self.quadruples.len(),
+                    Some(frame) => {
+                        let value = match &quad.operand1 {
+                            Operand::Empty => None,
+                            operand => Some(self.read(operand)?),
+                        };
+                        self.memory.variables.extend(frame.variables);
+                        self.memory.arrays.extend(frame.arrays);
+                        self.memory.temps = frame.temps;
+                        if let Some(value) = value
+                            && frame.result != Operand::Empty
+                        {
+                            self.write(&frame.result, value)?;
+                        }
+                        frame.return_pc
+                    }
+                };
+            }
+            Operation::Entry(_) => {}
+        }
+
+        self.pc = next_pc;
+        Ok(())
+    }
+
+    /// Saves the state of the caller, binds the pending arguments to the parameters of
+    /// the subprogram and returns the index of its entry
+    fn call(&mut self, name: &str, result: &Operand) -> Result<usize, RuntimeError> {
+        let entry = *self
+            .entries
+            .get(name)
+            .ok_or_else(|| self.invalid_program(format!("call to undefined subprogram '{}'", name)))?;
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            let (line, column) = self.location();
+            return Err(RuntimeError::CallDepthExceeded {
+                depth: MAX_CALL_DEPTH,
+                line,
+                column,
+            });
+        }
+
+        // Every activation starts with its parameters and locals uninitialized
+        let prefix = format!("{}.", name);
+        let mut variables = Vec::new();
+        for (local, value) in self.memory.variables.iter_mut() {
+            if local.starts_with(&prefix) {
+                variables.push((local.clone(), std::mem::replace(value, Value::Uninitialized)));
+            }
+        }
+        let mut arrays = Vec::new();
+        for (local, elements) in self.memory.arrays.iter_mut() {
+            if local.starts_with(&prefix) {
+                let cleared = vec![Value::Uninitialized; elements.len()];
+                arrays.push((local.clone(), std::mem::replace(elements, cleared)));
+            }
+        }
+        self.frames.push(Frame {
+            return_pc: self.pc + 1,
+            result: result.clone(),
+            variables,
+            arrays,
+            temps: self.memory.temps.clone(),
+        });
+
+        for (target, argument) in std::mem::take(&mut self.arguments) {
+            match argument {
+                Argument::Value(value) => self.write(&Operand::Variable(target), value)?,
+                Argument::Array(elements) => {
+                    let typ = self.memory.get_type(&target).cloned();
+                    let elements = match typ {
+                        Some(typ) => elements.into_iter().map(|value| value.coerce_to(&typ)).collect(),
+                        None => elements,
+                    };
+                    self.memory.arrays.insert(target, elements);
+                }
+            }
+        }
+        Ok(entry)
+    }
+
+    /// Line and column of the quadruple being executed, (0, 0) when unknown
+    fn location(&self) -> (usize, usize) {
+        let span = self
+            .quadruples
+            .get(self.pc)
+            .and_then(|quad| quad.span.as_ref());
+        match (&self.source_map, span) {
+            (Some(source_map), Some(span)) => source_map.get_line_column(span),
+            _ => (0, 0),
+        }
+    }
+
+    fn invalid_program(&self, message: String) -> RuntimeError {
+        let (line, column) = self.location();
+        RuntimeError::InvalidProgram {
+            message,
+            line,
+            column,
+        }
+    }
+
+    fn resolve_label(&self, label: usize) -> Result<usize, RuntimeError> {
+        self.labels
+            .get(&label)
+            .copied()
+            .ok_or_else(|| self.invalid_program(format!("jump to undefined label {}", label)))
+    }
+
+    fn operand_name(&self, operand: &Operand) -> Result<String, RuntimeError> {
+        match operand {
+            Operand::Variable(name)
+            | Operand::TempVariable(name)
+            | Operand::ArrayElement(name, _) => Ok(name.clone()),
+            other => Err(self.invalid_program(format!("expected a variable, found '{}'", other))),
+        }
+    }
+
+    fn target_type(&self, operand: &Operand) -> Option<&Type> {
+        match operand {
+            Operand::Variable(name) | Operand::ArrayElement(name, _) => self.memory.get_type(name),
+            _ => None,
+        }
+    }
+
+    fn read(&self, operand: &Operand) -> Result<Value, RuntimeError> {
+        let value = match operand {
+            Operand::IntLiteral(i) => Value::Int(*i),
+            Operand::FloatLiteral(f) => Value::Float(*f),
+            Operand::StringLiteral(s) => Value::String(s.clone()),
+            Operand::Variable(name) => self
+                .memory
+                .variables
+                .get(name)
+                .cloned()
+                .unwrap_or(Value::Uninitialized),
+            Operand::TempVariable(name) => self
+                .memory
+                .temps
+                .get(name)
+                .cloned()
+                .unwrap_or(Value::Uninitialized),
+            Operand::ArrayElement(name, index) => {
+                let index = self.read_index(index)?;
+                self.load_element(name, index)?
+            }
+            Operand::Empty => return Err(self.invalid_program("missing operand".to_string())),
+        };
+
+        if value == Value::Uninitialized {
+            let (line, column) = self.location();
+            let name = match operand {
+                Operand::Variable(name) => source_name(name).to_string(),
+                other => other.to_string(),
+            };
+            return Err(RuntimeError::UninitializedRead {
+                name,
+                line,
+                column,
+            });
+        }
+        Ok(value)
+    }
+
+    fn read_index(&self, operand: &Operand) -> Result<i32, RuntimeError> {
+        match self.read(operand)? {
+            Value::Int(index) => Ok(index),
+            other => Err(self.invalid_program(format!(
+                "array index must be an integer, found '{}'",
+                other
+            ))),
+        }
+    }
+
+    fn write(&mut self, operand: &Operand, value: Value) -> Result<(), RuntimeError> {
+        match operand {
+            Operand::Variable(name) => {
+                let value = match self.memory.get_type(name) {
+                    Some(typ) => value.coerce_to(typ),
+                    None => value,
+                };
+                self.memory.variables.insert(name.clone(), value);
+                Ok(())
+            }
+            Operand::TempVariable(name) => {
+                self.memory.temps.insert(name.clone(), value);
+                Ok(())
+            }
+            Operand::ArrayElement(name, index) => {
+                let index = self.read_index(index)?;
+                self.store_element(name, index, value)
+            }
+            other => Err(self.invalid_program(format!("cannot assign to '{}'", other))),
+        }
+    }
+
+    /// Checks an index against the declared size of an array
+    fn check_bounds(&self, name: &str, index: i32) -> Result<usize, RuntimeError> {
+        let size = self
+            .memory
+            .arrays
+            .get(name)
+            .map(Vec::len)
+            .ok_or_else(|| self.invalid_program(format!("unknown array '{}'", name)))?;
+
+        match usize::try_from(index) {
+            Ok(i) if i < size => Ok(i),
+            _ => {
+                let (line, column) = self.location();
+                Err(RuntimeError::ArrayIndexOutOfBounds {
+                    name: source_name(name).to_string(),
+                    index,
+                    size,
+                    line,
+                    column,
+                })
+            }
+        }
+    }
+
+    fn load_element(&self, name: &str, index: i32) -> Result<Value, RuntimeError> {
+        let i = self.check_bounds(name, index)?;
+        let value = self.memory.arrays[name][i].clone();
+        if value == Value::Uninitialized {
+            let (line, column) = self.location();
+            return Err(RuntimeError::UninitializedRead {
+                name: format!("{}[{}]", source_name(name), index),
+                line,
+                column,
+            });
+        }
+        Ok(value)
+    }
+
+    fn store_element(&mut self, name: &str, index: i32, value: Value) -> Result<(), RuntimeError> {
+        let i = self.check_bounds(name, index)?;
+        let value = match self.memory.get_type(name) {
+            Some(typ) => value.coerce_to(typ),
+            None => value,
+        };
+        if let Some(array) = self.memory.arrays.get_mut(name) {
+            array[i] = value;
+        }
+        Ok(())
+    }
+
+    fn binary_operation(
+        &self,
+        operation: &Operation,
+        left: Value,
+        right: Value,
+    ) -> Result<Value, RuntimeError> {
+        let description = format!("invalid operands '{}' and '{}' for '{}'", left, right, operation);
+        Value::binary(operation, left, right)
+            .map_err(|fault| self.operation_error(fault, description))
+    }
+
+    /// Error for an operation without a result, `description` tells why for invalid operands
+    fn operation_error(&self, fault: OperationFault, description: String) -> RuntimeError {
+        let (line, column) = self.location();
+        match fault {
+            OperationFault::DivisionByZero => RuntimeError::DivisionByZero { line, column },
+            OperationFault::Overflow(value) => RuntimeError::IntegerOverflow {
+                value,
+                line,
+                column,
+            },
+            OperationFault::Unsupported => self.invalid_program(description),
+        }
+    }
+}
+
+/// Executes a program from start to finish against a fixed list of input values.
+/// Output produced before a failure is kept alongside the error.
+pub fn execute(
+    program: &QuadrupleProgram,
+    symbol_table: &SymbolTable,
+    source_code: &String,
+    inputs: Vec<String>,
+) -> ExecutionResult {
+    execute_with_input(
+        program,
+        symbol_table,
+        source_code,
+        &mut BufferedInput::new(inputs),
+    )
+}
+
+/// Like `execute`, the values for `input` statements coming from `input` as they are read
+pub fn execute_with_input(
+    program: &QuadrupleProgram,
+    symbol_table: &SymbolTable,
+    source_code: &String,
+    input: &mut dyn InputSource,
+) -> ExecutionResult {
+    let mut vm = VirtualMachine::new(program).with_source(source_code);
+    vm.load_symbols(symbol_table);
+
+    let mut output = BufferedOutput::new();
+    let error = vm.run(input, &mut output).err();
+
+    ExecutionResult {
+        output: output.into_lines(),
+        memory: vm.memory,
+        steps: vm.steps,
+        error,
+    }
+}
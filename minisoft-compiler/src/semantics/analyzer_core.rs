@@ -1,17 +1,33 @@
 mod declaration_analyzer;
 mod expression_analyzer;
+mod initialization_analyzer;
+mod range_analyzer;
 mod statement_analyzer;
+mod subprogram_analyzer;
 
-use crate::parser::ast::{Expression, ExpressionKind, LiteralKind, Operator, Program, Type};
+use crate::parser::ast::{
+    Expression, ExpressionKind, LiteralKind, Operator, Program, Type, UnaryOperator,
+};
+use crate::error_reporter::{ErrorReporter, Severity};
 use crate::semantics::error::SemanticError;
 use crate::semantics::source_map::SourceMap;
 use crate::semantics::symbol_table::{SymbolKind, SymbolTable, SymbolValue};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
+use subprogram_analyzer::Signature;
 
 pub struct SemanticAnalyzer {
     symbol_table: SymbolTable,
+    /// Functions and procedures by name
+    signatures: HashMap<String, Signature>,
+    /// Function or procedure whose body is being analyzed
+    current_subprogram: Option<String>,
+    /// Globals some subprogram writes, any call may change them
+    subprogram_writes: HashSet<String>,
+    /// Path of the scope opened by each block, by position of the block in the source
+    block_scopes: HashMap<usize, String>,
     errors: Vec<SemanticError>,
+    warnings: Vec<SemanticError>,
     reported_errors: HashSet<String>,
     source_map: SourceMap,
 }
@@ -20,7 +36,12 @@ impl SemanticAnalyzer {
     pub fn new(source_code: &String) -> Self {
         SemanticAnalyzer {
             symbol_table: SymbolTable::new(),
+            signatures: HashMap::new(),
+            current_subprogram: None,
+            subprogram_writes: HashSet::new(),
+            block_scopes: HashMap::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
             reported_errors: HashSet::new(),
             source_map: SourceMap::new(source_code),
         }
@@ -35,12 +56,62 @@ impl SemanticAnalyzer {
             self.analyze_declaration(decl);
         }
 
+        // Subprograms can be called from each other and from the program body
+        // A body is only checked against a signature of its own
+        for subprogram in self.declare_subprograms(&program.subprograms) {
+            self.analyze_subprogram(subprogram);
+        }
+
         // Second pass: analyze statements
-        for stmt in &program.statements {
-            self.analyze_statement(stmt);
+        self.handle_scope(&program.statements);
+
+        // Third pass: flow-sensitive warnings
+        self.check_initialization(program);
+        self.check_index_ranges(program);
+    }
+
+    /// Starts analyzing another piece of a program entered one declaration or statement at
+    /// a time, as the REPL does. Declared symbols stay, diagnostics of the earlier pieces
+    /// are dropped and positions now refer to `source`.
+    pub fn begin_fragment(&mut self, source: &str) {
+        self.errors.clear();
+        self.warnings.clear();
+        self.reported_errors.clear();
+        self.block_scopes.clear();
+        self.source_map = SourceMap::new(&source.to_string());
+    }
+
+    /// Hands over the errors and warnings reported so far
+    pub fn take_diagnostics(&mut self) -> (Vec<SemanticError>, Vec<SemanticError>) {
+        (
+            std::mem::take(&mut self.errors),
+            std::mem::take(&mut self.warnings),
+        )
+    }
+
+    /// Puts back a symbol table saved before a fragment that turned out to be invalid
+    pub fn restore_symbol_table(&mut self, symbol_table: SymbolTable) {
+        self.symbol_table = symbol_table;
+    }
+
+    /// Opens the scope of the block at `span`, the same one every time a pass over the
+    /// program comes back to the block
+    fn enter_block(&mut self, span: &Range<usize>) {
+        match self.block_scopes.get(&span.start) {
+            Some(path) => {
+                self.symbol_table.reopen_scope(path);
+            }
+            None => {
+                let path = self.symbol_table.enter_block();
+                self.block_scopes.insert(span.start, path);
+            }
         }
     }
 
+    fn exit_block(&mut self) {
+        self.symbol_table.exit_scope();
+    }
+
     // Error helper methods
     fn empty_program(&mut self) {
         self.add_error(SemanticError::EmptyProgram);
@@ -140,6 +211,22 @@ impl SemanticAnalyzer {
         });
     }
 
+    fn shadowed_declaration_warning(
+        &mut self,
+        span: &Range<usize>,
+        name: &str,
+        original_line: usize,
+        original_column: usize,
+    ) {
+        self.add_error(SemanticError::ShadowedDeclaration {
+            name: name.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+            original_line,
+            original_column,
+        });
+    }
+
     fn condition_value_error(&mut self, span: &Range<usize>, found: String) {
         self.add_error(SemanticError::InvalidConditionValue {
             found,
@@ -157,6 +244,29 @@ impl SemanticAnalyzer {
         });
     }
 
+    fn zero_loop_step_error(&mut self, span: &Range<usize>) {
+        self.add_error(SemanticError::ZeroLoopStep {
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn infinite_loop_warning(&mut self, span: &Range<usize>, constant: bool) {
+        self.add_error(SemanticError::InfiniteLoop {
+            constant,
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn unreachable_code_warning(&mut self, span: &Range<usize>, context: &str) {
+        self.add_error(SemanticError::UnreachableCode {
+            context: context.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
     fn assignement_to_array_error(&mut self, span: &Range<usize>, name: &str) {
         self.add_error(SemanticError::AssignmentToArray {
             name: name.to_string(),
@@ -170,7 +280,11 @@ impl SemanticAnalyzer {
         let error_key = format!("{:?}", error);
         if !self.reported_errors.contains(&error_key) {
             self.reported_errors.insert(error_key);
-            self.errors.push(error);
+            // Warnings and notes don't prevent code generation
+            match error.severity() {
+                Severity::Error => self.errors.push(error),
+                Severity::Warning | Severity::Note => self.warnings.push(error),
+            }
         }
     }
 
@@ -178,6 +292,10 @@ impl SemanticAnalyzer {
         &self.errors
     }
 
+    pub fn get_warnings(&self) -> &Vec<SemanticError> {
+        &self.warnings
+    }
+
     pub fn get_symbol_table(&self) -> &SymbolTable {
         &self.symbol_table
     }
@@ -202,6 +320,11 @@ impl SemanticAnalyzer {
                 let left_val = self.evaluate_constant_expression(left)?;
                 let right_val = self.evaluate_constant_expression(right)?;
 
+                // Comparisons and logical operators give 1 or 0
+                if let Some(result) = evaluate_condition(&left_val, op, &right_val) {
+                    return Some(LiteralKind::Int(result as i32));
+                }
+
                 match (left_val, right_val) {
                     (LiteralKind::Int(l), LiteralKind::Int(r)) => match op {
                         Operator::Add => Some(LiteralKind::Int(l + r)),
@@ -217,6 +340,12 @@ impl SemanticAnalyzer {
                         }
                         _ => None,
                     },
+                    (LiteralKind::String(l), LiteralKind::String(r)) => match op {
+                        Operator::Add => Some(LiteralKind::String(l + &r)),
+                        Operator::Equal => Some(LiteralKind::Int((l == r) as i32)),
+                        Operator::NotEqual => Some(LiteralKind::Int((l != r) as i32)),
+                        _ => None,
+                    },
                     (LiteralKind::Float(l), LiteralKind::Float(r)) => match op {
                         Operator::Add => Some(LiteralKind::Float(l + r)),
                         Operator::Subtract => Some(LiteralKind::Float(l - r)),
@@ -234,6 +363,20 @@ impl SemanticAnalyzer {
                     _ => None,
                 }
             }
+            ExpressionKind::UnaryOp(UnaryOperator::Not, operand) => {
+                match self.evaluate_constant_expression(operand)? {
+                    LiteralKind::Int(value @ (0 | 1)) => Some(LiteralKind::Int(1 - value)),
+                    _ => None,
+                }
+            }
+            ExpressionKind::UnaryOp(UnaryOperator::Length, operand) => {
+                match self.evaluate_constant_expression(operand)? {
+                    LiteralKind::String(value) => {
+                        Some(LiteralKind::Int(value.chars().count() as i32))
+                    }
+                    _ => None,
+                }
+            }
             ExpressionKind::ArrayAccess(name, index_expr) => {
                 // Handle array access for constant expressions
                 // First evaluate the index expression to avoid borrowing conflicts
@@ -256,7 +399,28 @@ impl SemanticAnalyzer {
                 }
                 None
             }
-            _ => None,
+            // A function may return something else every time it's called
+            ExpressionKind::Call(..) => None,
         }
     }
 }
+
+fn evaluate_condition(left: &LiteralKind, op: &Operator, right: &LiteralKind) -> Option<bool> {
+    let number = |literal: &LiteralKind| match literal {
+        LiteralKind::Int(value) => Some(*value as f32),
+        LiteralKind::Float(value) => Some(*value),
+        LiteralKind::String(_) => None,
+    };
+    let (left, right) = (number(left)?, number(right)?);
+    match op {
+        Operator::GreaterThan => Some(left > right),
+        Operator::LessThan => Some(left < right),
+        Operator::GreaterEqual => Some(left >= right),
+        Operator::LessEqual => Some(left <= right),
+        Operator::Equal => Some(left == right),
+        Operator::NotEqual => Some(left != right),
+        Operator::And => Some(left == 1.0 && right == 1.0),
+        Operator::Or => Some(left == 1.0 || right == 1.0),
+        _ => None,
+    }
+}
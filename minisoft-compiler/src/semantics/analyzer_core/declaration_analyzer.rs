@@ -46,6 +46,21 @@ impl SemanticAnalyzer {
         }
     }
 
+    /// Whether `name` can be declared in the innermost scope. A second declaration in the
+    /// same scope is an error, hiding a name declared around the scope only a warning.
+    pub(super) fn check_declaration(&mut self, span: &Range<usize>, name: &str) -> bool {
+        if let Some(existing) = self.symbol_table.get_local(name) {
+            let (line, column) = (existing.line, existing.column);
+            self.duplicate_declaration_error(span, name, line, column);
+            return false;
+        }
+        if let Some(outer) = self.symbol_table.get_shadowed(name) {
+            let (line, column) = (outer.line, outer.column);
+            self.shadowed_declaration_warning(span, name, line, column);
+        }
+        true
+    }
+
     fn handle_constant_declaration(
         &mut self,
         value: &str,
@@ -53,10 +68,7 @@ impl SemanticAnalyzer {
         literal: &Literal,
         span: &Range<usize>,
     ) {
-        // Check for duplicate declaration
-        if self.symbol_table.contains(value) {
-            let existing = self.symbol_table.get(value).unwrap();
-            self.duplicate_declaration_error(span, value, existing.line, existing.column);
+        if !self.check_declaration(span, value) {
             return;
         }
         
@@ -67,6 +79,9 @@ impl SemanticAnalyzer {
             LiteralKind::Float(_) if !typ.is_compatible_with(&Type::Float) => {
                 self.type_mismatch_error(span, typ, &Type::Float, Some("constant"));
             },
+            LiteralKind::String(_) if !typ.is_compatible_with(&Type::String) => {
+                self.type_mismatch_error(span, typ, &Type::String, Some("constant"));
+            },
             _ => {}
         }
 
@@ -81,16 +96,14 @@ impl SemanticAnalyzer {
             line,
             column,
             is_constant: true,
+            scope: String::new(),
         };
 
         self.symbol_table.add_symbol(symbol);
     }
 
     fn handle_variable_declaration(&mut self, name: &str, typ: &Type, span: &Range<usize>) {
-        // Check for duplicate declaration
-        if self.symbol_table.contains(name) {
-            let existing = self.symbol_table.get(name).unwrap();
-            self.duplicate_declaration_error(span, name, existing.line, existing.column);
+        if !self.check_declaration(span, name) {
             return;
         }
 
@@ -104,7 +117,8 @@ impl SemanticAnalyzer {
             value: SymbolValue::Uninitialized,
             line,
             column,
-            is_constant: false, 
+            is_constant: false,
+            scope: String::new(),
         };
         self.symbol_table.add_symbol(symbol);
     }
@@ -116,10 +130,7 @@ impl SemanticAnalyzer {
             return;
         }
         
-        // Check for duplicate declaration
-        if self.symbol_table.contains(name) {
-            let existing = self.symbol_table.get(name).unwrap();
-            self.duplicate_declaration_error(span, name, existing.line, existing.column);
+        if !self.check_declaration(span, name) {
             return;
         }
 
@@ -134,6 +145,7 @@ impl SemanticAnalyzer {
             line,
             column,
             is_constant: false,
+            scope: String::new(),
         };
 
         self.symbol_table.add_symbol(symbol);
@@ -158,10 +170,7 @@ impl SemanticAnalyzer {
             }
         }
 
-        // Check for duplicate declaration
-        if self.symbol_table.contains(name) {
-            let existing = self.symbol_table.get(name).unwrap();
-            self.duplicate_declaration_error(span, name, existing.line, existing.column);
+        if !self.check_declaration(span, name) {
             return;
         }
 
@@ -179,6 +188,7 @@ impl SemanticAnalyzer {
             line,
             column,
             is_constant: false,
+            scope: String::new(),
         };
         
         self.symbol_table.add_symbol(symbol);
@@ -224,10 +234,7 @@ impl SemanticAnalyzer {
             }
         }
 
-        // Check for duplicate declaration
-        if self.symbol_table.contains(name) {
-            let existing = self.symbol_table.get(name).unwrap();
-            self.duplicate_declaration_error(span, name, existing.line, existing.column);
+        if !self.check_declaration(span, name) {
             return;
         }
 
@@ -246,6 +253,7 @@ impl SemanticAnalyzer {
             line,
             column,
             is_constant: false,
+            scope: String::new(),
         };
 
         self.symbol_table.add_symbol(symbol);
@@ -52,6 +52,9 @@ impl SemanticAnalyzer {
             ExpressionKind::UnaryOp(unary_operator, located) => {
                 self.handle_unary_operation(unary_operator, located, &expr.span)
             }
+            ExpressionKind::Call(name, arguments) => {
+                self.handle_call_expression(name, arguments, &expr.span)
+            }
         }
     }
 
@@ -154,7 +157,7 @@ impl SemanticAnalyzer {
         match literal.node {
             LiteralKind::Int(value) => Some(ValueType::new(Type::Int, Some(value as f32))),
             LiteralKind::Float(value) => Some(ValueType::new(Type::Float, Some(value))),
-            _ => None,
+            LiteralKind::String(_) => Some(ValueType::new(Type::String, None)),
         }
     }
 
@@ -174,6 +177,10 @@ impl SemanticAnalyzer {
         let left_type = left_type.unwrap();
         let right_type = right_type.unwrap();
 
+        if left_type.typ == Type::String || right_type.typ == Type::String {
+            return self.handle_string_operation(left, &left_type, operator, right, &right_type);
+        }
+
         match operator {
             Operator::Add | Operator::Subtract | Operator::Multiply | Operator::Divide => {
                 if left_type.typ != Type::Int && left_type.typ != Type::Float {
@@ -308,6 +315,40 @@ impl SemanticAnalyzer {
         }
     }
 
+    /// Strings are joined with `+` and compared with `==` and `!=`, both operands must be
+    /// strings
+    fn handle_string_operation(
+        &mut self,
+        left: &Expression,
+        left_type: &ValueType,
+        operator: &Operator,
+        right: &Expression,
+        right_type: &ValueType,
+    ) -> Option<ValueType> {
+        let span = left.span.start..right.span.end;
+        let (result, context) = match operator {
+            Operator::Add => (Type::String, "concatenation"),
+            Operator::Equal | Operator::NotEqual => (Type::Int, "string comparison"),
+            _ => {
+                let context = match operator {
+                    Operator::Subtract | Operator::Multiply | Operator::Divide => "arithmetic",
+                    Operator::And | Operator::Or => "logical",
+                    _ => "comparison",
+                };
+                self.type_mismatch_error(&span, &Type::Int, &Type::String, Some(context));
+                return None;
+            }
+        };
+
+        for operand in [left_type, right_type] {
+            if operand.typ != Type::String {
+                self.type_mismatch_error(&span, &Type::String, &operand.typ, Some(context));
+                return None;
+            }
+        }
+        Some(ValueType::from(result))
+    }
+
     fn handle_unary_operation(
         &mut self,
         unary_operator: &UnaryOperator,
@@ -327,22 +368,36 @@ impl SemanticAnalyzer {
                     );
                     return None;
                 }
-                if expression_type.value != Some(0.0) && expression_type.value != Some(1.0) {
-                    self.condition_value_error(span, expression_type.value.unwrap().to_string());
+                // Only a known value can be checked, anything else is decided at runtime
+                if let Some(value) = expression_type.value
+                    && value != 0.0
+                    && value != 1.0
+                {
+                    self.condition_value_error(span, value.to_string());
                     return None;
                 }
 
-                let negated_value = match expression_type.value {
-                    Some(value) => {
-                        if value == 0.0 {
-                            1.0
-                        } else {
-                            0.0
-                        }
-                    }
-                    None => 0.0,
+                let negated_value = expression_type
+                    .value
+                    .map(|value| if value == 0.0 { 1.0 } else { 0.0 });
+                Some(ValueType::new(Type::Int, negated_value))
+            }
+            UnaryOperator::Length => {
+                if expression_type.typ != Type::String {
+                    self.type_mismatch_error(
+                        span,
+                        &Type::String,
+                        &expression_type.typ,
+                        Some("len"),
+                    );
+                    return None;
+                }
+
+                let length = match self.evaluate_constant_expression(expression) {
+                    Some(LiteralKind::String(value)) => Some(value.chars().count() as f32),
+                    _ => None,
                 };
-                Some(ValueType::new(Type::Int, Some(negated_value)))
+                Some(ValueType::new(Type::Int, length))
             }
         }
     }
@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::parser::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, LiteralKind, Program, Statement,
+    StatementKind, Subprogram,
+};
+use crate::semantics::analyzer_core::SemanticAnalyzer;
+use crate::semantics::analyzer_core::subprogram_analyzer::expression_calls;
+use crate::semantics::error::SemanticError;
+use crate::semantics::symbol_table::SymbolKind;
+
+/// What has been written at some point of the program
+#[derive(Debug, Clone, Default)]
+struct Initialized {
+    /// Variables written on every path
+    variables: BTreeSet<String>,
+    /// Array elements written on every path, by constant index
+    elements: BTreeMap<String, BTreeSet<usize>>,
+    /// Arrays written on some path at an index only known at runtime
+    dynamic: BTreeSet<String>,
+}
+
+impl Initialized {
+    /// What is written when two paths meet
+    fn join(&self, other: &Initialized) -> Initialized {
+        let elements = self
+            .elements
+            .iter()
+            .filter_map(|(name, indices)| {
+                let common: BTreeSet<usize> = indices
+                    .intersection(other.elements.get(name)?)
+                    .copied()
+                    .collect();
+                Some((name.clone(), common))
+            })
+            .collect();
+        Initialized {
+            variables: self
+                .variables
+                .intersection(&other.variables)
+                .cloned()
+                .collect(),
+            elements,
+            dynamic: self.dynamic.union(&other.dynamic).cloned().collect(),
+        }
+    }
+
+    /// Replaces what is known about `name` by what `other` knows
+    fn copy_name(&mut self, name: &str, other: &Initialized) {
+        self.variables.remove(name);
+        if other.variables.contains(name) {
+            self.variables.insert(name.to_string());
+        }
+        match other.elements.get(name) {
+            Some(indices) => {
+                self.elements.insert(name.to_string(), indices.clone());
+            }
+            None => {
+                self.elements.remove(name);
+            }
+        }
+        self.dynamic.remove(name);
+        if other.dynamic.contains(name) {
+            self.dynamic.insert(name.to_string());
+        }
+    }
+}
+
+impl SemanticAnalyzer {
+    /// Warns about variables and array elements that may be read before being written.
+    ///
+    /// Writes count only once they happen on every path reaching the read. An array
+    /// written at an index unknown at compile time is assumed to be initialized
+    /// from there on, to avoid warning about arrays filled by loops.
+    pub fn check_initialization(&mut self, program: &Program) {
+        let mut state = Initialized::default();
+        for declaration in &program.declarations {
+            self.initialize_declaration(declaration, &mut state, true);
+        }
+        self.check_block(&program.statements, &mut state, true);
+    }
+
+    /// Same check for the body of a subprogram, `initialized` naming what holds a value
+    /// on entry: its parameters and the globals, which the caller may have set
+    pub(super) fn check_subprogram_initialization(
+        &mut self,
+        subprogram: &Subprogram,
+        initialized: &[String],
+    ) {
+        let mut state = Initialized::default();
+        for name in initialized {
+            match self.symbol_table.get(name).map(|symbol| &symbol.kind) {
+                Some(SymbolKind::Array(_)) => {
+                    state.dynamic.insert(name.clone());
+                }
+                _ => {
+                    state.variables.insert(name.clone());
+                }
+            }
+        }
+        for declaration in &subprogram.declarations {
+            self.initialize_declaration(declaration, &mut state, true);
+        }
+        self.check_block(&subprogram.statements, &mut state, true);
+    }
+
+    /// Globals written by subprograms count as initialized once one is called
+    fn initialize_call_writes(&self, state: &mut Initialized) {
+        for name in &self.subprogram_writes {
+            match self.symbol_table.get(name).map(|symbol| &symbol.kind) {
+                Some(SymbolKind::Array(_)) => {
+                    state.dynamic.insert(name.clone());
+                }
+                Some(_) => {
+                    state.variables.insert(name.clone());
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn initialize_declaration(
+        &mut self,
+        declaration: &Declaration,
+        state: &mut Initialized,
+        report: bool,
+    ) {
+        // Initializers read the names the declaration may hide
+        let forget = |state: &mut Initialized| {
+            for name in declaration.node.names() {
+                state.copy_name(name, &Initialized::default());
+            }
+        };
+        match &declaration.node {
+            DeclarationKind::VariableWithInit(names, _, init) => {
+                self.check_reads(init, state, report);
+                forget(state);
+                state.variables.extend(names.iter().cloned());
+            }
+            DeclarationKind::ArrayWithInit(names, _, _, values) => {
+                for value in values {
+                    self.check_reads(value, state, report);
+                }
+                forget(state);
+                for name in names {
+                    state
+                        .elements
+                        .insert(name.clone(), (0..values.len()).collect());
+                }
+            }
+            DeclarationKind::Constant(name, _, _) => {
+                forget(state);
+                state.variables.insert(name.clone());
+            }
+            DeclarationKind::Variable(..) | DeclarationKind::Array(..) => forget(state),
+        }
+    }
+
+    fn check_block(&mut self, statements: &[Statement], state: &mut Initialized, report: bool) {
+        for statement in statements {
+            self.check_statement(statement, state, report);
+        }
+    }
+
+    /// Follows one statement, only emitting warnings when `report` is set
+    fn check_statement(&mut self, statement: &Statement, state: &mut Initialized, report: bool) {
+        match &statement.node {
+            StatementKind::Assignment(target, value) => {
+                self.check_reads(value, state, report);
+                if expression_calls(value) {
+                    self.initialize_call_writes(state);
+                }
+                self.check_write(target, state, report);
+            }
+            StatementKind::Input(target) => self.check_write(target, state, report),
+            StatementKind::Output(expressions) => {
+                for expression in expressions {
+                    self.check_reads(expression, state, report);
+                }
+                if expressions.iter().any(expression_calls) {
+                    self.initialize_call_writes(state);
+                }
+            }
+            StatementKind::Call(_, arguments) => {
+                for argument in arguments {
+                    self.check_reads(argument, state, report);
+                }
+                self.initialize_call_writes(state);
+            }
+            StatementKind::Return(value) => {
+                if let Some(value) = value {
+                    self.check_reads(value, state, report);
+                }
+            }
+            StatementKind::IfThen(condition, then_block) => {
+                self.check_reads(condition, state, report);
+                let mut then_state = state.clone();
+                self.check_block(then_block, &mut then_state, report);
+                *state = state.join(&then_state);
+            }
+            StatementKind::IfThenElse(condition, then_block, else_block) => {
+                self.check_reads(condition, state, report);
+                let mut then_state = state.clone();
+                self.check_block(then_block, &mut then_state, report);
+                self.check_block(else_block, state, report);
+                *state = state.join(&then_state);
+            }
+            StatementKind::DoWhile(body, condition) => {
+                // The body runs at least once, later iterations start from what the previous one wrote
+                let entry = self.loop_entry(body, state);
+                *state = entry;
+                self.check_block(body, state, report);
+                self.check_reads(condition, state, report);
+            }
+            StatementKind::While(condition, body) => {
+                // Later tests of the condition only see more writes than the first one
+                self.check_reads(condition, state, report);
+                let mut body_state = self.loop_entry(body, state);
+                self.check_block(body, &mut body_state, report);
+                *state = state.join(&body_state);
+            }
+            StatementKind::For(iterator, init, end, step, body) => {
+                self.check_reads(init, state, report);
+                self.check_reads(end, state, report);
+                self.check_reads(step, state, report);
+                self.check_write(iterator, state, report);
+                // The body may not run at all
+                let mut body_state = self.loop_entry(body, state);
+                self.check_block(body, &mut body_state, report);
+                *state = state.join(&body_state);
+            }
+            StatementKind::Scope(declarations, statements) => {
+                self.check_scope(statement, declarations, statements, state, report)
+            }
+            StatementKind::Empty => {}
+        }
+    }
+
+    /// Names declared by a block hold nothing until written, even when they hide names
+    /// of an enclosing scope. What was known about those is back after the block.
+    fn check_scope(
+        &mut self,
+        block: &Statement,
+        declarations: &[Declaration],
+        statements: &[Statement],
+        state: &mut Initialized,
+        report: bool,
+    ) {
+        self.enter_block(&block.span);
+        let outer = state.clone();
+        let names: Vec<&str> = declarations
+            .iter()
+            .flat_map(|declaration| declaration.node.names())
+            .collect();
+        for declaration in declarations {
+            self.initialize_declaration(declaration, state, report);
+        }
+        self.check_block(statements, state, report);
+        for name in &names {
+            state.copy_name(name, &outer);
+        }
+        self.exit_block();
+    }
+
+    /// State at the start of any iteration of a loop body entered with `state`.
+    /// Writes only accumulate, so one silent pass over the body is enough.
+    fn loop_entry(&mut self, body: &[Statement], state: &Initialized) -> Initialized {
+        let mut exit = state.clone();
+        self.check_block(body, &mut exit, false);
+        state.join(&exit)
+    }
+
+    fn check_write(&mut self, target: &Expression, state: &mut Initialized, report: bool) {
+        match &target.node {
+            ExpressionKind::Identifier(name) => {
+                state.variables.insert(name.clone());
+            }
+            ExpressionKind::ArrayAccess(name, index) => {
+                self.check_reads(index, state, report);
+                match self.constant_index(index) {
+                    Some(index) => {
+                        state
+                            .elements
+                            .entry(name.clone())
+                            .or_default()
+                            .insert(index);
+                    }
+                    None => {
+                        state.dynamic.insert(name.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_reads(&mut self, expression: &Expression, state: &Initialized, report: bool) {
+        match &expression.node {
+            ExpressionKind::Identifier(name) => {
+                let is_variable = self
+                    .symbol_table
+                    .get(name)
+                    .is_some_and(|symbol| symbol.kind == SymbolKind::Variable);
+                if report && is_variable && !state.variables.contains(name) {
+                    self.use_before_initialization_warning(expression, name, None);
+                }
+            }
+            ExpressionKind::ArrayAccess(name, index) => {
+                self.check_reads(index, state, report);
+                let Some(SymbolKind::Array(size)) = self
+                    .symbol_table
+                    .get(name)
+                    .map(|symbol| symbol.kind.clone())
+                else {
+                    return;
+                };
+                if !report || state.dynamic.contains(name) {
+                    return;
+                }
+                let written = state.elements.get(name);
+                let initialized = match self.constant_index(index) {
+                    // Out of bounds indices are reported on their own
+                    Some(constant) => {
+                        constant >= size || written.is_some_and(|set| set.contains(&constant))
+                    }
+                    None => written.is_some_and(|set| set.len() >= size),
+                };
+                if !initialized {
+                    self.use_before_initialization_warning(
+                        expression,
+                        name,
+                        Some(describe_index(index)),
+                    );
+                }
+            }
+            ExpressionKind::BinaryOp(left, _, right) => {
+                self.check_reads(left, state, report);
+                self.check_reads(right, state, report);
+            }
+            ExpressionKind::UnaryOp(_, operand) => self.check_reads(operand, state, report),
+            ExpressionKind::Call(_, arguments) => {
+                for argument in arguments {
+                    self.check_reads(argument, state, report);
+                }
+            }
+            ExpressionKind::Literal(_) => {}
+        }
+    }
+
+    fn constant_index(&mut self, index: &Expression) -> Option<usize> {
+        match self.evaluate_constant_expression(index)? {
+            LiteralKind::Int(value) if value >= 0 => Some(value as usize),
+            _ => None,
+        }
+    }
+
+    fn use_before_initialization_warning(
+        &mut self,
+        expression: &Expression,
+        name: &str,
+        index: Option<String>,
+    ) {
+        self.add_error(SemanticError::UseBeforeInitialization {
+            name: name.to_string(),
+            index,
+            line: self.source_map.get_line(&expression.span),
+            column: self.source_map.get_column(&expression.span),
+        });
+    }
+}
+
+/// Short text for an index in messages, the index itself when it is simple enough
+fn describe_index(index: &Expression) -> String {
+    match &index.node {
+        ExpressionKind::Literal(literal) => LiteralKind::format_literal(&literal.node),
+        ExpressionKind::Identifier(name) => name.clone(),
+        _ => "...".to_string(),
+    }
+}
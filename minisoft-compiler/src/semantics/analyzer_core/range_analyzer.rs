@@ -0,0 +1,644 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::parser::ast::{
+    Declaration, Expression, ExpressionKind, LiteralKind, Operator, Program, Statement,
+    StatementKind, Type, UnaryOperator,
+};
+use crate::semantics::analyzer_core::SemanticAnalyzer;
+use crate::semantics::analyzer_core::subprogram_analyzer::expression_calls;
+use crate::semantics::error::SemanticError;
+use crate::semantics::symbol_table::{SymbolKind, SymbolValue};
+
+/// Loop iterations after which growing bounds jump to infinity
+const WIDENING_DELAY: usize = 3;
+
+/// Integer values between two bounds, `None` standing for infinity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Interval {
+    low: Option<i64>,
+    high: Option<i64>,
+}
+
+impl Interval {
+    const TOP: Interval = Interval {
+        low: None,
+        high: None,
+    };
+    const BOOLEAN: Interval = Interval {
+        low: Some(0),
+        high: Some(1),
+    };
+
+    fn constant(value: i64) -> Self {
+        Interval {
+            low: Some(value),
+            high: Some(value),
+        }
+    }
+
+    fn bounded(&self) -> Option<(i64, i64)> {
+        Some((self.low?, self.high?))
+    }
+
+    /// Smallest interval holding both
+    fn hull(&self, other: &Interval) -> Interval {
+        Interval {
+            low: self.low.zip(other.low).map(|(a, b)| a.min(b)),
+            high: self.high.zip(other.high).map(|(a, b)| a.max(b)),
+        }
+    }
+
+    /// Keeps stable bounds and sends the ones still moving to infinity
+    fn widen(&self, next: &Interval) -> Interval {
+        Interval {
+            low: self
+                .low
+                .zip(next.low)
+                .filter(|(old, new)| new >= old)
+                .map(|(old, _)| old),
+            high: self
+                .high
+                .zip(next.high)
+                .filter(|(old, new)| new <= old)
+                .map(|(old, _)| old),
+        }
+    }
+
+    /// `None` when no value satisfies both
+    fn intersect(&self, other: &Interval) -> Option<Interval> {
+        let low = match (self.low, other.low) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let high = match (self.high, other.high) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        match (low, high) {
+            (Some(low), Some(high)) if low > high => None,
+            _ => Some(Interval { low, high }),
+        }
+    }
+
+    fn add(&self, other: &Interval) -> Interval {
+        Interval {
+            low: self.low.zip(other.low).and_then(|(a, b)| a.checked_add(b)),
+            high: self
+                .high
+                .zip(other.high)
+                .and_then(|(a, b)| a.checked_add(b)),
+        }
+    }
+
+    fn subtract(&self, other: &Interval) -> Interval {
+        Interval {
+            low: self.low.zip(other.high).and_then(|(a, b)| a.checked_sub(b)),
+            high: self.high.zip(other.low).and_then(|(a, b)| a.checked_sub(b)),
+        }
+    }
+
+    /// Extremes of an operation over the corners of two bounded intervals
+    fn corners(&self, other: &Interval, operation: fn(i64, i64) -> Option<i64>) -> Interval {
+        let (Some((a, b)), Some((c, d))) = (self.bounded(), other.bounded()) else {
+            return Interval::TOP;
+        };
+        let values: Option<Vec<i64>> = [(a, c), (a, d), (b, c), (b, d)]
+            .into_iter()
+            .map(|(x, y)| operation(x, y))
+            .collect();
+        match values {
+            Some(values) => Interval {
+                low: values.iter().min().copied(),
+                high: values.iter().max().copied(),
+            },
+            None => Interval::TOP,
+        }
+    }
+
+    fn multiply(&self, other: &Interval) -> Interval {
+        self.corners(other, i64::checked_mul)
+    }
+
+    /// Division truncates, so it is monotonic as long as the divisor doesn't cross zero
+    fn divide(&self, other: &Interval) -> Interval {
+        match other.bounded() {
+            Some((low, high)) if low > 0 || high < 0 => self.corners(other, i64::checked_div),
+            _ => Interval::TOP,
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.low {
+            Some(low) => write!(f, "[{}, ", low)?,
+            None => write!(f, "[-inf, ")?,
+        }
+        match self.high {
+            Some(high) => write!(f, "{}]", high),
+            None => write!(f, "+inf]"),
+        }
+    }
+}
+
+/// Ranges of the `Int` variables at a point, `None` when the point can't be reached.
+/// Variables missing from the map may hold any value.
+type Ranges = Option<BTreeMap<String, Interval>>;
+
+fn join(left: &Ranges, right: &Ranges) -> Ranges {
+    match (left, right) {
+        (None, ranges) | (ranges, None) => ranges.clone(),
+        (Some(left), Some(right)) => Some(
+            left.iter()
+                .filter_map(|(name, range)| Some((name.clone(), range.hull(right.get(name)?))))
+                .collect(),
+        ),
+    }
+}
+
+fn widen(old: &Ranges, next: &Ranges) -> Ranges {
+    match (old, next) {
+        (Some(old), Some(next)) => Some(
+            next.iter()
+                .filter_map(|(name, range)| Some((name.clone(), old.get(name)?.widen(range))))
+                .collect(),
+        ),
+        _ => next.clone(),
+    }
+}
+
+/// Which way a `for` loop counts
+#[derive(Clone, Copy)]
+enum Direction {
+    Up,
+    Down,
+    Unknown,
+}
+
+impl SemanticAnalyzer {
+    /// Reports array accesses whose index range, computed by interval analysis over
+    /// `Int` variables, lies outside the array (an error) or crosses one of its ends
+    /// (a warning). Indices that are plain literals are checked on their own.
+    pub fn check_index_ranges(&mut self, program: &Program) {
+        self.check_statement_ranges(&program.statements);
+    }
+
+    /// Same check for statements starting with nothing known, such as a subprogram body
+    pub(super) fn check_statement_ranges(&mut self, statements: &[Statement]) {
+        let mut ranges: Ranges = Some(BTreeMap::new());
+        self.range_block(statements, &mut ranges, true);
+    }
+
+    /// Globals written by subprograms may hold anything after a call
+    fn forget_call_writes(&self, ranges: &mut Ranges) {
+        if let Some(values) = ranges {
+            values.retain(|name, _| !self.subprogram_writes.contains(name));
+        }
+    }
+
+    fn range_block(&mut self, statements: &[Statement], ranges: &mut Ranges, report: bool) {
+        for statement in statements {
+            self.range_statement(statement, ranges, report);
+        }
+    }
+
+    fn range_statement(&mut self, statement: &Statement, ranges: &mut Ranges, report: bool) {
+        match &statement.node {
+            StatementKind::Assignment(target, value) => {
+                self.check_ranges(value, ranges, report);
+                self.check_ranges(target, ranges, report);
+                let interval = self.interval_of(value, ranges);
+                if expression_calls(value) {
+                    self.forget_call_writes(ranges);
+                }
+                self.assign_range(target, interval, ranges);
+            }
+            StatementKind::Input(target) => {
+                self.check_ranges(target, ranges, report);
+                self.assign_range(target, Interval::TOP, ranges);
+            }
+            StatementKind::Output(expressions) => {
+                for expression in expressions {
+                    self.check_ranges(expression, ranges, report);
+                }
+                if expressions.iter().any(expression_calls) {
+                    self.forget_call_writes(ranges);
+                }
+            }
+            StatementKind::Call(_, arguments) => {
+                for argument in arguments {
+                    self.check_ranges(argument, ranges, report);
+                }
+                self.forget_call_writes(ranges);
+            }
+            // Nothing after a return runs
+            StatementKind::Return(value) => {
+                if let Some(value) = value {
+                    self.check_ranges(value, ranges, report);
+                }
+                *ranges = None;
+            }
+            StatementKind::IfThen(condition, then_block) => {
+                self.check_ranges(condition, ranges, report);
+                let mut then_ranges = self.refine(condition, ranges, true);
+                self.range_block(then_block, &mut then_ranges, report);
+                let else_ranges = self.refine(condition, ranges, false);
+                *ranges = join(&then_ranges, &else_ranges);
+            }
+            StatementKind::IfThenElse(condition, then_block, else_block) => {
+                self.check_ranges(condition, ranges, report);
+                let mut then_ranges = self.refine(condition, ranges, true);
+                self.range_block(then_block, &mut then_ranges, report);
+                let mut else_ranges = self.refine(condition, ranges, false);
+                self.range_block(else_block, &mut else_ranges, report);
+                *ranges = join(&then_ranges, &else_ranges);
+            }
+            StatementKind::DoWhile(body, condition) => {
+                let entry = ranges.clone();
+                let head = self.loop_head(&entry, |analyzer, head| {
+                    let mut exit = head.clone();
+                    analyzer.range_block(body, &mut exit, false);
+                    analyzer.refine(condition, &exit, true)
+                });
+                let mut exit = head;
+                self.range_block(body, &mut exit, report);
+                self.check_ranges(condition, &exit, report);
+                *ranges = self.refine(condition, &exit, false);
+            }
+            StatementKind::While(condition, body) => {
+                // The condition is tested before every iteration, the body may not run at all
+                let entry = ranges.clone();
+                let head = self.loop_head(&entry, |analyzer, head| {
+                    let mut exit = analyzer.refine(condition, head, true);
+                    analyzer.range_block(body, &mut exit, false);
+                    exit
+                });
+                self.check_ranges(condition, &head, report);
+                let mut body_ranges = self.refine(condition, &head, true);
+                self.range_block(body, &mut body_ranges, report);
+                *ranges = self.refine(condition, &head, false);
+            }
+            StatementKind::For(iterator, init, end, step, body) => {
+                self.check_ranges(init, ranges, report);
+                self.check_ranges(end, ranges, report);
+                self.check_ranges(step, ranges, report);
+                let ExpressionKind::Identifier(name) = &iterator.node else {
+                    return;
+                };
+                // Bounds and step are evaluated once, before the first iteration
+                let end = self.interval_of(end, ranges);
+                let step = self.interval_of(step, ranges);
+                let direction = match step.bounded() {
+                    Some((low, _)) if low > 0 => Direction::Up,
+                    Some((_, high)) if high < 0 => Direction::Down,
+                    _ => Direction::Unknown,
+                };
+                let init = self.interval_of(init, ranges);
+                self.assign_range(iterator, init, ranges);
+
+                let entry = ranges.clone();
+                let head = self.loop_head(&entry, |analyzer, head| {
+                    let mut exit = limit_iterator(head, name, &end, direction, true);
+                    analyzer.range_block(body, &mut exit, false);
+                    analyzer.step_iterator(iterator, &step, &mut exit);
+                    exit
+                });
+                let mut exit = limit_iterator(&head, name, &end, direction, true);
+                self.range_block(body, &mut exit, report);
+                *ranges = limit_iterator(&head, name, &end, direction, false);
+            }
+            StatementKind::Scope(declarations, statements) => {
+                self.range_scope(statement, declarations, statements, ranges, report)
+            }
+            StatementKind::Empty => {}
+        }
+    }
+
+    /// Nothing is known about the names a block declares, which may hide names of an
+    /// enclosing scope whose ranges are back after the block
+    fn range_scope(
+        &mut self,
+        block: &Statement,
+        declarations: &[Declaration],
+        statements: &[Statement],
+        ranges: &mut Ranges,
+        report: bool,
+    ) {
+        self.enter_block(&block.span);
+        let outer = ranges.clone();
+        let names: Vec<&str> = declarations
+            .iter()
+            .flat_map(|declaration| declaration.node.names())
+            .collect();
+        if let Some(values) = ranges {
+            values.retain(|name, _| !names.contains(&name.as_str()));
+        }
+        self.range_block(statements, ranges, report);
+        if let (Some(values), Some(outer)) = (ranges, &outer) {
+            for name in names {
+                match outer.get(name) {
+                    Some(range) => values.insert(name.to_string(), *range),
+                    None => values.remove(name),
+                };
+            }
+        }
+        self.exit_block();
+    }
+
+    /// Ranges at the head of a loop entered with `entry`, `back_edge` giving the ranges
+    /// flowing back from one iteration started with the given head ranges
+    fn loop_head(
+        &mut self,
+        entry: &Ranges,
+        mut back_edge: impl FnMut(&mut Self, &Ranges) -> Ranges,
+    ) -> Ranges {
+        let mut head = entry.clone();
+        let mut iteration = 0;
+        loop {
+            let mut next = join(entry, &back_edge(self, &head));
+            if iteration >= WIDENING_DELAY {
+                next = widen(&head, &next);
+            }
+            if next == head {
+                break;
+            }
+            head = next;
+            iteration += 1;
+        }
+        // One more iteration without widening tightens bounds the condition limits
+        join(entry, &back_edge(self, &head))
+    }
+
+    fn step_iterator(&mut self, iterator: &Expression, step: &Interval, ranges: &mut Ranges) {
+        let stepped = self.interval_of(iterator, ranges).add(step);
+        self.assign_range(iterator, stepped, ranges);
+    }
+
+    fn assign_range(&self, target: &Expression, value: Interval, ranges: &mut Ranges) {
+        let (ExpressionKind::Identifier(name), Some(ranges)) = (&target.node, ranges) else {
+            return;
+        };
+        if self.is_int_variable(name) {
+            ranges.insert(name.clone(), value);
+        }
+    }
+
+    fn is_int_variable(&self, name: &str) -> bool {
+        self.symbol_table.get(name).is_some_and(|symbol| {
+            symbol.kind == SymbolKind::Variable && symbol.symbol_type == Type::Int
+        })
+    }
+
+    /// Values an `Int` expression may take, anything for other types
+    fn interval_of(&self, expression: &Expression, ranges: &Ranges) -> Interval {
+        let Some(values) = ranges else {
+            return Interval::TOP;
+        };
+        match &expression.node {
+            ExpressionKind::Literal(literal) => match literal.node {
+                LiteralKind::Int(value) => Interval::constant(value as i64),
+                _ => Interval::TOP,
+            },
+            ExpressionKind::Identifier(name) => match self.symbol_table.get(name) {
+                Some(symbol) if symbol.is_constant => match &symbol.value {
+                    SymbolValue::Single(LiteralKind::Int(value)) => {
+                        Interval::constant(*value as i64)
+                    }
+                    _ => Interval::TOP,
+                },
+                _ => values.get(name).copied().unwrap_or(Interval::TOP),
+            },
+            ExpressionKind::BinaryOp(left, operator, right) => {
+                let left = self.interval_of(left, ranges);
+                let right = self.interval_of(right, ranges);
+                match operator {
+                    Operator::Add => left.add(&right),
+                    Operator::Subtract => left.subtract(&right),
+                    Operator::Multiply => left.multiply(&right),
+                    Operator::Divide => left.divide(&right),
+                    _ => Interval::BOOLEAN,
+                }
+            }
+            ExpressionKind::UnaryOp(UnaryOperator::Not, _) => Interval::BOOLEAN,
+            ExpressionKind::UnaryOp(UnaryOperator::Length, _) => Interval {
+                low: Some(0),
+                high: None,
+            },
+            ExpressionKind::ArrayAccess(..) | ExpressionKind::Call(..) => Interval::TOP,
+        }
+    }
+
+    /// Ranges where `condition` evaluates to `holds`
+    fn refine(&self, condition: &Expression, ranges: &Ranges, holds: bool) -> Ranges {
+        ranges.as_ref()?;
+        match &condition.node {
+            ExpressionKind::UnaryOp(UnaryOperator::Not, operand) => {
+                self.refine(operand, ranges, !holds)
+            }
+            // Both sides hold when an And holds, both fail when an Or fails
+            ExpressionKind::BinaryOp(left, Operator::And, right) if holds => {
+                let ranges = self.refine(left, ranges, true);
+                self.refine(right, &ranges, true)
+            }
+            ExpressionKind::BinaryOp(left, Operator::Or, right) if !holds => {
+                let ranges = self.refine(left, ranges, false);
+                self.refine(right, &ranges, false)
+            }
+            ExpressionKind::BinaryOp(left, operator, right) => {
+                let Some(operator) = comparison(operator, holds) else {
+                    return ranges.clone();
+                };
+                let ranges = self.constrain(left, &operator, right, ranges);
+                self.constrain(right, &mirror(operator), left, &ranges)
+            }
+            _ => match self.interval_of(condition, ranges).bounded() {
+                // A condition known to be false (or true) never holds (or fails)
+                Some((0, 0)) if holds => None,
+                Some((low, _)) if low != 0 && !holds => None,
+                _ => ranges.clone(),
+            },
+        }
+    }
+
+    /// Narrows the range of `target`, when it is an `Int` variable, so that `target operator other` holds
+    fn constrain(
+        &self,
+        target: &Expression,
+        operator: &Operator,
+        other: &Expression,
+        ranges: &Ranges,
+    ) -> Ranges {
+        let ExpressionKind::Identifier(name) = &target.node else {
+            return ranges.clone();
+        };
+        if !self.is_int_variable(name) {
+            return ranges.clone();
+        }
+        let current = self.interval_of(target, ranges);
+        let other = self.interval_of(other, ranges);
+        let allowed = match operator {
+            Operator::LessThan => Interval {
+                low: None,
+                high: other.high.map(|high| high - 1),
+            },
+            Operator::LessEqual => Interval {
+                low: None,
+                high: other.high,
+            },
+            Operator::GreaterThan => Interval {
+                low: other.low.map(|low| low + 1),
+                high: None,
+            },
+            Operator::GreaterEqual => Interval {
+                low: other.low,
+                high: None,
+            },
+            Operator::Equal => other,
+            // Only a single excluded value at one end of the range can be cut off
+            _ => match (other.bounded(), current.bounded()) {
+                (Some((value, excluded)), Some((low, high))) if value == excluded => {
+                    if value == low {
+                        Interval {
+                            low: Some(low + 1),
+                            high: None,
+                        }
+                    } else if value == high {
+                        Interval {
+                            low: None,
+                            high: Some(high - 1),
+                        }
+                    } else {
+                        Interval::TOP
+                    }
+                }
+                _ => Interval::TOP,
+            },
+        };
+        let mut values = ranges.clone()?;
+        values.insert(name.clone(), current.intersect(&allowed)?);
+        Some(values)
+    }
+
+    /// Checks the array accesses of an expression against the ranges of their indices
+    fn check_ranges(&mut self, expression: &Expression, ranges: &Ranges, report: bool) {
+        match &expression.node {
+            ExpressionKind::ArrayAccess(name, index) => {
+                self.check_ranges(index, ranges, report);
+                if !report || ranges.is_none() {
+                    return;
+                }
+                // Literal indices are already checked by the expression analyzer
+                if let ExpressionKind::Literal(_) = index.node {
+                    return;
+                }
+                let Some(SymbolKind::Array(size)) = self
+                    .symbol_table
+                    .get(name)
+                    .map(|symbol| symbol.kind.clone())
+                else {
+                    return;
+                };
+                let range = self.interval_of(index, ranges);
+                let size = size as i64;
+                let below = range.high.is_some_and(|high| high < 0);
+                let above = range.low.is_some_and(|low| low >= size);
+                let may_cross = range.low.is_some_and(|low| low < 0)
+                    || range.high.is_some_and(|high| high >= size);
+                if below || above || may_cross {
+                    self.index_range_error(index, name, &range, size as usize, below || above);
+                }
+            }
+            ExpressionKind::BinaryOp(left, _, right) => {
+                self.check_ranges(left, ranges, report);
+                self.check_ranges(right, ranges, report);
+            }
+            ExpressionKind::UnaryOp(_, operand) => self.check_ranges(operand, ranges, report),
+            ExpressionKind::Call(_, arguments) => {
+                for argument in arguments {
+                    self.check_ranges(argument, ranges, report);
+                }
+            }
+            ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => {}
+        }
+    }
+
+    fn index_range_error(
+        &mut self,
+        index: &Expression,
+        name: &str,
+        range: &Interval,
+        size: usize,
+        definite: bool,
+    ) {
+        self.add_error(SemanticError::IndexRangeOutOfBounds {
+            name: name.to_string(),
+            range: range.to_string(),
+            size,
+            definite,
+            line: self.source_map.get_line(&index.span),
+            column: self.source_map.get_column(&index.span),
+        });
+    }
+}
+
+/// Ranges at the head of a `for` loop limited to the iterations that run the body,
+/// or to the ones leaving the loop. The end bound is inclusive.
+fn limit_iterator(
+    ranges: &Ranges,
+    name: &str,
+    end: &Interval,
+    direction: Direction,
+    runs: bool,
+) -> Ranges {
+    let mut values = ranges.clone()?;
+    let Some(current) = values.get(name).copied() else {
+        return Some(values);
+    };
+    let allowed = match (direction, runs) {
+        (Direction::Up, true) => Interval {
+            low: None,
+            high: end.high,
+        },
+        (Direction::Up, false) => Interval {
+            low: end.low.map(|low| low + 1),
+            high: None,
+        },
+        (Direction::Down, true) => Interval {
+            low: end.low,
+            high: None,
+        },
+        (Direction::Down, false) => Interval {
+            low: None,
+            high: end.high.map(|high| high - 1),
+        },
+        (Direction::Unknown, _) => Interval::TOP,
+    };
+    values.insert(name.to_string(), current.intersect(&allowed)?);
+    Some(values)
+}
+
+/// The comparison holding when `operator` evaluates to `holds`
+fn comparison(operator: &Operator, holds: bool) -> Option<Operator> {
+    let (positive, negated) = match operator {
+        Operator::LessThan => (Operator::LessThan, Operator::GreaterEqual),
+        Operator::LessEqual => (Operator::LessEqual, Operator::GreaterThan),
+        Operator::GreaterThan => (Operator::GreaterThan, Operator::LessEqual),
+        Operator::GreaterEqual => (Operator::GreaterEqual, Operator::LessThan),
+        Operator::Equal => (Operator::Equal, Operator::NotEqual),
+        Operator::NotEqual => (Operator::NotEqual, Operator::Equal),
+        _ => return None,
+    };
+    Some(if holds { positive } else { negated })
+}
+
+/// `b mirror(op) a` holds exactly when `a op b` does
+fn mirror(operator: Operator) -> Operator {
+    match operator {
+        Operator::LessThan => Operator::GreaterThan,
+        Operator::LessEqual => Operator::GreaterEqual,
+        Operator::GreaterThan => Operator::LessThan,
+        Operator::GreaterEqual => Operator::LessEqual,
+        operator => operator,
+    }
+}
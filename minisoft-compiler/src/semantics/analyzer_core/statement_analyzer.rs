@@ -1,6 +1,8 @@
-use crate::parser::ast::{Expression, ExpressionKind, Statement, StatementKind, Type};
+use crate::parser::ast::{Expression, ExpressionKind, LiteralKind, Statement, StatementKind, Type};
 use crate::semantics::analyzer_core::SemanticAnalyzer;
+use crate::semantics::analyzer_core::subprogram_analyzer::{expression_calls, statements_call};
 use crate::semantics::symbol_table::SymbolKind;
+use std::collections::HashSet;
 
 impl SemanticAnalyzer {
     pub fn analyze_statement(&mut self, stmt: &Statement) {
@@ -11,11 +13,19 @@ impl SemanticAnalyzer {
 
             StatementKind::IfThen(condition, then_block) => {
                 self.handle_condition(condition, Some("if condition"));
+                if self.constant_condition(condition) == Some(false) {
+                    self.unreachable_block(then_block, "then branch");
+                }
                 self.handle_scope(then_block);
             }
 
             StatementKind::IfThenElse(condition, then_block, else_block) => {
                 self.handle_condition(condition, Some("if-else condition"));
+                match self.constant_condition(condition) {
+                    Some(false) => self.unreachable_block(then_block, "then branch"),
+                    Some(true) => self.unreachable_block(else_block, "else branch"),
+                    None => {}
+                }
                 self.handle_scope(then_block);
                 self.handle_scope(else_block);
             }
@@ -25,6 +35,16 @@ impl SemanticAnalyzer {
                 self.analyze_expression(condition);
 
                 self.handle_condition(condition, Some("do-while condition"));
+                self.handle_loop_termination(body, condition);
+            }
+
+            StatementKind::While(condition, body) => {
+                self.handle_condition(condition, Some("while condition"));
+                if self.constant_condition(condition) == Some(false) {
+                    self.unreachable_block(body, "while body");
+                }
+                self.handle_scope(body);
+                self.handle_loop_termination(body, condition);
             }
 
             StatementKind::For(iterator, init, end, step, body) => {
@@ -39,8 +59,21 @@ impl SemanticAnalyzer {
                 self.handle_output(expressions);
             }
 
-            StatementKind::Scope(statements) => {
+            StatementKind::Scope(declarations, statements) => {
+                self.enter_block(&stmt.span);
+                for declaration in declarations {
+                    self.analyze_declaration(declaration);
+                }
                 self.handle_scope(statements);
+                self.exit_block();
+            }
+
+            StatementKind::Call(name, arguments) => {
+                self.handle_call(name, arguments, &stmt.span);
+            }
+
+            StatementKind::Return(value) => {
+                self.handle_return(value.as_ref(), &stmt.span);
             }
 
             StatementKind::Empty => {
@@ -102,10 +135,106 @@ impl SemanticAnalyzer {
         }
     }
 
-    fn handle_scope(&mut self, then_block: &Vec<Statement>) {
+    pub(super) fn handle_scope(&mut self, then_block: &Vec<Statement>) {
         for stmt in then_block {
             self.analyze_statement(stmt);
         }
+
+        // Nothing after a loop that never ends can run
+        let after_infinite_loop = then_block
+            .iter()
+            .position(|stmt| self.never_terminates(stmt))
+            .and_then(|index| then_block.get(index + 1));
+        if let Some(next) = after_infinite_loop {
+            self.unreachable_code_warning(&next.span, "statement after an infinite loop");
+        }
+
+        let after_return = then_block
+            .iter()
+            .position(|stmt| matches!(stmt.node, StatementKind::Return(_)))
+            .and_then(|index| then_block.get(index + 1));
+        if let Some(next) = after_return {
+            self.unreachable_code_warning(&next.span, "statement after a return");
+        }
+    }
+
+    /// Value of a condition known at compile time, `None` when it depends on the input
+    fn constant_condition(&mut self, condition: &Expression) -> Option<bool> {
+        match self.evaluate_constant_expression(condition)? {
+            LiteralKind::Int(value) => Some(value != 0),
+            _ => None,
+        }
+    }
+
+    pub(super) fn never_terminates(&mut self, stmt: &Statement) -> bool {
+        match &stmt.node {
+            StatementKind::DoWhile(_, condition) | StatementKind::While(condition, _) => {
+                self.constant_condition(condition) == Some(true)
+            }
+            _ => false,
+        }
+    }
+
+    fn unreachable_block(&mut self, block: &[Statement], context: &str) {
+        if let Some(first) = block.first() {
+            self.unreachable_code_warning(&first.span, context);
+        }
+    }
+
+    /// A loop repeats forever when its condition is always true, or once it is true
+    /// if the body never changes what the condition reads. A body that always returns
+    /// leaves the loop before it can repeat.
+    fn handle_loop_termination(&mut self, body: &[Statement], condition: &Expression) {
+        if self.always_returns(body) {
+            return;
+        }
+        match self.constant_condition(condition) {
+            Some(true) => self.infinite_loop_warning(&condition.span, true),
+            Some(false) => {}
+            None => {
+                let mut read = HashSet::new();
+                self.collect_variables(condition, &mut read);
+                let mut written = HashSet::new();
+                collect_assigned(body, &mut written);
+                // A call may write any global a subprogram writes, a function may
+                // return something else every time
+                if statements_call(body) {
+                    written.extend(self.subprogram_writes.iter().cloned());
+                }
+                if expression_calls(condition) {
+                    return;
+                }
+                if !read.is_empty() && read.is_disjoint(&written) {
+                    self.infinite_loop_warning(&condition.span, false);
+                }
+            }
+        }
+    }
+
+    /// Variables and arrays an expression reads, constants excluded
+    fn collect_variables(&self, expression: &Expression, names: &mut HashSet<String>) {
+        match &expression.node {
+            ExpressionKind::Identifier(name) => {
+                if self.symbol_table.get(name).is_some_and(|symbol| !symbol.is_constant) {
+                    names.insert(name.clone());
+                }
+            }
+            ExpressionKind::ArrayAccess(name, index) => {
+                names.insert(name.clone());
+                self.collect_variables(index, names);
+            }
+            ExpressionKind::BinaryOp(left, _, right) => {
+                self.collect_variables(left, names);
+                self.collect_variables(right, names);
+            }
+            ExpressionKind::UnaryOp(_, operand) => self.collect_variables(operand, names),
+            ExpressionKind::Call(_, arguments) => {
+                for argument in arguments {
+                    self.collect_variables(argument, names);
+                }
+            }
+            ExpressionKind::Literal(_) => {}
+        }
     }
 
     fn handle_forloop(
@@ -163,9 +292,27 @@ impl SemanticAnalyzer {
                     step_type.get_type(),
                     Some("for loop step"),
                 );
+            } else if let Some(LiteralKind::Int(0)) = self.evaluate_constant_expression(step) {
+                self.zero_loop_step_error(&step.span);
             }
         }
 
+        // A body that can't run at all is unreachable
+        let bounds = (
+            self.evaluate_constant_expression(init),
+            self.evaluate_constant_expression(end),
+            self.evaluate_constant_expression(step),
+        );
+        if let (
+            Some(LiteralKind::Int(init)),
+            Some(LiteralKind::Int(end)),
+            Some(LiteralKind::Int(step)),
+        ) = bounds
+            && ((step > 0 && init > end) || (step < 0 && init < end))
+        {
+            self.unreachable_block(body, "loop body");
+        }
+
         // Analyze loop body
         self.handle_scope(body);
     }
@@ -193,3 +340,36 @@ impl SemanticAnalyzer {
         }
     }
 }
+
+/// Names written by the statements, at any depth
+pub(super) fn collect_assigned(statements: &[Statement], names: &mut HashSet<String>) {
+    for stmt in statements {
+        match &stmt.node {
+            StatementKind::Assignment(target, _) | StatementKind::Input(target) => {
+                if let ExpressionKind::Identifier(name) | ExpressionKind::ArrayAccess(name, _) =
+                    &target.node
+                {
+                    names.insert(name.clone());
+                }
+            }
+            StatementKind::For(iterator, .., body) => {
+                if let ExpressionKind::Identifier(name) = &iterator.node {
+                    names.insert(name.clone());
+                }
+                collect_assigned(body, names);
+            }
+            StatementKind::IfThen(_, body)
+            | StatementKind::DoWhile(body, _)
+            | StatementKind::While(_, body)
+            | StatementKind::Scope(_, body) => collect_assigned(body, names),
+            StatementKind::IfThenElse(_, then_block, else_block) => {
+                collect_assigned(then_block, names);
+                collect_assigned(else_block, names);
+            }
+            StatementKind::Output(_)
+            | StatementKind::Call(..)
+            | StatementKind::Return(_)
+            | StatementKind::Empty => {}
+        }
+    }
+}
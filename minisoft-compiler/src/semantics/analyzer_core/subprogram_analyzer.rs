@@ -0,0 +1,395 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::parser::ast::{
+    Expression, ExpressionKind, LENGTH_BUILTIN, Located, ParameterKind, Statement, StatementKind,
+    Subprogram, Type,
+};
+use crate::semantics::analyzer_core::SemanticAnalyzer;
+use crate::semantics::analyzer_core::expression_analyzer::ValueType;
+use crate::semantics::analyzer_core::statement_analyzer::collect_assigned;
+use crate::semantics::error::SemanticError;
+use crate::semantics::symbol_table::{Symbol, SymbolKind, SymbolValue};
+
+/// What a call needs to know about a subprogram
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub parameters: Vec<ParameterKind>,
+    /// `None` for a procedure
+    pub return_type: Option<Type>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SemanticAnalyzer {
+    /// Records the signature of every subprogram before any body is analyzed, so that
+    /// subprograms can call themselves and the ones declared after them. Returns the
+    /// subprograms declared, one whose name is already taken is left out.
+    pub(super) fn declare_subprograms<'a>(
+        &mut self,
+        subprograms: &'a [Located<Subprogram>],
+    ) -> Vec<&'a Located<Subprogram>> {
+        let mut declared = Vec::new();
+        for subprogram in subprograms {
+            let node = &subprogram.node;
+            if node.name == LENGTH_BUILTIN {
+                self.add_error(SemanticError::BuiltinRedefined {
+                    name: node.name.clone(),
+                    line: self.source_map.get_line(&subprogram.span),
+                    column: self.source_map.get_column(&subprogram.span),
+                });
+                continue;
+            }
+            let original = match self.signatures.get(&node.name) {
+                Some(signature) => Some((signature.line, signature.column)),
+                None => self
+                    .symbol_table
+                    .get(&node.name)
+                    .map(|symbol| (symbol.line, symbol.column)),
+            };
+            if let Some((line, column)) = original {
+                self.duplicate_declaration_error(&subprogram.span, &node.name, line, column);
+                continue;
+            }
+
+            // Globals a subprogram writes may change at any call
+            let locals: HashSet<&str> = node.local_names().into_iter().collect();
+            let mut written = HashSet::new();
+            collect_assigned(&node.statements, &mut written);
+            self.subprogram_writes.extend(
+                written
+                    .into_iter()
+                    .filter(|name| !locals.contains(name.as_str())),
+            );
+
+            self.signatures.insert(
+                node.name.clone(),
+                Signature {
+                    parameters: node
+                        .parameters
+                        .iter()
+                        .map(|parameter| parameter.node.clone())
+                        .collect(),
+                    return_type: node.return_type.clone(),
+                    line: self.source_map.get_line(&subprogram.span),
+                    column: self.source_map.get_column(&subprogram.span),
+                },
+            );
+            declared.push(subprogram);
+        }
+        declared
+    }
+
+    /// Checks a subprogram in a scope of its own holding its parameters and locals, which
+    /// may hide globals of the same name
+    pub(super) fn analyze_subprogram(&mut self, subprogram: &Located<Subprogram>) {
+        let node = &subprogram.node;
+        self.symbol_table.enter_scope(&node.name);
+        self.current_subprogram = Some(node.name.clone());
+
+        for parameter in &node.parameters {
+            self.declare_parameter(parameter);
+        }
+        for declaration in &node.declarations {
+            self.analyze_declaration(declaration);
+        }
+        self.handle_scope(&node.statements);
+
+        if node.return_type.is_some() && !self.always_returns(&node.statements) {
+            self.add_error(SemanticError::MissingReturn {
+                name: node.name.clone(),
+                line: self.source_map.get_line(&subprogram.span),
+                column: self.source_map.get_column(&subprogram.span),
+            });
+        }
+
+        // Parameters hold the arguments and globals may have been set before the call
+        let locals = node.local_names();
+        let mut initialized: Vec<String> = self
+            .symbol_table
+            .get_all()
+            .into_iter()
+            .filter(|symbol| symbol.scope.is_empty() && !locals.contains(&symbol.name.as_str()))
+            .map(|symbol| symbol.name.clone())
+            .collect();
+        initialized.extend(
+            node.parameters
+                .iter()
+                .map(|parameter| parameter.node.name.clone()),
+        );
+        self.check_subprogram_initialization(node, &initialized);
+        self.check_statement_ranges(&node.statements);
+
+        self.current_subprogram = None;
+        self.symbol_table.exit_scope();
+    }
+
+    fn declare_parameter(&mut self, parameter: &Located<ParameterKind>) {
+        let name = &parameter.node.name;
+        if !self.check_declaration(&parameter.span, name) {
+            return;
+        }
+        self.symbol_table.add_symbol(Symbol {
+            name: name.clone(),
+            kind: match parameter.node.size {
+                Some(size) => SymbolKind::Array(size),
+                None => SymbolKind::Variable,
+            },
+            symbol_type: parameter.node.typ.clone(),
+            value: SymbolValue::Uninitialized,
+            is_constant: false,
+            line: self.source_map.get_line(&parameter.span),
+            column: self.source_map.get_column(&parameter.span),
+            scope: String::new(),
+        });
+    }
+
+    /// Checks the arguments of a call against the parameters of the subprogram and
+    /// gives the type of its value, `None` for a procedure or an invalid call
+    pub(super) fn handle_call(
+        &mut self,
+        name: &str,
+        arguments: &[Expression],
+        span: &Range<usize>,
+    ) -> Option<ValueType> {
+        let Some(signature) = self.signatures.get(name).cloned() else {
+            self.add_error(SemanticError::UndefinedSubprogram {
+                name: name.to_string(),
+                line: self.source_map.get_line(span),
+                column: self.source_map.get_column(span),
+            });
+            for argument in arguments {
+                self.analyze_expression(argument);
+            }
+            return None;
+        };
+
+        if arguments.len() != signature.parameters.len() {
+            self.add_error(SemanticError::ArgumentCountMismatch {
+                name: name.to_string(),
+                expected: signature.parameters.len(),
+                found: arguments.len(),
+                line: self.source_map.get_line(span),
+                column: self.source_map.get_column(span),
+            });
+        }
+
+        for (position, argument) in arguments.iter().enumerate() {
+            let context = format!("argument {} of '{}'", position + 1, name);
+            let Some(parameter) = signature.parameters.get(position) else {
+                self.analyze_expression(argument);
+                continue;
+            };
+            match parameter.size {
+                // Arrays are passed whole, by naming an array of the same type and size
+                Some(_) => {
+                    let found = match &argument.node {
+                        ExpressionKind::Identifier(array) => {
+                            self.symbol_table
+                                .get(array)
+                                .map(|symbol| match symbol.kind {
+                                    SymbolKind::Array(size) => {
+                                        format!("[{}; {}]", symbol.symbol_type, size)
+                                    }
+                                    _ => symbol.symbol_type.to_string(),
+                                })
+                        }
+                        _ => self
+                            .analyze_expression(argument)
+                            .map(|value| value.get_type().to_string()),
+                    };
+                    if let ExpressionKind::Identifier(array) = &argument.node
+                        && found.is_none()
+                    {
+                        self.undeclared_identifier_error(&argument.span, array);
+                    }
+                    if let Some(found) = found
+                        && found != parameter.type_name()
+                    {
+                        self.argument_type_error(
+                            &argument.span,
+                            &parameter.type_name(),
+                            &found,
+                            &context,
+                        );
+                    }
+                }
+                None => {
+                    if let ExpressionKind::Identifier(array) = &argument.node
+                        && let Some(SymbolKind::Array(size)) = self
+                            .symbol_table
+                            .get(array)
+                            .map(|symbol| symbol.kind.clone())
+                    {
+                        let typ = self.symbol_table.get(array).unwrap().symbol_type.clone();
+                        self.argument_type_error(
+                            &argument.span,
+                            &parameter.type_name(),
+                            &format!("[{}; {}]", typ, size),
+                            &context,
+                        );
+                        continue;
+                    }
+                    if let Some(found) = self.analyze_expression(argument)
+                        && !found.get_type().is_compatible_with(&parameter.typ)
+                    {
+                        self.type_mismatch_error(
+                            &argument.span,
+                            &parameter.typ,
+                            found.get_type(),
+                            Some(&context),
+                        );
+                    }
+                }
+            }
+        }
+
+        signature.return_type.map(ValueType::from)
+    }
+
+    /// A call used as a value must name a function
+    pub(super) fn handle_call_expression(
+        &mut self,
+        name: &str,
+        arguments: &[Expression],
+        span: &Range<usize>,
+    ) -> Option<ValueType> {
+        let is_procedure = self
+            .signatures
+            .get(name)
+            .is_some_and(|signature| signature.return_type.is_none());
+        let value = self.handle_call(name, arguments, span);
+        if is_procedure {
+            self.add_error(SemanticError::ProcedureValueUsed {
+                name: name.to_string(),
+                line: self.source_map.get_line(span),
+                column: self.source_map.get_column(span),
+            });
+        }
+        value
+    }
+
+    /// A function returns a value of its type, a procedure returns nothing and the
+    /// program body can't return at all
+    pub(super) fn handle_return(&mut self, value: Option<&Expression>, span: &Range<usize>) {
+        let return_type = match &self.current_subprogram {
+            None => {
+                self.invalid_return_error(span, "return outside of a function or procedure");
+                if let Some(value) = value {
+                    self.analyze_expression(value);
+                }
+                return;
+            }
+            Some(name) => (name.clone(), self.signatures[name].return_type.clone()),
+        };
+
+        match (return_type, value) {
+            ((name, None), Some(value)) => {
+                self.analyze_expression(value);
+                self.invalid_return_error(
+                    span,
+                    &format!("procedure '{}' cannot return a value", name),
+                );
+            }
+            ((name, Some(_)), None) => {
+                self.invalid_return_error(
+                    span,
+                    &format!("function '{}' must return a value", name),
+                );
+            }
+            ((name, Some(typ)), Some(value)) => {
+                if let Some(found) = self.analyze_expression(value)
+                    && !found.get_type().is_compatible_with(&typ)
+                {
+                    self.type_mismatch_error(
+                        &value.span,
+                        &typ,
+                        found.get_type(),
+                        Some(&format!("return value of '{}'", name)),
+                    );
+                }
+            }
+            ((_, None), None) => {}
+        }
+    }
+
+    /// Whether running the statements always ends with a return or never ends
+    pub(super) fn always_returns(&mut self, statements: &[Statement]) -> bool {
+        statements.iter().any(|statement| match &statement.node {
+            StatementKind::Return(_) => true,
+            StatementKind::IfThenElse(_, then_block, else_block) => {
+                self.always_returns(then_block) && self.always_returns(else_block)
+            }
+            StatementKind::DoWhile(body, _) | StatementKind::Scope(_, body) => {
+                self.always_returns(body)
+            }
+            _ => self.never_terminates(statement),
+        })
+    }
+
+    fn argument_type_error(
+        &mut self,
+        span: &Range<usize>,
+        expected: &str,
+        found: &str,
+        context: &str,
+    ) {
+        self.add_error(SemanticError::TypeMismatch {
+            expected: expected.to_string(),
+            found: found.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+            context: Some(context.to_string()),
+        });
+    }
+
+    fn invalid_return_error(&mut self, span: &Range<usize>, message: &str) {
+        self.add_error(SemanticError::InvalidReturn {
+            message: message.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+}
+
+/// Whether an expression calls a subprogram, at any depth
+pub(super) fn expression_calls(expression: &Expression) -> bool {
+    match &expression.node {
+        ExpressionKind::Call(..) => true,
+        ExpressionKind::ArrayAccess(_, operand) | ExpressionKind::UnaryOp(_, operand) => {
+            expression_calls(operand)
+        }
+        ExpressionKind::BinaryOp(left, _, right) => {
+            expression_calls(left) || expression_calls(right)
+        }
+        ExpressionKind::Identifier(_) | ExpressionKind::Literal(_) => false,
+    }
+}
+
+/// Whether the statements call a subprogram, at any depth
+pub(super) fn statements_call(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match &statement.node {
+        StatementKind::Call(..) => true,
+        StatementKind::Assignment(target, value) => {
+            expression_calls(target) || expression_calls(value)
+        }
+        StatementKind::Input(target) => expression_calls(target),
+        StatementKind::Output(expressions) => expressions.iter().any(expression_calls),
+        StatementKind::Return(value) => value.as_ref().is_some_and(expression_calls),
+        StatementKind::IfThen(condition, body)
+        | StatementKind::DoWhile(body, condition)
+        | StatementKind::While(condition, body) => {
+            expression_calls(condition) || statements_call(body)
+        }
+        StatementKind::IfThenElse(condition, then_block, else_block) => {
+            expression_calls(condition)
+                || statements_call(then_block)
+                || statements_call(else_block)
+        }
+        StatementKind::For(_, init, end, step, body) => {
+            [init, end, step].into_iter().any(expression_calls) || statements_call(body)
+        }
+        StatementKind::Scope(_, body) => statements_call(body),
+        StatementKind::Empty => false,
+    })
+}
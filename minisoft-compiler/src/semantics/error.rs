@@ -1,5 +1,5 @@
 use crate::error_reporter::format_code_context;
-use crate::error_reporter::ErrorReporter;
+use crate::error_reporter::{ErrorReporter, Severity};
 use colored::Colorize;
 use std::fmt;
 
@@ -37,6 +37,15 @@ pub enum SemanticError {
         original_column: usize,
     },
 
+    /// Declaration in a block or subprogram hiding a name declared around it
+    ShadowedDeclaration {
+        name: String,
+        line: usize,
+        column: usize,
+        original_line: usize,
+        original_column: usize,
+    },
+
     /// Type mismatch in operations or assignments
     TypeMismatch {
         expected: String,
@@ -75,6 +84,11 @@ pub enum SemanticError {
         line: usize,
         column: usize,
     },
+    /// `for` loop with a constant step of zero
+    ZeroLoopStep {
+        line: usize,
+        column: usize,
+    },
     InvalidArraySize {
         name: String,
         size: i32,
@@ -82,6 +96,75 @@ pub enum SemanticError {
         column: usize,
     },
     EmptyProgram,
+    /// Loop whose condition stays true once it is
+    InfiniteLoop {
+        /// Whether the condition is always true, rather than never changed by the body
+        constant: bool,
+        line: usize,
+        column: usize,
+    },
+    /// Statements that can never run, such as a branch behind a constant condition
+    UnreachableCode {
+        context: String,
+        line: usize,
+        column: usize,
+    },
+    /// Variable or array element that may be read before anything was written to it
+    UseBeforeInitialization {
+        name: String,
+        index: Option<String>,
+        line: usize,
+        column: usize,
+    },
+    /// Array index whose computed range of values leaves the bounds of the array
+    IndexRangeOutOfBounds {
+        name: String,
+        /// Values the index may take, such as `[0, 10]`
+        range: String,
+        size: usize,
+        /// Whether every value of the range is out of bounds, rather than some of them
+        definite: bool,
+        line: usize,
+        column: usize,
+    },
+    /// Call to a name that isn't a function or procedure
+    UndefinedSubprogram {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+    /// Call with more or fewer arguments than the subprogram has parameters
+    ArgumentCountMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+        line: usize,
+        column: usize,
+    },
+    /// Procedure called where a value is expected
+    ProcedureValueUsed {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+    /// Function whose body can reach its end without returning a value
+    MissingReturn {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+    /// `return` outside of a subprogram, or that doesn't match the kind of subprogram
+    InvalidReturn {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// Subprogram named like a builtin, whose calls would never reach it
+    BuiltinRedefined {
+        name: String,
+        line: usize,
+        column: usize,
+    },
 }
 
 impl ErrorReporter for SemanticError {
@@ -90,7 +173,7 @@ impl ErrorReporter for SemanticError {
 
         result.push_str(&format!(
             "{}: {}\n",
-            "Semantic Error".red().bold(),
+            self.severity().paint(&self.get_error_name()),
             self.get_detailed_message()
         ));
 
@@ -107,7 +190,9 @@ impl ErrorReporter for SemanticError {
             if line <= lines.len() && line > 0 {
                 let line_content = lines[line - 1];
 
-                if let SemanticError::DuplicateDeclaration { original_line, .. } = self {
+                if let SemanticError::DuplicateDeclaration { original_line, .. }
+                | SemanticError::ShadowedDeclaration { original_line, .. } = self
+                {
                     result.push_str(&format_code_context(
                         line_content,
                         column,
@@ -161,6 +246,10 @@ impl ErrorReporter for SemanticError {
                 "Use a different name for the second declaration of '{}'",
                 name
             )),
+            SemanticError::ShadowedDeclaration { name, .. } => Some(format!(
+                "Rename this '{}' if the outer one is meant to be used here",
+                name
+            )),
             SemanticError::TypeMismatch {
                 expected,
                 found,
@@ -204,15 +293,60 @@ impl ErrorReporter for SemanticError {
             SemanticError::InvalidConditionValue { found, .. } => {
                 Some(format!("Condition must return 1 or 0, found '{}'", found))
             }
+            SemanticError::ZeroLoopStep { .. } => Some(
+                "Use a positive step to count up or a negative step to count down".to_string(),
+            ),
             SemanticError::InvalidArraySize { name, .. } => {
                 Some(format!("Declare array '{}' with a positive size", name))
             }
             SemanticError::EmptyProgram => Some("Program is empty. Add some code.".to_string()),
+            SemanticError::InfiniteLoop { constant: true, .. } => {
+                Some("Use a condition that eventually becomes false".to_string())
+            }
+            SemanticError::InfiniteLoop { constant: false, .. } => Some(
+                "Change one of the variables of the condition in the loop body".to_string(),
+            ),
+            SemanticError::UnreachableCode { .. } => {
+                Some("Remove this code or fix the condition that skips it".to_string())
+            }
+            SemanticError::UseBeforeInitialization { name, index, .. } => Some(match index {
+                Some(_) => format!("Assign the elements of '{}' before reading them", name),
+                None => format!(
+                    "Assign a value to '{}' on every path before reading it",
+                    name
+                ),
+            }),
+            SemanticError::IndexRangeOutOfBounds { name, size, .. } => Some(format!(
+                "Array '{}' has size {}. Keep the index between 0 and {}",
+                name,
+                size,
+                size.saturating_sub(1)
+            )),
+            SemanticError::UndefinedSubprogram { name, .. } => Some(format!(
+                "Declare a function or procedure named '{}' before 'BeginPg'",
+                name
+            )),
+            SemanticError::ArgumentCountMismatch { name, expected, .. } => Some(format!(
+                "Pass exactly {} argument(s) to '{}'",
+                expected, name
+            )),
+            SemanticError::ProcedureValueUsed { name, .. } => Some(format!(
+                "Call '{}' as a statement, or make it a Function with a return type",
+                name
+            )),
+            SemanticError::MissingReturn { name, .. } => Some(format!(
+                "End every path through '{}' with a return statement",
+                name
+            )),
+            SemanticError::InvalidReturn { .. } => None,
+            SemanticError::BuiltinRedefined { .. } => {
+                Some("Give the function or procedure another name".to_string())
+            }
         }
     }
 
     fn get_error_name(&self) -> String {
-        "Semantic Error".to_string()
+        format!("Semantic {}", self.severity().title())
     }
 
     fn get_location_info(&self) -> (usize, usize) {
@@ -221,14 +355,39 @@ impl ErrorReporter for SemanticError {
             SemanticError::ArraySizeMismatch { line, column, .. } => (*line, *column),
             SemanticError::UndeclaredIdentifier { line, column, .. } => (*line, *column),
             SemanticError::DuplicateDeclaration { line, column, .. } => (*line, *column),
+            SemanticError::ShadowedDeclaration { line, column, .. } => (*line, *column),
             SemanticError::TypeMismatch { line, column, .. } => (*line, *column),
             SemanticError::DivisionByZero { line, column } => (*line, *column),
             SemanticError::ConstantModification { line, column, .. } => (*line, *column),
             SemanticError::ArrayIndexOutOfBounds { line, column, .. } => (*line, *column),
             SemanticError::InvalidConditionValue { line, column, .. } => (*line, *column),
             SemanticError::NonArrayIndexing { line, column, .. } => (*line, *column),
+            SemanticError::ZeroLoopStep { line, column } => (*line, *column),
             SemanticError::InvalidArraySize { line, column, .. } => (*line, *column),
             SemanticError::EmptyProgram => (0, 0),
+            SemanticError::InfiniteLoop { line, column, .. } => (*line, *column),
+            SemanticError::UnreachableCode { line, column, .. } => (*line, *column),
+            SemanticError::UseBeforeInitialization { line, column, .. } => (*line, *column),
+            SemanticError::IndexRangeOutOfBounds { line, column, .. } => (*line, *column),
+            SemanticError::UndefinedSubprogram { line, column, .. } => (*line, *column),
+            SemanticError::ArgumentCountMismatch { line, column, .. } => (*line, *column),
+            SemanticError::ProcedureValueUsed { line, column, .. } => (*line, *column),
+            SemanticError::MissingReturn { line, column, .. } => (*line, *column),
+            SemanticError::InvalidReturn { line, column, .. } => (*line, *column),
+            SemanticError::BuiltinRedefined { line, column, .. } => (*line, *column),
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self {
+            SemanticError::InfiniteLoop { .. }
+            | SemanticError::ShadowedDeclaration { .. }
+            | SemanticError::UnreachableCode { .. }
+            | SemanticError::UseBeforeInitialization { .. }
+            | SemanticError::IndexRangeOutOfBounds {
+                definite: false, ..
+            } => Severity::Warning,
+            _ => Severity::Error,
         }
     }
 }
@@ -262,6 +421,15 @@ impl SemanticError {
                     name, original_line, original_column
                 )
             }
+            SemanticError::ShadowedDeclaration {
+                name,
+                original_line,
+                original_column,
+                ..
+            } => format!(
+                "Declaration of '{}' hides the one at line {}, column {}",
+                name, original_line, original_column
+            ),
             SemanticError::TypeMismatch {
                 expected,
                 found,
@@ -295,6 +463,9 @@ impl SemanticError {
             SemanticError::NonArrayIndexing { var_name, .. } => {
                 format!("Attempt to index non-array variable '{}'", var_name)
             }
+            SemanticError::ZeroLoopStep { .. } => {
+                "For loop step is zero, the loop would never terminate".to_string()
+            }
             SemanticError::InvalidArraySize { name, size, .. } => {
                 format!(
                     "Invalid array size: {} for array '{}'. Array size must be positive",
@@ -302,6 +473,67 @@ impl SemanticError {
                 )
             }
             SemanticError::EmptyProgram => "Program is empty. Add some code.".to_string(),
+            SemanticError::InfiniteLoop { constant: true, .. } => {
+                "Loop condition is always true, the loop never terminates".to_string()
+            }
+            SemanticError::InfiniteLoop { constant: false, .. } => {
+                "Loop condition never changes in the loop body, the loop never terminates once it repeats"
+                    .to_string()
+            }
+            SemanticError::UnreachableCode { context, .. } => {
+                format!("Unreachable code: the {} never runs", context)
+            }
+            SemanticError::UseBeforeInitialization { name, index, .. } => match index {
+                Some(index) => format!(
+                    "Array element '{}[{}]' may be read before being written",
+                    name, index
+                ),
+                None => format!("Variable '{}' may be read before being written", name),
+            },
+            SemanticError::IndexRangeOutOfBounds {
+                name,
+                range,
+                size,
+                definite: true,
+                ..
+            } => format!(
+                "Array index out of bounds: index in {} is always outside array '{}' of size {}",
+                range, name, size
+            ),
+            SemanticError::IndexRangeOutOfBounds {
+                name,
+                range,
+                size,
+                definite: false,
+                ..
+            } => format!(
+                "Array index may be out of bounds: index in {} for array '{}' of size {}",
+                range, name, size
+            ),
+            SemanticError::UndefinedSubprogram { name, .. } => {
+                format!("Undefined function or procedure '{}'", name)
+            }
+            SemanticError::ArgumentCountMismatch {
+                name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "Wrong number of arguments for '{}': expected {}, found {}",
+                name, expected, found
+            ),
+            SemanticError::ProcedureValueUsed { name, .. } => {
+                format!("Procedure '{}' does not return a value", name)
+            }
+            SemanticError::MissingReturn { name, .. } => {
+                format!("Function '{}' may end without returning a value", name)
+            }
+            SemanticError::InvalidReturn { message, .. } => {
+                format!("Invalid return: {}", message)
+            }
+            SemanticError::BuiltinRedefined { name, .. } => {
+                format!("'{}' is a builtin and cannot name a function or procedure", name)
+            }
         }
     }
 
@@ -311,14 +543,29 @@ impl SemanticError {
             SemanticError::ArraySizeMismatch { name, .. } => name.len(),
             SemanticError::UndeclaredIdentifier { name, .. } => name.len(),
             SemanticError::DuplicateDeclaration { name, .. } => name.len(),
+            SemanticError::ShadowedDeclaration { name, .. } => name.len(),
             SemanticError::TypeMismatch { .. } => 1, // Default token length
             SemanticError::DivisionByZero { .. } => 1,
             SemanticError::ConstantModification { name, .. } => name.len(),
             SemanticError::ArrayIndexOutOfBounds { name, .. } => name.len(),
             SemanticError::InvalidConditionValue { found, .. } => found.len(),
             SemanticError::NonArrayIndexing { var_name, .. } => var_name.len(),
+            SemanticError::ZeroLoopStep { .. } => 1,
             SemanticError::InvalidArraySize { name, .. } => name.len(),
             SemanticError::EmptyProgram => 0,
+            SemanticError::InfiniteLoop { .. } => 1,
+            SemanticError::UnreachableCode { .. } => 1,
+            SemanticError::UseBeforeInitialization { name, index, .. } => match index {
+                Some(index) => name.len() + index.len() + 2,
+                None => name.len(),
+            },
+            SemanticError::IndexRangeOutOfBounds { .. } => 1,
+            SemanticError::UndefinedSubprogram { name, .. } => name.len(),
+            SemanticError::ArgumentCountMismatch { name, .. } => name.len(),
+            SemanticError::ProcedureValueUsed { name, .. } => name.len(),
+            SemanticError::MissingReturn { name, .. } => name.len(),
+            SemanticError::InvalidReturn { .. } => 6,
+            SemanticError::BuiltinRedefined { name, .. } => name.len(),
         }
     }
 }
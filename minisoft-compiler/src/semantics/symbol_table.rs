@@ -1,5 +1,8 @@
-use crate::parser::ast::{LiteralKind, Type};
-use std::{collections::HashMap, default};
+use crate::parser::ast::{Declaration, LiteralKind, Statement, StatementKind, Subprogram, Type};
+use std::{
+    collections::{HashMap, HashSet},
+    default,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SymbolKind {
@@ -24,41 +27,266 @@ pub struct Symbol {
     pub is_constant: bool,
     pub line: usize,
     pub column: usize,
+    /// Path of the scope declaring the symbol, empty for a global. Set when the symbol
+    /// is added to a table.
+    pub scope: String,
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct SymbolTable {
+impl Symbol {
+    /// Name the symbol is stored under once the program is compiled
+    pub fn qualified_name(&self) -> String {
+        qualified_name(&self.scope, &self.name)
+    }
+
+    /// Path of the scope as shown to users, `global` for the globals
+    pub fn scope_name(&self) -> &str {
+        if self.scope.is_empty() {
+            "global"
+        } else {
+            &self.scope
+        }
+    }
+}
+
+/// Name of `name` declared in the scope at `path`. Paths are made of a subprogram name
+/// and block numbers separated by `.`, which identifiers can't contain, so locals never
+/// clash with globals or with each other: `n` of `Fact` is `Fact.n` and `x` declared in
+/// the first block of the program body is `1.x`.
+pub fn qualified_name(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Name as written in the source of a name `qualified_name` gave, `z` for `Fact.1.z`
+pub fn source_name(qualified: &str) -> &str {
+    qualified.rsplit('.').next().unwrap_or(qualified)
+}
+
+#[derive(Debug, Clone)]
+struct Scope {
+    path: String,
     symbols: HashMap<String, Symbol>,
+    /// Blocks opened in this scope so far, the next one gets the following number
+    blocks: usize,
+}
+
+impl Scope {
+    fn new(path: String) -> Self {
+        Scope {
+            path,
+            symbols: HashMap::new(),
+            blocks: 0,
+        }
+    }
+
+    /// Path of the next block opened in this scope
+    fn next_block(&mut self) -> String {
+        self.blocks += 1;
+        qualified_name(&self.path, &self.blocks.to_string())
+    }
+}
+
+/// Symbols by scope. Lookups start in the innermost open scope and walk outward to the
+/// globals, so a name declared in a block hides the same name declared around it.
+/// Scopes that were left keep their symbols, which still need storage at runtime.
+#[derive(Debug, Clone)]
+pub struct SymbolTable {
+    /// Open scopes, the globals first and the innermost last
+    scopes: Vec<Scope>,
+    closed: Vec<Scope>,
 }
 
 impl SymbolTable {
     pub fn new() -> Self {
         SymbolTable {
-            symbols: HashMap::new(),
+            scopes: vec![Scope::new(String::new())],
+            closed: Vec::new(),
         }
     }
 
-    pub fn add_symbol(&mut self, symbol: Symbol) -> bool {
-        if self.symbols.contains_key(&symbol.name) {
+    /// Adds a symbol to the innermost scope, unless that scope already has the name
+    pub fn add_symbol(&mut self, mut symbol: Symbol) -> bool {
+        let scope = self.innermost();
+        if scope.symbols.contains_key(&symbol.name) {
             return false;
         }
-        self.symbols.insert(symbol.name.clone(), symbol);
+        symbol.scope = scope.path.clone();
+        scope.symbols.insert(symbol.name.clone(), symbol);
         true
     }
 
-    /// Checks if a symbol exists in the table
+    /// Checks if a symbol is visible from the innermost scope
     pub fn contains(&self, name: &str) -> bool {
-        self.symbols.contains_key(name)
+        self.get(name).is_some()
     }
 
-    /// Gets a symbol by name
+    /// Gets the symbol a name refers to from the innermost scope
     pub fn get(&self, name: &str) -> Option<&Symbol> {
-        self.symbols.get(name)
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.symbols.get(name))
+    }
+
+    /// Gets a symbol declared in the innermost scope itself
+    pub fn get_local(&self, name: &str) -> Option<&Symbol> {
+        self.scopes.last().and_then(|scope| scope.symbols.get(name))
+    }
+
+    /// Gets the symbol a name declared in the innermost scope would hide
+    pub fn get_shadowed(&self, name: &str) -> Option<&Symbol> {
+        self.scopes
+            .iter()
+            .rev()
+            .skip(1)
+            .find_map(|scope| scope.symbols.get(name))
     }
 
-    /// Gets all symbols
+    /// Gets all symbols, including those of the scopes that were left
     pub fn get_all(&self) -> Vec<&Symbol> {
-        self.symbols.values().collect()
+        self.scopes
+            .iter()
+            .chain(&self.closed)
+            .flat_map(|scope| scope.symbols.values())
+            .collect()
+    }
+
+    /// Path of the innermost scope, empty for the globals
+    pub fn path(&self) -> &str {
+        self.scopes.last().map_or("", |scope| scope.path.as_str())
+    }
+
+    /// Opens the scope of a subprogram, nested in the innermost one
+    pub fn enter_scope(&mut self, name: &str) {
+        let path = qualified_name(&self.innermost().path, name);
+        self.scopes.push(Scope::new(path));
+    }
+
+    /// Opens the scope of a block, numbered after the blocks already opened in the
+    /// innermost scope, and gives its path
+    pub fn enter_block(&mut self) -> String {
+        let path = self.innermost().next_block();
+        self.scopes.push(Scope::new(path.clone()));
+        path
+    }
+
+    /// Opens again a scope that was left, with the symbols it had
+    pub fn reopen_scope(&mut self, path: &str) -> bool {
+        match self.closed.iter().position(|scope| scope.path == path) {
+            Some(index) => {
+                let scope = self.closed.remove(index);
+                self.scopes.push(scope);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Leaves the innermost scope, the globals are never left
+    pub fn exit_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            let scope = self.scopes.pop().unwrap();
+            self.closed.push(scope);
+        }
+    }
+
+    fn innermost(&mut self) -> &mut Scope {
+        self.scopes.last_mut().unwrap()
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Qualified names of what is in scope while walking a program in source order, for
+/// passes that only have its syntax tree. Blocks are numbered the way the symbol table
+/// numbers them, as long as every scope statement is either entered or skipped.
+#[derive(Debug, Clone)]
+pub struct ScopeNames {
+    /// Open scopes, innermost last
+    scopes: Vec<(Scope, HashSet<String>)>,
+}
+
+impl ScopeNames {
+    pub fn new() -> Self {
+        ScopeNames {
+            scopes: vec![(Scope::new(String::new()), HashSet::new())],
+        }
+    }
+
+    /// Enters a subprogram, its parameters become visible
+    pub fn enter_subprogram(&mut self, subprogram: &Subprogram) {
+        let names = subprogram
+            .parameters
+            .iter()
+            .map(|parameter| parameter.node.name.clone())
+            .collect();
+        self.scopes
+            .push((Scope::new(subprogram.name.clone()), names));
+    }
+
+    /// Enters a block, nothing it declares is visible yet
+    pub fn enter_block(&mut self) {
+        let path = self.scopes.last_mut().unwrap().0.next_block();
+        self.scopes.push((Scope::new(path), HashSet::new()));
+    }
+
+    /// Makes the names of a declaration visible in the innermost scope, once its
+    /// initializer is done with the names they may hide
+    pub fn declare(&mut self, declaration: &Declaration) {
+        let names = &mut self.scopes.last_mut().unwrap().1;
+        names.extend(declaration.node.names().into_iter().map(str::to_string));
+    }
+
+    pub fn exit(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+
+    /// Counts the blocks of statements that are walked over without entering them, so
+    /// that the blocks after them keep their numbers
+    pub fn skip(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            match &statement.node {
+                StatementKind::Scope(..) => {
+                    self.scopes.last_mut().unwrap().0.next_block();
+                }
+                StatementKind::IfThen(_, body)
+                | StatementKind::DoWhile(body, _)
+                | StatementKind::While(_, body)
+                | StatementKind::For(.., body) => self.skip(body),
+                StatementKind::IfThenElse(_, then_block, else_block) => {
+                    self.skip(then_block);
+                    self.skip(else_block);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Qualified name of the declaration `name` refers to, the name itself for a global
+    pub fn resolve(&self, name: &str) -> String {
+        self.scopes
+            .iter()
+            .rev()
+            .find(|(_, names)| names.contains(name))
+            .map_or_else(
+                || name.to_string(),
+                |(scope, _)| qualified_name(&scope.path, name),
+            )
+    }
+}
+
+impl Default for ScopeNames {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -72,6 +300,7 @@ impl default::Default for Symbol {
             line: 0,
             column: 0,
             is_constant: false,
+            scope: String::new(),
         }
     }
 }
@@ -0,0 +1,190 @@
+#[cfg(test)]
+mod cli_tests {
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::process::{Command, Output, Stdio};
+    use std::time::{Duration, Instant};
+
+    const PROGRAM: &str = "MainPrgm sum;
+Var
+let i, s, n: Int;
+BeginPg
+{
+    input(n);
+    s := 0;
+    for i from 1 to n step 1 {
+        s := s + i;
+    }
+    output(s);
+}
+EndPg;
+";
+
+    /// Runs `minisoftc` with the given arguments, `stdin` piped to it
+    fn minisoftc(arguments: &[&str], stdin: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_minisoftc"))
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to start minisoftc");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    fn stdout(output: &Output) -> String {
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    #[test]
+    fn test_run_with_inputs() {
+        let output = minisoftc(&["run", "--input", "4"], PROGRAM);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "10\n");
+
+        let output = minisoftc(&["--json", "run", "-i", "3"], PROGRAM);
+        let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(result["output"], serde_json::json!(["6"]));
+        assert!(result["compilation_errors"].is_null());
+    }
+
+    #[test]
+    fn test_run_optimized() {
+        let output = minisoftc(&["run", "-O2", "-i", "4"], PROGRAM);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "10\n");
+
+        let steps = |level: &str| {
+            let output = minisoftc(&["--json", "run", "-O", level, "-i", "5"], PROGRAM);
+            let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            assert_eq!(result["output"], serde_json::json!(["15"]));
+            result["steps"].as_u64().unwrap()
+        };
+        assert!(steps("O2") < steps("O0"));
+    }
+
+    /// Writes `source` to a file of its own in the temporary directory
+    fn source_file(name: &str, source: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("minisoftc-{}-{}.ms", name, std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_run_reads_stdin_as_needed() {
+        let path = source_file("sum", PROGRAM);
+        let output = minisoftc(&["run", path.to_str().unwrap()], "4\n");
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), "10\n");
+
+        // A program without input finishes while standard input stays open
+        let path = source_file("hello", "MainPrgm t; Var BeginPg { output(1); } EndPg;");
+        let mut child = Command::new(env!("CARGO_BIN_EXE_minisoftc"))
+            .args(["run", path.to_str().unwrap()])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("Failed to start minisoftc");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while child.try_wait().unwrap().is_none() {
+            if Instant::now() > deadline {
+                child.kill().unwrap();
+                panic!("minisoftc waited for standard input it doesn't need");
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn test_closed_output_pipe() {
+        let body = "output(1);\n".repeat(5000);
+        let source = format!("MainPrgm t; Var BeginPg {{ {} }} EndPg;", body);
+        let mut child = Command::new(env!("CARGO_BIN_EXE_minisoftc"))
+            .args(["quads", "-O0"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to start minisoftc");
+        drop(child.stdout.take());
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(source.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(!String::from_utf8_lossy(&output.stderr).contains("panicked"));
+        assert_ne!(output.status.code(), Some(101));
+    }
+
+    #[test]
+    fn test_check_reports_errors() {
+        let source = PROGRAM.replace("s := 0;", "s := 0.5;");
+        let output = minisoftc(&["check", "--color", "never"], &source);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(stdout(&output).contains("Semantic Error: Type mismatch"));
+
+        let output = minisoftc(&["check", "--json"], &source);
+        assert_eq!(output.status.code(), Some(1));
+        let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(
+            result["errors"]["semantic_errors"][0]["type"],
+            "TypeMismatch"
+        );
+
+        let output = minisoftc(&["check"], PROGRAM);
+        assert!(output.status.success());
+
+        let output = minisoftc(&["check", "--disable-lint", "unused-variable"], PROGRAM);
+        assert!(output.status.success());
+        let output = minisoftc(&["check", "--disable-lint", "bogus-rule"], PROGRAM);
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("bogus-rule"));
+    }
+
+    #[test]
+    fn test_tokens_and_quadruples() {
+        let output = minisoftc(&["tokens"], PROGRAM);
+        assert!(output.status.success());
+        assert!(stdout(&output).starts_with("   1:1    MainPrgm         MainPrgm\n"));
+
+        let output = minisoftc(&["quads", "-O0", "--json"], PROGRAM);
+        let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(result["optimization_level"], "O0");
+        assert_eq!(result["quadruples"], result["optimized_quadruples"]);
+    }
+
+    #[test]
+    fn test_fmt() {
+        let messy = PROGRAM.replace("    s := 0;\n", "s:=0;\n");
+        let output = minisoftc(&["fmt"], &messy);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), PROGRAM);
+
+        assert_eq!(
+            minisoftc(&["fmt", "--check"], &messy).status.code(),
+            Some(1)
+        );
+        assert!(
+            minisoftc(&["fmt", "--check", "-"], PROGRAM)
+                .status
+                .success()
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let output = minisoftc(&["ast", "does-not-exist.ms"], "");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("does-not-exist.ms"));
+    }
+}
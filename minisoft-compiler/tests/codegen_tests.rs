@@ -0,0 +1,601 @@
+#[cfg(test)]
+mod codegen_tests {
+    use minisoft_compiler::codegen::generator::CodeGenerator;
+    use minisoft_compiler::codegen::{
+        ControlFlowGraph, Operand, Operation, Quadruple, QuadrupleProgram, ValidationError,
+        validate, validate_branches,
+    };
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::parser::ast::Program;
+    use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::runtime::{Value, execute};
+    use minisoft_compiler::semantics::analyzer_core::SemanticAnalyzer;
+
+    /// Helper function to generate quadruples for a valid program, along with its syntax tree
+    fn generate_test(source: &str) -> (Program, QuadrupleProgram) {
+        let (tokens, _) = tokenize(source);
+        let program = match parse(tokens, source) {
+            Ok(program) => program,
+            Err(e) => panic!("Parse error: {}", e),
+        };
+
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        assert!(
+            analyzer.get_errors().is_empty(),
+            "Expected no semantic errors, but found: {:?}",
+            analyzer.get_errors()
+        );
+
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+        (program, quadruples)
+    }
+
+    /// Helper function to generate quadruples and check they pass validation
+    fn generate_valid_test(source: &str) -> QuadrupleProgram {
+        let (program, quadruples) = generate_test(source);
+        let mut errors = validate(&quadruples);
+        errors.extend(validate_branches(&quadruples, &program));
+        assert!(
+            errors.is_empty(),
+            "Expected a valid quadruple program, but found: {:?}",
+            errors
+        );
+        quadruples
+    }
+
+    fn quad(operation: Operation, operand1: Operand, result: Operand) -> Quadruple {
+        Quadruple {
+            operation,
+            operand1,
+            operand2: Operand::Empty,
+            result,
+            span: None,
+            statement_span: None,
+        }
+    }
+
+    const IF_ELSE_SOURCE: &str = r#"
+        MainPrgm test;
+        Var
+        let x, y: Int;
+        BeginPg
+        {
+            input(x);
+            if (x > 0) then {
+                y := 1;
+            } else {
+                y := 2;
+            }
+            output(y);
+        }
+        EndPg;
+    "#;
+
+    #[test]
+    fn test_if_then_else_jumps_over_else_block() {
+        let quadruples = generate_valid_test(IF_ELSE_SOURCE);
+        let ops: Vec<&Operation> = quadruples.quadruples.iter().map(|q| &q.operation).collect();
+
+        let else_position = ops
+            .iter()
+            .position(|op| matches!(op, Operation::JumpIfFalse(_)))
+            .expect("Missing conditional jump");
+        let Operation::JumpIfFalse(else_label) = ops[else_position] else {
+            unreachable!()
+        };
+        let else_label_position = ops
+            .iter()
+            .position(|op| **op == Operation::Label(*else_label))
+            .expect("Missing else label");
+
+        // The then block must end with a jump past the else block
+        let Operation::Jump(end_label) = ops[else_label_position - 1] else {
+            panic!(
+                "Expected a jump before the else label, found {:?}",
+                ops[else_label_position - 1]
+            );
+        };
+        let end_label_position = ops
+            .iter()
+            .position(|op| **op == Operation::Label(*end_label))
+            .expect("Missing end label");
+        assert!(end_label_position > else_label_position);
+    }
+
+    #[test]
+    fn test_if_then_else_executes_one_branch() {
+        let quadruples = generate_valid_test(IF_ELSE_SOURCE);
+        let (tokens, _) = tokenize(IF_ELSE_SOURCE);
+        let program = parse(tokens, IF_ELSE_SOURCE).unwrap();
+        let mut analyzer = SemanticAnalyzer::new(&IF_ELSE_SOURCE.to_string());
+        analyzer.analyze(&program);
+
+        for (input, expected) in [("5", 1), ("-5", 2)] {
+            let result = execute(
+                &quadruples,
+                analyzer.get_symbol_table(),
+                &IF_ELSE_SOURCE.to_string(),
+                vec![input.to_string()],
+            );
+            assert!(
+                result.error.is_none(),
+                "Unexpected error: {:?}",
+                result.error
+            );
+            assert_eq!(
+                result.memory.variables.get("y"),
+                Some(&Value::Int(expected))
+            );
+        }
+    }
+
+    #[test]
+    fn test_while_tests_condition_at_top() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                i := 0;
+                while (i < 3) {
+                    i := i + 1;
+                }
+                output(i);
+            }
+            EndPg;
+        "#;
+        let quadruples = generate_valid_test(source);
+        let ops: Vec<&Operation> = quadruples.quadruples.iter().map(|q| &q.operation).collect();
+
+        // Label, comparison, then the exit jump before any of the body
+        let Some(start) = ops.iter().position(|op| matches!(op, Operation::Label(_))) else {
+            panic!("Missing loop label");
+        };
+        let Operation::Label(start_label) = ops[start] else {
+            unreachable!()
+        };
+        assert_eq!(*ops[start + 1], Operation::LessThan);
+        let Operation::JumpIfFalse(end_label) = ops[start + 2] else {
+            panic!("Expected the exit test, found {:?}", ops[start + 2]);
+        };
+        let end = ops
+            .iter()
+            .position(|op| **op == Operation::Label(*end_label))
+            .expect("Missing end label");
+        assert_eq!(*ops[end - 1], Operation::Jump(*start_label));
+    }
+
+    #[test]
+    fn test_subprogram_calling_convention() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Function Twice(n: Int): Int
+            Var
+            let d: Int;
+            {
+                d := n * 2;
+                return d;
+            }
+            Procedure Reset()
+            Var
+            {
+                if (x > 0) then {
+                    x := 0;
+                    return;
+                } else {
+                    return;
+                }
+            }
+            BeginPg
+            {
+                x := Twice(4);
+                Reset();
+            }
+            EndPg;
+        "#;
+        let quadruples = generate_valid_test(source).quadruples;
+        let ops: Vec<&Operation> = quadruples.iter().map(|q| &q.operation).collect();
+
+        // Arguments go to the qualified parameter, the value comes back in a temporary
+        assert_eq!(*ops[0], Operation::Param);
+        assert_eq!(quadruples[0].operand1, Operand::IntLiteral(4));
+        assert_eq!(
+            quadruples[0].result,
+            Operand::Variable("Twice.n".to_string())
+        );
+        assert_eq!(*ops[1], Operation::Call("Twice".to_string()));
+        assert_eq!(quadruples[1].operand1, Operand::IntLiteral(1));
+        assert!(matches!(quadruples[1].result, Operand::TempVariable(_)));
+        assert_eq!(*ops[3], Operation::Call("Reset".to_string()));
+        assert_eq!(quadruples[3].result, Operand::Empty);
+
+        // The program body ends before the first subprogram
+        assert_eq!(*ops[4], Operation::Return);
+        assert_eq!(*ops[5], Operation::Entry("Twice".to_string()));
+        assert_eq!(
+            quadruples[6].operand1,
+            Operand::Variable("Twice.n".to_string())
+        );
+        assert_eq!(
+            quadruples[7].result,
+            Operand::Variable("Twice.d".to_string())
+        );
+        assert_eq!(*ops[8], Operation::Return);
+        assert_eq!(
+            quadruples[8].operand1,
+            Operand::Variable("Twice.d".to_string())
+        );
+
+        // A branch that returns doesn't jump past the else branch
+        assert_eq!(*ops[9], Operation::Entry("Reset".to_string()));
+        assert!(!ops[9..].iter().any(|op| matches!(op, Operation::Jump(_))));
+        assert_eq!(
+            ops[13..],
+            [
+                &Operation::Return,
+                &Operation::Label(1),
+                &Operation::Return,
+                &Operation::Label(2),
+                &Operation::Return
+            ]
+        );
+
+        let cfg = ControlFlowGraph::build(&generate_valid_test(source));
+        assert_eq!(cfg.roots().len(), 3);
+    }
+
+    #[test]
+    fn test_block_names_are_qualified() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Procedure P(x: Int)
+            Var
+            {
+                if (x > 0) then {
+                    return;
+                    {
+                        let a: Int = 1;
+                        output(a);
+                    }
+                }
+                {
+                    let b: Int = x;
+                    output(b);
+                }
+            }
+            BeginPg
+            {
+                x := 1;
+                {
+                    let x: Int = x + 1;
+                    output(x);
+                }
+                P(x);
+            }
+            EndPg;
+        "#;
+        let quadruples = generate_valid_test(source).quadruples;
+        let variables: Vec<String> = quadruples
+            .iter()
+            .flat_map(|q| [&q.operand1, &q.result])
+            .filter_map(|operand| match operand {
+                Operand::Variable(name) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // The initializer reads the global, everything after it the block's own x
+        assert_eq!(quadruples[1].operand1, Operand::Variable("x".to_string()));
+        assert!(variables.contains(&"1.x".to_string()));
+        assert!(variables.contains(&"P.x".to_string()));
+        // The unreachable block after the return still takes its number
+        assert!(variables.contains(&"P.2.b".to_string()));
+        assert!(!variables.iter().any(|name| name.ends_with(".a")));
+    }
+
+    #[test]
+    fn test_string_operations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let s: String;
+            let n: Int;
+            Function Name(): String
+            Var
+            {
+                return "ada";
+            }
+            BeginPg
+            {
+                s := Name() + "!";
+                n := len(s) + 1;
+                n := n + len(s + s);
+            }
+            EndPg;
+        "#;
+        let quadruples = generate_valid_test(source).quadruples;
+        let ops: Vec<&Operation> = quadruples.iter().map(|q| &q.operation).collect();
+
+        // A string on the left of `+` makes it a join, the lengths are added as numbers
+        assert_eq!(*ops[1], Operation::Concat);
+        assert_eq!(
+            quadruples[1].operand2,
+            Operand::StringLiteral("!".to_string())
+        );
+        assert_eq!(*ops[3], Operation::Length);
+        assert_eq!(quadruples[3].operand1, Operand::Variable("s".to_string()));
+        assert_eq!(*ops[4], Operation::Add);
+        assert_eq!(*ops[6], Operation::Concat);
+        assert_eq!(*ops[7], Operation::Length);
+        assert_eq!(*ops[8], Operation::Add);
+    }
+
+    #[test]
+    fn test_generated_programs_are_valid() {
+        generate_valid_test(
+            r#"
+            MainPrgm test;
+            Var
+            let i, sum: Int;
+            let a: [Int; 10] = {1, 2, 3, 4, 5, 6, 7, 8, 9, 10};
+            @define Const Limit: Int = 5;
+            BeginPg
+            {
+                sum := 0;
+                for i from 0 to Limit step 1 {
+                    if (a[i] > 2 AND a[i] < 8) then {
+                        sum := sum + a[i] * 2;
+                    }
+                }
+                do {
+                    if (sum > 10) then {
+                        sum := sum - 3;
+                    } else {
+                        if (sum == 0) then {
+                            output("zero");
+                        } else {
+                            output(sum);
+                        }
+                    }
+                } while (sum > 10);
+                output("sum = ", sum);
+            }
+            EndPg;
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_validator_reports_undefined_and_duplicate_labels() {
+        let mut program = QuadrupleProgram::new();
+        let label = program.new_label();
+        program.add(quad(Operation::Jump(7), Operand::Empty, Operand::Empty));
+        program.add(quad(
+            Operation::Label(label),
+            Operand::Empty,
+            Operand::Empty,
+        ));
+        program.add(quad(
+            Operation::Label(label),
+            Operand::Empty,
+            Operand::Empty,
+        ));
+
+        let errors = validate(&program);
+        assert!(errors.contains(&ValidationError::UndefinedLabel { index: 0, label: 7 }));
+        assert!(errors.contains(&ValidationError::DuplicateLabel {
+            index: 2,
+            label,
+            first_index: 1
+        }));
+    }
+
+    #[test]
+    fn test_validator_reports_unreachable_code_and_undefined_temps() {
+        let mut program = QuadrupleProgram::new();
+        let label = program.new_label();
+        program.add(quad(Operation::Jump(label), Operand::Empty, Operand::Empty));
+        program.add(quad(
+            Operation::Assign,
+            Operand::IntLiteral(1),
+            Operand::Variable("x".to_string()),
+        ));
+        program.add(quad(
+            Operation::Label(label),
+            Operand::Empty,
+            Operand::Empty,
+        ));
+        program.add(quad(
+            Operation::Assign,
+            Operand::TempVariable("t0".to_string()),
+            Operand::Variable("x".to_string()),
+        ));
+
+        let errors = validate(&program);
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::UnreachableCode { index: 1 },
+                ValidationError::TempUsedBeforeDefinition {
+                    index: 3,
+                    name: "t0".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validator_reports_branch_fallthrough() {
+        let (program, mut quadruples) = generate_test(IF_ELSE_SOURCE);
+
+        // The old lowering had no jump over the else block
+        let jump = quadruples
+            .quadruples
+            .iter()
+            .position(|quad| matches!(quad.operation, Operation::Jump(_)))
+            .expect("Missing jump over the else block");
+        quadruples.quadruples.remove(jump);
+
+        assert!(validate(&quadruples).is_empty());
+        assert_eq!(
+            validate_branches(&quadruples, &program),
+            vec![ValidationError::BranchFallsThrough {
+                index: jump - 1,
+                into: jump + 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validator_accepts_branches_inside_loops() {
+        generate_valid_test(
+            r#"
+            MainPrgm test;
+            Var
+            let i, x: Int;
+            BeginPg
+            {
+                x := 0;
+                for i from 1 to 4 step 1 {
+                    if (i > 2) then {
+                        x := x + i;
+                    } else {
+                        if (x == 0) then {
+                            x := 1;
+                        } else {
+                            x := x * 2;
+                        }
+                    }
+                }
+                do {
+                    if (x > 5) then {
+                        x := x - 3;
+                    } else {
+                        x := x - 1;
+                    }
+                } while (x > 0);
+                output(x);
+            }
+            EndPg;
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_cfg_if_then_else_diamond() {
+        let quadruples = generate_valid_test(IF_ELSE_SOURCE);
+        let cfg = ControlFlowGraph::build(&quadruples);
+
+        // input + condition, then, else, join
+        assert_eq!(cfg.blocks.len(), 4);
+        assert_eq!(cfg.blocks[0].successors, vec![2, 1]);
+        assert_eq!(cfg.blocks[1].successors, vec![3]);
+        assert_eq!(cfg.blocks[2].successors, vec![3]);
+        assert_eq!(cfg.blocks[3].predecessors, vec![1, 2]);
+        assert!(cfg.blocks[3].successors.is_empty());
+
+        let order = cfg.reverse_post_order();
+        assert_eq!(order.first(), Some(&0));
+        assert_eq!(order.last(), Some(&3));
+        assert_eq!(order.len(), 4);
+    }
+
+    #[test]
+    fn test_cfg_loop_back_edge() {
+        let quadruples = generate_valid_test(
+            r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                i := 0;
+                do {
+                    i := i + 1;
+                } while (i < 3);
+                output(i);
+            }
+            EndPg;
+            "#,
+        );
+        let cfg = ControlFlowGraph::build(&quadruples);
+
+        let body = cfg
+            .blocks
+            .iter()
+            .find(|block| block.label().is_some())
+            .expect("Missing loop header block");
+        assert!(body.successors.contains(&body.id), "Expected a self loop");
+        assert!(body.predecessors.contains(&0));
+
+        // Flattening the blocks gives back the original program
+        assert_eq!(cfg.to_program().quadruples, quadruples.quadruples);
+    }
+
+    #[test]
+    fn test_cfg_unreachable_blocks_not_ordered() {
+        let mut program = QuadrupleProgram::new();
+        let end = program.new_label();
+        program.add(quad(Operation::Jump(end), Operand::Empty, Operand::Empty));
+        program.add(quad(
+            Operation::Assign,
+            Operand::IntLiteral(1),
+            Operand::Variable("x".to_string()),
+        ));
+        program.add(quad(Operation::Label(end), Operand::Empty, Operand::Empty));
+
+        let cfg = ControlFlowGraph::build(&program);
+        assert_eq!(cfg.blocks.len(), 3);
+        assert!(cfg.blocks[1].predecessors.is_empty());
+        assert_eq!(cfg.block_of_label(end), Some(2));
+        assert_eq!(cfg.reverse_post_order(), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_cfg_nested_natural_loops() {
+        let quadruples = generate_valid_test(
+            r#"
+            MainPrgm test;
+            Var
+            let i, j, s: Int;
+            BeginPg
+            {
+                s := 0;
+                for i from 1 to 3 step 1 {
+                    for j from 1 to i step 1 {
+                        s := s + j;
+                    }
+                }
+                output(s);
+            }
+            EndPg;
+            "#,
+        );
+        let cfg = ControlFlowGraph::build(&quadruples);
+        let dominators = cfg.dominators();
+        assert!(dominators.iter().all(|set| set.contains(&0)));
+
+        let loops = cfg.natural_loops();
+        assert_eq!(loops.len(), 2);
+        let (inner, outer) = (&loops[0], &loops[1]);
+        assert!(inner.blocks.iter().all(|&block| outer.contains(block)));
+        assert!(!inner.contains(outer.header));
+        for natural in &loops {
+            assert!(cfg.blocks[natural.header].label().is_some());
+            for &block in &natural.blocks {
+                assert!(dominators[block].contains(&natural.header));
+            }
+            for &latch in &natural.latches {
+                assert!(cfg.blocks[latch].successors.contains(&natural.header));
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod dataflow_tests {
+    use minisoft_compiler::codegen::generator::CodeGenerator;
+    use minisoft_compiler::codegen::{
+        ControlFlowGraph, Operand, Operation, QuadrupleProgram, validate, validate_branches,
+    };
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::optimizer::{Liveness, ReachingDefinitions, Slot, solve};
+    use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::semantics::analyzer_core::SemanticAnalyzer;
+    use minisoft_compiler::{CompilerOptions, run_compiler};
+
+    /// Helper function to generate and validate quadruples for a valid program
+    fn generate_test(source: &str) -> QuadrupleProgram {
+        let (tokens, _) = tokenize(source);
+        let program = parse(tokens, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        assert!(analyzer.get_errors().is_empty());
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+        assert!(validate(&quadruples).is_empty());
+        assert!(validate_branches(&quadruples, &program).is_empty());
+        quadruples
+    }
+
+    fn variable(name: &str) -> Slot {
+        Slot::Variable(name.to_string())
+    }
+
+    fn position(program: &QuadrupleProgram, operation: Operation, operand: &Operand) -> usize {
+        program
+            .quadruples
+            .iter()
+            .position(|quad| quad.operation == operation && &quad.operand1 == operand)
+            .expect("Missing quadruple")
+    }
+
+    #[test]
+    fn test_liveness_around_loop() {
+        let program = generate_test(
+            r#"
+            MainPrgm test;
+            Var
+            let i, n, s, unused: Int;
+            BeginPg
+            {
+                input(n);
+                unused := 5;
+                s := 0;
+                i := 0;
+                do {
+                    s := s + i;
+                    i := i + 1;
+                } while (i < n);
+                output(s);
+            }
+            EndPg;
+            "#,
+        );
+        let cfg = ControlFlowGraph::build(&program);
+        let liveness = solve(&cfg, &Liveness);
+        assert_eq!(liveness.before.len(), program.quadruples.len());
+
+        // Nothing is live at the start, and a variable assigned but never read never is
+        assert!(liveness.before(0).is_empty());
+        assert!(
+            liveness
+                .before
+                .iter()
+                .all(|live| !live.contains(&variable("unused")))
+        );
+
+        // Around the loop header the loop-carried variables are all live
+        let header = cfg.natural_loops()[0].header;
+        let entry = &liveness.block_entry[header];
+        for name in ["i", "n", "s"] {
+            assert!(entry.contains(&variable(name)), "{} should be live", name);
+        }
+
+        // After the final output nothing is live any more
+        let last = program.quadruples.len() - 1;
+        assert!(liveness.after(last).is_empty());
+    }
+
+    #[test]
+    fn test_reaching_definitions_merge_branches() {
+        let program = generate_test(
+            r#"
+            MainPrgm test;
+            Var
+            let c, x: Int;
+            BeginPg
+            {
+                input(c);
+                x := 0;
+                if (c > 0) then {
+                    x := 1;
+                } else {
+                    x := 2;
+                }
+                output(x);
+            }
+            EndPg;
+            "#,
+        );
+        let cfg = ControlFlowGraph::build(&program);
+        let reaching = solve(&cfg, &ReachingDefinitions::new(&cfg));
+
+        let x = Operand::Variable("x".to_string());
+        let definitions: Vec<usize> = program
+            .quadruples
+            .iter()
+            .enumerate()
+            .filter(|(_, quad)| quad.result == x)
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(definitions.len(), 3);
+
+        // Both branch assignments reach the output, the first one is killed on both paths
+        let output = position(&program, Operation::Output, &x);
+        let reaching_output = reaching.before(output);
+        assert!(!reaching_output.contains(&definitions[0]));
+        assert!(reaching_output.contains(&definitions[1]));
+        assert!(reaching_output.contains(&definitions[2]));
+    }
+
+    #[test]
+    fn test_compiler_output_includes_dataflow() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            BeginPg
+            {
+                input(x);
+                y := x + 1;
+                output(y);
+            }
+            EndPg;
+        "#;
+        let result = run_compiler(source.to_string(), false, CompilerOptions::default()).unwrap();
+        let points = &result.dataflow.points;
+        assert_eq!(points.len(), result.quadruples.quadruples.len());
+        assert!(points[0].live_in.is_empty());
+        assert_eq!(points[0].live_out, vec!["x".to_string()]);
+        assert!(
+            points
+                .iter()
+                .any(|point| point.reaching_definitions.contains(&0))
+        );
+    }
+}
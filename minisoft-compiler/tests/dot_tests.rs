@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod dot_tests {
+    use minisoft_compiler::codegen::generator::CodeGenerator;
+    use minisoft_compiler::codegen::{ControlFlowGraph, validate, validate_branches};
+    use minisoft_compiler::dot::{ast_to_dot, cfg_to_dot, symbol_table_to_dot};
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::parser::ast::Program;
+    use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::semantics::analyzer_core::SemanticAnalyzer;
+    use minisoft_compiler::{CompilerOptions, run_compiler};
+
+    const SOURCE: &str = r#"
+        MainPrgm test;
+        Var
+        let x: Int;
+        @define Const Greeting: Int = 3;
+        BeginPg
+        {
+            input(x);
+            if (x > Greeting) then {
+                output("big");
+            } else {
+                output("small");
+            }
+        }
+        EndPg;
+    "#;
+
+    /// Helper function to parse and analyze a valid program
+    fn analyze_test(source: &str) -> (Program, SemanticAnalyzer) {
+        let (tokens, _) = tokenize(source);
+        let program = match parse(tokens, source) {
+            Ok(program) => program,
+            Err(e) => panic!("Parse error: {}", e),
+        };
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        (program, analyzer)
+    }
+
+    #[test]
+    fn test_ast_dot_is_a_tree() {
+        let (program, _) = analyze_test(SOURCE);
+        let dot = ast_to_dot(&program);
+
+        assert!(dot.starts_with("digraph AST {"));
+        assert!(dot.contains("n0 [label=\"Program: test\"]"));
+        assert!(dot.contains("[label=\"Literal: String(\\\"big\\\")\"]"));
+
+        // Every node but the root has exactly one incoming edge
+        let nodes = dot.lines().filter(|line| line.contains("[label=")).count();
+        let edges = dot.lines().filter(|line| line.contains("->")).count();
+        assert_eq!(edges, nodes - 1);
+    }
+
+    #[test]
+    fn test_cfg_dot_labels_branches() {
+        let (program, _) = analyze_test(SOURCE);
+        let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+        assert!(validate(&quadruples).is_empty());
+        assert!(validate_branches(&quadruples, &program).is_empty());
+        let cfg = ControlFlowGraph::build(&quadruples);
+        let dot = cfg_to_dot(&cfg);
+
+        assert!(dot.contains("entry -> B0;"));
+        assert!(dot.contains("B0 -> B2 [label=\"false\"];"));
+        assert!(dot.contains("B0 -> B1 [label=\"true\"];"));
+        assert!(dot.contains("B1 -> B3;"));
+        assert!(dot.contains("(OUTPUT, \\\"big\\\", _, _)"));
+    }
+
+    #[test]
+    fn test_symbol_table_dot() {
+        let (_, analyzer) = analyze_test(SOURCE);
+        let dot = symbol_table_to_dot(analyzer.get_symbol_table());
+
+        let greeting = dot.find("<td>Greeting</td>").expect("Missing constant row");
+        let x = dot.find("<td>x</td>").expect("Missing variable row");
+        assert!(greeting < x, "Symbols should be sorted by name");
+        assert!(dot.contains("<td>Constant</td>"));
+    }
+
+    #[test]
+    fn test_compiler_output_includes_dot() {
+        let result = run_compiler(SOURCE.to_string(), false, CompilerOptions::default()).unwrap();
+        assert!(result.errors.is_none());
+        assert!(result.dot.ast.starts_with("digraph AST"));
+        assert!(result.dot.control_flow_graph.starts_with("digraph CFG"));
+        assert!(result.dot.symbol_table.starts_with("digraph SymbolTable"));
+    }
+}
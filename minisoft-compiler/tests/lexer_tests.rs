@@ -2,7 +2,7 @@
 mod lexer_tests {
     use logos::Logos;
     use minisoft_compiler::lexer::error::LexicalErrorType;
-    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::lexer::lexer_core::{tokenize, tokenize_with_comments};
     use minisoft_compiler::lexer::token::Token;
 
     #[test]
@@ -31,6 +31,15 @@ mod lexer_tests {
         assert_eq!(lexer.next(), None);
     }
 
+    #[test]
+    fn test_string_type_and_len() {
+        // `len` is called like a function, it stays a plain identifier
+        let mut lexer = Token::lexer("String len");
+        assert_eq!(lexer.next(), Some(Ok(Token::String)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Identifier("len".to_string()))));
+        assert_eq!(lexer.next(), None);
+    }
+
     #[test]
     fn test_punctuation() {
         let mut lexer = Token::lexer("; , : [ ] { } ( )");
@@ -139,8 +148,17 @@ mod lexer_tests {
     fn test_comments() {
         let mut lexer = Token::lexer("a <!- commented text -!> b {-- another comment --} c");
         assert_eq!(lexer.next(), Some(Ok(Token::Identifier("a".to_string()))));
+        assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
         assert_eq!(lexer.next(), Some(Ok(Token::Identifier("b".to_string()))));
+        assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
         assert_eq!(lexer.next(), Some(Ok(Token::Identifier("c".to_string()))));
+
+        // Comments never reach the parser
+        let (tokens, _) = tokenize("a <!- commented text -!> b {-- another comment --} c");
+        assert_eq!(tokens.len(), 3);
+        let (tokens, _) = tokenize_with_comments("a <!- commented text -!> b");
+        assert_eq!(tokens[1].kind, Token::Comment);
+        assert_eq!(tokens[1].value, "<!- commented text -!>");
     }
 
     #[test]
@@ -150,8 +168,15 @@ mod lexer_tests {
             lexer.next(),
             Some(Ok(Token::Identifier("start".to_string())))
         );
+        assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
         assert_eq!(lexer.next(), Some(Ok(Token::Identifier("end".to_string()))));
         assert_eq!(lexer.next(), None);
+
+        // Lines inside the comment still count
+        let (tokens, _) = tokenize_with_comments("start\n  {-- line1\nline2\nline3 --} x\nend");
+        assert_eq!((tokens[1].line, tokens[1].column), (2, 3));
+        assert_eq!((tokens[2].line, tokens[2].column), (4, 11));
+        assert_eq!((tokens[3].line, tokens[3].column), (5, 1));
     }
 
     #[test]
@@ -281,7 +306,10 @@ mod lexer_tests {
     #[test]
     fn test_only_comments() {
         let mut lexer = Token::lexer("{-- comment --} <!- another -!>");
+        assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
         assert_eq!(lexer.next(), None);
+        assert!(tokenize("{-- comment --} <!- another -!>").0.is_empty());
     }
 
     #[test]
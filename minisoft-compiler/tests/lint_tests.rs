@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod lint_tests {
+    use minisoft_compiler::error_reporter::{ErrorReporter, Severity};
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::lint::{LintWarning, Linter, RULES};
+    use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::semantics::analyzer_core::SemanticAnalyzer;
+    use minisoft_compiler::{CompilerOptions, run_compiler};
+
+    /// Helper function to lint a valid program with the given rules disabled
+    fn lint_test(source: &str, disabled: &[&str]) -> Vec<LintWarning> {
+        let (tokens, _) = tokenize(source);
+        let program = parse(tokens, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        assert!(analyzer.get_errors().is_empty());
+        Linter::without_rules(disabled).run(&program, analyzer.get_symbol_table(), source)
+    }
+
+    fn rules(warnings: &[LintWarning]) -> Vec<&'static str> {
+        warnings.iter().map(|warning| warning.rule).collect()
+    }
+
+    const USAGE_SOURCE: &str = r#"
+        MainPrgm test;
+        Var
+        let used, unused, written: Int;
+        @define Const Limit: Int = 10;
+        @define Const Spare: Int = 3;
+        BeginPg
+        {
+            input(used);
+            written := used + Limit;
+            output(used);
+        }
+        EndPg;
+    "#;
+
+    #[test]
+    fn test_usage_lints() {
+        let warnings = lint_test(USAGE_SOURCE, &[]);
+        assert_eq!(
+            rules(&warnings),
+            vec!["unused-variable", "unused-constant", "assigned-never-read"]
+        );
+
+        // Declarations are reported at the declared name
+        assert_eq!((warnings[0].line, warnings[0].column), (4, 19));
+        assert!(warnings[0].message.contains("'unused'"));
+        assert_eq!(warnings[1].severity, Severity::Note);
+        assert!(warnings[1].message.contains("'Spare'"));
+        assert_eq!((warnings[2].line, warnings[2].column), (10, 13));
+    }
+
+    #[test]
+    fn test_lints_can_be_disabled() {
+        let warnings = lint_test(USAGE_SOURCE, &["unused-constant", "assigned-never-read"]);
+        assert_eq!(rules(&warnings), vec!["unused-variable"]);
+        assert_eq!(
+            Linter::without_rules(&["unused-variable", "unknown"])
+                .rule_names()
+                .len(),
+            RULES.len() - 1
+        );
+    }
+
+    #[test]
+    fn test_shadowed_iterator() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, s: Int;
+            BeginPg
+            {
+                s := 0;
+                for i from 0 to 3 step 1 {
+                    for i from 0 to 2 step 1 {
+                        s := s + i;
+                    }
+                }
+                output(s);
+            }
+            EndPg;
+        "#;
+        let warnings = lint_test(source, &[]);
+        assert_eq!(rules(&warnings), vec!["shadowed-iterator"]);
+        assert_eq!(warnings[0].line, 9);
+    }
+
+    #[test]
+    fn test_comparison_always_true() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            @define Const N: Int = 4;
+            BeginPg
+            {
+                input(x);
+                if (x == x) then {
+                    output(x);
+                }
+                if (N * 2 > 7) then {
+                    output(N);
+                }
+                if (x < N) then {
+                    output(1);
+                }
+            }
+            EndPg;
+        "#;
+        let warnings = lint_test(source, &[]);
+        assert_eq!(
+            rules(&warnings),
+            vec!["comparison-always-true", "comparison-always-true"]
+        );
+        assert_eq!(warnings[0].line, 9);
+        assert_eq!(warnings[1].line, 12);
+        assert!(
+            warnings[0]
+                .report(None)
+                .contains("[comparison-always-true]")
+        );
+    }
+
+    #[test]
+    fn test_warnings_do_not_block_code_generation() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y, unused: Int;
+            BeginPg
+            {
+                output(x);
+                y := 1;
+                output(y);
+            }
+            EndPg;
+        "#;
+        let options = CompilerOptions {
+            disabled_lints: vec!["unused-variable".to_string()],
+            ..CompilerOptions::default()
+        };
+        let result = run_compiler(source.to_string(), false, options).unwrap();
+        assert!(result.errors.is_none());
+        assert!(!result.quadruples.quadruples.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.lints.is_empty());
+
+        let result = run_compiler(source.to_string(), false, CompilerOptions::default()).unwrap();
+        assert_eq!(result.lints.len(), 1);
+        assert_eq!(result.lints[0].rule, "unused-variable");
+    }
+
+    #[test]
+    fn test_diagnostic_severity() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                output(x);
+                x := 1.5;
+            }
+            EndPg;
+        "#;
+        let (tokens, _) = tokenize(source);
+        let program = parse(tokens, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+
+        assert!(
+            analyzer
+                .get_errors()
+                .iter()
+                .all(|error| error.severity() == Severity::Error)
+        );
+        let warning = &analyzer.get_warnings()[0];
+        assert_eq!(warning.severity(), Severity::Warning);
+        assert!(warning.get_error_name().contains("Warning"));
+
+        let (_, lexical_errors) = tokenize("MainPrgm te__st;");
+        assert_eq!(lexical_errors[0].severity(), Severity::Error);
+    }
+}
@@ -0,0 +1,1208 @@
+#[cfg(test)]
+mod optimizer_tests {
+    use minisoft_compiler::codegen::generator::CodeGenerator;
+    use minisoft_compiler::codegen::{
+        ControlFlowGraph, Operand, Operation, Quadruple, QuadrupleProgram,
+    };
+    use minisoft_compiler::codegen::{validate, validate_branches};
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::optimizer::pass_manager::diff;
+    use minisoft_compiler::optimizer::{
+        ConstantFolding, DeadCodeElimination, LocalValueNumbering, LoopInvariantCodeMotion,
+        OptimizationLevel, Pass, PassManager, Peephole, QuadrupleChange, StrengthReduction,
+    };
+    use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::runtime::execute;
+    use minisoft_compiler::semantics::analyzer_core::SemanticAnalyzer;
+    use minisoft_compiler::{CompilerOptions, run_compiler};
+
+    /// Helper function to generate quadruples for a valid program
+    fn generate_test(source: &str) -> (QuadrupleProgram, SemanticAnalyzer) {
+        let (tokens, _) = tokenize(source);
+        let program = match parse(tokens, source) {
+            Ok(program) => program,
+            Err(e) => panic!("Parse error: {}", e),
+        };
+
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        assert!(
+            analyzer.get_errors().is_empty(),
+            "Expected no semantic errors, but found: {:?}",
+            analyzer.get_errors()
+        );
+
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+        assert!(validate(&quadruples).is_empty());
+        assert!(validate_branches(&quadruples, &program).is_empty());
+        (quadruples, analyzer)
+    }
+
+    /// Helper function to check an optimized program behaves like the original
+    fn assert_same_behavior(
+        source: &str,
+        original: &QuadrupleProgram,
+        optimized: &QuadrupleProgram,
+        analyzer: &SemanticAnalyzer,
+        inputs: &[&str],
+    ) {
+        let inputs: Vec<String> = inputs.iter().map(|s| s.to_string()).collect();
+        let before = execute(
+            original,
+            analyzer.get_symbol_table(),
+            &source.to_string(),
+            inputs.clone(),
+        );
+        let after = execute(
+            optimized,
+            analyzer.get_symbol_table(),
+            &source.to_string(),
+            inputs,
+        );
+        assert_eq!(before.output, after.output);
+        assert_eq!(before.error.is_some(), after.error.is_some());
+        assert_eq!(before.memory.variables, after.memory.variables);
+    }
+
+    fn fold_test(source: &str) -> (QuadrupleProgram, QuadrupleProgram, SemanticAnalyzer) {
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        ConstantFolding::new(analyzer.get_symbol_table()).run(&mut optimized);
+        (quadruples, optimized, analyzer)
+    }
+
+    #[test]
+    fn test_folds_literal_arithmetic() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                x := 2 + 3 * 4;
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, analyzer) = fold_test(source);
+        assert!(
+            !optimized
+                .quadruples
+                .iter()
+                .any(|quad| matches!(quad.operation, Operation::Add | Operation::Multiply)),
+            "Arithmetic on literals should be folded: {:?}",
+            optimized.quadruples
+        );
+        assert!(optimized.quadruples.iter().any(|quad| {
+            quad.operation == Operation::Output && quad.operand1 == Operand::IntLiteral(14)
+        }));
+        assert_same_behavior(source, &original, &optimized, &analyzer, &[]);
+    }
+
+    #[test]
+    fn test_propagates_assignments_and_constants() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let f: Float;
+            let n: Int;
+            @define Const Limit: Int = 10;
+            BeginPg
+            {
+                f := 7.0;
+                input(n);
+                output(f / 2.0, " ", n + Limit);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, analyzer) = fold_test(source);
+        assert!(optimized.quadruples.iter().any(|quad| {
+            quad.operation == Operation::Output && quad.operand1 == Operand::FloatLiteral(3.5)
+        }));
+        assert!(optimized.quadruples.iter().any(|quad| {
+            quad.operation == Operation::Add && quad.operand2 == Operand::IntLiteral(10)
+        }));
+        assert_same_behavior(source, &original, &optimized, &analyzer, &["5"]);
+    }
+
+    #[test]
+    fn test_folds_string_operations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let s: String;
+            let n: Int;
+            @define Const Sep: String = ", ";
+            BeginPg
+            {
+                s := "a" + Sep + "b";
+                n := len(s);
+                input(s);
+                output(s + Sep, n);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, analyzer) = fold_test(source);
+        assert!(optimized.quadruples.iter().any(|quad| {
+            quad.operation == Operation::Output && quad.operand1 == Operand::IntLiteral(4)
+        }));
+        // Only the join with the input is left
+        let joins: Vec<_> = optimized
+            .quadruples
+            .iter()
+            .filter(|quad| quad.operation == Operation::Concat)
+            .collect();
+        assert_eq!(joins.len(), 1, "{:?}", optimized.quadruples);
+        assert_eq!(joins[0].operand2, Operand::StringLiteral(", ".to_string()));
+        assert!(
+            !optimized
+                .quadruples
+                .iter()
+                .any(|quad| quad.operation == Operation::Length)
+        );
+        assert_same_behavior(source, &original, &optimized, &analyzer, &["x"]);
+    }
+
+    #[test]
+    fn test_folds_constant_conditions() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            @define Const Debug: Int = 0;
+            BeginPg
+            {
+                if (Debug == 1) then {
+                    x := 1;
+                } else {
+                    x := 2;
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, analyzer) = fold_test(source);
+        assert!(
+            !optimized
+                .quadruples
+                .iter()
+                .any(|quad| matches!(quad.operation, Operation::JumpIfFalse(_))),
+            "Constant condition should become an unconditional jump: {:?}",
+            optimized.quadruples
+        );
+        assert_same_behavior(source, &original, &optimized, &analyzer, &[]);
+    }
+
+    #[test]
+    fn test_keeps_values_changed_in_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, sum: Int;
+            BeginPg
+            {
+                sum := 0;
+                for i from 1 to 4 step 1 {
+                    sum := sum + i;
+                }
+                output(sum);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, analyzer) = fold_test(source);
+        assert!(optimized.quadruples.iter().any(|quad| {
+            quad.operation == Operation::Add
+                && quad.operand1 == Operand::Variable("sum".to_string())
+        }));
+        assert_same_behavior(source, &original, &optimized, &analyzer, &[]);
+    }
+
+    #[test]
+    fn test_leaves_faulting_operations_for_runtime() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                x := 20000;
+                x := x * 2;
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, analyzer) = fold_test(source);
+        assert!(
+            optimized
+                .quadruples
+                .iter()
+                .any(|quad| quad.operation == Operation::Multiply)
+        );
+        assert_same_behavior(source, &original, &optimized, &analyzer, &[]);
+    }
+
+    /// Helper function to fold constants then remove dead code, returning the removed count
+    fn eliminate_test(source: &str) -> (QuadrupleProgram, QuadrupleProgram, usize) {
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        ConstantFolding::new(analyzer.get_symbol_table()).run(&mut optimized);
+        let removed = DeadCodeElimination::new().run(&mut optimized);
+        assert!(
+            validate(&optimized).is_empty(),
+            "Optimized program is malformed: {:?}",
+            validate(&optimized)
+        );
+
+        let original_run = execute(
+            &quadruples,
+            analyzer.get_symbol_table(),
+            &source.to_string(),
+            vec![],
+        );
+        let optimized_run = execute(
+            &optimized,
+            analyzer.get_symbol_table(),
+            &source.to_string(),
+            vec![],
+        );
+        assert_eq!(original_run.output, optimized_run.output);
+        (quadruples, optimized, removed)
+    }
+
+    #[test]
+    fn test_removes_constant_false_branch() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                x := 0;
+                if (0) then {
+                    output("never");
+                }
+                output("done");
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, removed) = eliminate_test(source);
+        assert_eq!(
+            removed,
+            original.quadruples.len() - optimized.quadruples.len()
+        );
+        assert!(
+            !optimized
+                .quadruples
+                .iter()
+                .any(|quad| { quad.operand1 == Operand::StringLiteral("never".to_string()) }),
+            "The then block of if (0) is unreachable: {:?}",
+            optimized.quadruples
+        );
+        // The jump over the removed block is left for the peephole optimizer
+        let labels = optimized
+            .quadruples
+            .iter()
+            .filter(|quad| matches!(quad.operation, Operation::Label(_)))
+            .count();
+        assert!(
+            labels <= 1,
+            "Unused labels should be removed: {:?}",
+            optimized.quadruples
+        );
+    }
+
+    #[test]
+    fn test_removes_unused_temps_and_variables() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b, unused: Int;
+            BeginPg
+            {
+                a := 3;
+                b := a * 2;
+                unused := b + 1;
+                output(b);
+            }
+            EndPg;
+        "#;
+
+        let (_, optimized, removed) = eliminate_test(source);
+        assert!(removed > 0);
+        assert_eq!(
+            optimized
+                .quadruples
+                .iter()
+                .filter(|quad| quad.operation != Operation::Output)
+                .count(),
+            0,
+            "Only the output should be left: {:?}",
+            optimized.quadruples
+        );
+    }
+
+    #[test]
+    fn test_keeps_side_effects() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n, i: Int;
+            let a: [Int; 2];
+            BeginPg
+            {
+                input(n);
+                a[0] := 1;
+                i := 0;
+                do {
+                    i := i + 1;
+                } while (i < 3);
+                output(a[0]);
+            }
+            EndPg;
+        "#;
+
+        let (mut optimized, _) = generate_test(source);
+        DeadCodeElimination::new().run(&mut optimized);
+
+        let ops: Vec<&Operation> = optimized.quadruples.iter().map(|q| &q.operation).collect();
+        assert!(ops.contains(&&Operation::Input));
+        assert!(ops.contains(&&Operation::ArrayStore));
+        // The loop counter feeds the loop condition, so it stays
+        assert!(ops.contains(&&Operation::Add));
+    }
+
+    /// Helper function to run value numbering and check the program still behaves the same
+    fn value_numbering_test(source: &str, inputs: &[&str]) -> (QuadrupleProgram, usize) {
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        let replaced = LocalValueNumbering::new().run(&mut optimized);
+        assert_same_behavior(source, &quadruples, &optimized, &analyzer, inputs);
+        (optimized, replaced)
+    }
+
+    fn count(program: &QuadrupleProgram, operation: Operation) -> usize {
+        program
+            .quadruples
+            .iter()
+            .filter(|quad| quad.operation == operation)
+            .count()
+    }
+
+    #[test]
+    fn test_value_numbering_reuses_loads_and_products() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, b, x, y: Int;
+            let a: [Int; 4] = {1, 2, 3, 4};
+            BeginPg
+            {
+                input(i);
+                input(b);
+                x := a[i] + a[i] * b;
+                y := b * a[i];
+                output(x, " ", y);
+            }
+            EndPg;
+        "#;
+
+        let (optimized, replaced) = value_numbering_test(source, &["2", "5"]);
+        assert_eq!(replaced, 3);
+        assert_eq!(count(&optimized, Operation::ArrayLoad), 1);
+        assert_eq!(count(&optimized, Operation::Multiply), 1);
+    }
+
+    #[test]
+    fn test_value_numbering_invalidated_by_writes() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, x, y, z: Int;
+            let a: [Int; 4] = {1, 2, 3, 4};
+            BeginPg
+            {
+                input(i);
+                x := a[i] + 1;
+                a[i] := 10;
+                y := a[i] + 1;
+                i := i + 1;
+                z := a[i] + 1;
+                input(i);
+                z := z + a[i];
+                input(a[i]);
+                z := z + a[i];
+                output(x, " ", y, " ", z);
+            }
+            EndPg;
+        "#;
+
+        let (optimized, replaced) = value_numbering_test(source, &["1", "0", "5"]);
+        assert_eq!(
+            replaced, 0,
+            "Nothing can be reused: {:?}",
+            optimized.quadruples
+        );
+        assert_eq!(count(&optimized, Operation::ArrayLoad), 5);
+    }
+
+    #[test]
+    fn test_value_numbering_stays_within_blocks() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            BeginPg
+            {
+                input(x);
+                y := x * 2;
+                if (y > 4) then {
+                    y := x * 2 + 1;
+                }
+                output(y);
+            }
+            EndPg;
+        "#;
+
+        let (optimized, replaced) = value_numbering_test(source, &["3"]);
+        assert_eq!(replaced, 0);
+        assert_eq!(count(&optimized, Operation::Multiply), 2);
+    }
+
+    /// Helper function to run a loop pass and check the result is still well formed
+    fn loop_test(
+        source: &str,
+        inputs: &[&str],
+        pass: impl FnOnce(&SemanticAnalyzer) -> Box<dyn Pass>,
+    ) -> (QuadrupleProgram, QuadrupleProgram, usize) {
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        let changes = pass(&analyzer).run(&mut optimized);
+        assert!(
+            validate(&optimized).is_empty(),
+            "Invalid program: {:?}",
+            validate(&optimized)
+        );
+        assert_same_behavior(source, &quadruples, &optimized, &analyzer, inputs);
+        (quadruples, optimized, changes)
+    }
+
+    /// Quadruples inside the innermost loop of a program
+    fn loop_body(program: &QuadrupleProgram) -> Vec<Operation> {
+        let cfg = ControlFlowGraph::build(program);
+        let natural = cfg
+            .natural_loops()
+            .into_iter()
+            .next()
+            .expect("Missing loop");
+        natural
+            .blocks
+            .iter()
+            .flat_map(|&block| cfg.blocks[block].quadruples.iter())
+            .map(|quad| quad.operation.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_hoists_invariant_computations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n, s: Int;
+            BeginPg
+            {
+                input(n);
+                s := 0;
+                do {
+                    s := s + n * 2 + (n > 3);
+                    i := n / 2;
+                } while (s < 50);
+                output(s, " ", i);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, moved) =
+            loop_test(source, &["4"], |_| Box::new(LoopInvariantCodeMotion::new()));
+        assert_eq!(moved, 3);
+        assert_eq!(original.quadruples.len(), optimized.quadruples.len());
+
+        let body = loop_body(&optimized);
+        assert!(!body.contains(&Operation::Multiply));
+        assert!(!body.contains(&Operation::GreaterThan));
+        assert!(!body.contains(&Operation::Divide));
+    }
+
+    #[test]
+    fn test_keeps_failing_computations_in_skipped_paths() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n, x: Int;
+            let a: [Int; 3];
+            BeginPg
+            {
+                input(n);
+                x := 0;
+                for i from 1 to 0 step 1 {
+                    x := a[n] + 10 / n;
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        // The loop never runs, the hoisted load and division must be skipped too
+        let (_, optimized, moved) =
+            loop_test(source, &["0"], |_| Box::new(LoopInvariantCodeMotion::new()));
+        assert_eq!(moved, 3);
+        let body = loop_body(&optimized);
+        assert!(!body.contains(&Operation::ArrayLoad));
+        assert!(!body.contains(&Operation::Divide));
+    }
+
+    #[test]
+    fn test_skipped_loops_evaluate_nothing() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, k, n, x: Int;
+            BeginPg
+            {
+                input(n);
+                x := 0;
+                k := 100;
+                for i from 1 to n step 1 {
+                    x := k * 1000;
+                }
+                while (x < n) {
+                    x := x + k * 1000;
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        // `k * 1000` overflows, but only a loop that runs evaluates it
+        let (_, _, moved) = loop_test(source, &["0"], |_| Box::new(LoopInvariantCodeMotion::new()));
+        assert_eq!(moved, 2);
+        loop_test(source, &["1"], |_| Box::new(LoopInvariantCodeMotion::new()));
+    }
+
+    #[test]
+    fn test_keeps_conditional_failing_computations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, k, n, x: Int;
+            BeginPg
+            {
+                input(n);
+                x := 0;
+                k := 100;
+                for i from 1 to 3 step 1 {
+                    if (i > n) then {
+                        x := k * 1000;
+                    }
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        // The multiplication only runs on some iterations, possibly none
+        let (_, optimized, moved) =
+            loop_test(source, &["5"], |_| Box::new(LoopInvariantCodeMotion::new()));
+        assert_eq!(moved, 0);
+        assert!(loop_body(&optimized).contains(&Operation::Multiply));
+    }
+
+    #[test]
+    fn test_keeps_loads_of_stored_arrays() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, k, x: Int;
+            let a: [Int; 3];
+            BeginPg
+            {
+                input(k);
+                a[k] := 0;
+                i := 0;
+                do {
+                    x := a[k];
+                    a[k] := x + 1;
+                    i := i + 1;
+                } while (i < 3);
+                output(a[k]);
+            }
+            EndPg;
+        "#;
+
+        // The loop stores to `a`, so the load must see each iteration's store
+        let (_, optimized, moved) =
+            loop_test(source, &["1"], |_| Box::new(LoopInvariantCodeMotion::new()));
+        assert_eq!(moved, 0);
+        assert!(loop_body(&optimized).contains(&Operation::ArrayLoad));
+
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        PassManager::for_level(OptimizationLevel::O2, analyzer.get_symbol_table())
+            .run(&mut optimized);
+        assert_same_behavior(source, &quadruples, &optimized, &analyzer, &["1"]);
+    }
+
+    #[test]
+    fn test_hoists_from_nested_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, j, n, s: Int;
+            BeginPg
+            {
+                input(n);
+                s := 0;
+                for i from 1 to 3 step 1 {
+                    for j from 1 to n step 1 {
+                        s := s + (n + 1) * (i + 2);
+                    }
+                }
+                output(s);
+            }
+            EndPg;
+        "#;
+
+        let (_, optimized, moved) =
+            loop_test(source, &["4"], |_| Box::new(LoopInvariantCodeMotion::new()));
+        assert!(moved >= 2, "Expected hoisted quadruples, got {}", moved);
+
+        // `n + 1` leaves both loops, `i + 2` only the inner one
+        let inner = loop_body(&optimized);
+        assert_eq!(inner.iter().filter(|op| **op == Operation::Add).count(), 2);
+    }
+
+    #[test]
+    fn test_strength_reduces_induction_products() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, s: Int;
+            let a: [Int; 40];
+            BeginPg
+            {
+                s := 0;
+                for i from 0 to 9 step 1 {
+                    a[i * 4] := i;
+                    s := s + a[i * 4] + i * 3;
+                }
+                for i from 10 to 1 step (-3) {
+                    s := s + i * 2;
+                }
+                output(s);
+            }
+            EndPg;
+        "#;
+
+        let (_, optimized, replaced) = loop_test(source, &[], |analyzer| {
+            Box::new(StrengthReduction::new(analyzer.get_symbol_table()))
+        });
+        assert_eq!(replaced, 4);
+
+        let cfg = ControlFlowGraph::build(&optimized);
+        for natural in cfg.natural_loops() {
+            let multiplies = natural
+                .blocks
+                .iter()
+                .flat_map(|&block| cfg.blocks[block].quadruples.iter())
+                .filter(|quad| quad.operation == Operation::Multiply)
+                .count();
+            assert_eq!(multiplies, 0);
+        }
+    }
+
+    #[test]
+    fn test_strength_reduction_stays_within_iterations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n, x: Int;
+            BeginPg
+            {
+                input(n);
+                x := 0;
+                for i from 1 to n step 1 {
+                    x := i * 10000;
+                }
+                for i from 4 to n step 1 {
+                    x := x + i * 10000;
+                }
+                i := 1;
+                do {
+                    x := i * 16000;
+                    i := i + 1;
+                } while (i < n);
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        // `4 * 10000` and `3 * 16000` overflow: no step after the last
+        // iteration and no skipped loop may compute them
+        let (_, _, replaced) = loop_test(source, &["3"], |analyzer| {
+            Box::new(StrengthReduction::new(analyzer.get_symbol_table()))
+        });
+        assert_eq!(replaced, 3);
+    }
+
+    #[test]
+    fn test_strength_reduction_skips_other_writes() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, s: Int;
+            BeginPg
+            {
+                s := 0;
+                i := 1;
+                do {
+                    s := s + i * 5;
+                    i := i * 2;
+                } while (i < 100);
+                output(s);
+            }
+            EndPg;
+        "#;
+
+        let (_, optimized, replaced) = loop_test(source, &[], |analyzer| {
+            Box::new(StrengthReduction::new(analyzer.get_symbol_table()))
+        });
+        assert_eq!(replaced, 0);
+        assert!(loop_body(&optimized).contains(&Operation::Multiply));
+    }
+
+    /// Helper function to run the peephole pass with the given rules and check behavior
+    fn peephole_test(
+        source: &str,
+        inputs: &[&str],
+        peephole: Peephole,
+    ) -> (QuadrupleProgram, QuadrupleProgram, Peephole) {
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        let mut peephole = peephole;
+        let changes = peephole.run(&mut optimized);
+        assert_eq!(
+            changes,
+            peephole.hits().iter().map(|(_, hits)| hits).sum::<usize>()
+        );
+        assert!(
+            validate(&optimized).is_empty(),
+            "Invalid program: {:?}",
+            validate(&optimized)
+        );
+        assert_same_behavior(source, &quadruples, &optimized, &analyzer, inputs);
+        (quadruples, optimized, peephole)
+    }
+
+    fn hits(peephole: &Peephole, rule: &str) -> usize {
+        peephole
+            .hits()
+            .iter()
+            .find(|(name, _)| *name == rule)
+            .map(|(_, hits)| *hits)
+            .unwrap_or_else(|| panic!("Rule {} not enabled", rule))
+    }
+
+    #[test]
+    fn test_peephole_coalesces_copies() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, s: Int;
+            let h: Int;
+            BeginPg
+            {
+                s := 0;
+                for i from 1 to 4 step 1 {
+                    s := s + i;
+                }
+                h := s / 2;
+                output(s, " ", h);
+            }
+            EndPg;
+        "#;
+
+        let (original, optimized, peephole) = peephole_test(source, &[], Peephole::new());
+        assert_eq!(hits(&peephole, "copy-coalescing"), 3);
+        assert_eq!(optimized.quadruples.len(), original.quadruples.len() - 3);
+        assert!(optimized.quadruples.iter().any(|quad| {
+            quad.operation == Operation::Add
+                && quad.operand1 == Operand::Variable("i".to_string())
+                && quad.result == Operand::Variable("i".to_string())
+        }));
+    }
+
+    #[test]
+    fn test_peephole_chains_jumps() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            BeginPg
+            {
+                input(x);
+                y := 0;
+                if (x > 0) then {
+                    if (x > 10) then {
+                        y := 2;
+                    } else {
+                        y := 1;
+                    }
+                } else {
+                    y := 0;
+                }
+                output(y);
+            }
+            EndPg;
+        "#;
+
+        for input in ["-1", "5", "20"] {
+            let (_, optimized, peephole) = peephole_test(
+                source,
+                &[input],
+                Peephole::with_rules(&["jump-to-next", "jump-chaining"]),
+            );
+            assert_eq!(peephole.hits().len(), 2);
+            assert_eq!(hits(&peephole, "jump-chaining"), 1);
+            assert_eq!(hits(&peephole, "jump-to-next"), 0);
+
+            // No jump lands on another jump
+            for quad in &optimized.quadruples {
+                let Operation::Jump(label) = quad.operation else {
+                    continue;
+                };
+                let target = optimized
+                    .quadruples
+                    .iter()
+                    .position(|quad| quad.operation == Operation::Label(label))
+                    .unwrap();
+                assert!(!matches!(
+                    optimized
+                        .quadruples
+                        .get(target + 1)
+                        .map(|quad| &quad.operation),
+                    Some(Operation::Jump(_))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_peephole_removes_jump_to_next() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                if (1) then {
+                    x := 1;
+                } else {
+                    x := 2;
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        // Removing the else block leaves the jump over it pointing at the next quadruple
+        let (quadruples, analyzer) = generate_test(source);
+        let mut optimized = quadruples.clone();
+        ConstantFolding::new(analyzer.get_symbol_table()).run(&mut optimized);
+        DeadCodeElimination::new().run(&mut optimized);
+
+        let mut peephole = Peephole::with_rules(&["jump-to-next"]);
+        assert_eq!(peephole.run(&mut optimized), 1);
+        assert!(
+            !optimized
+                .quadruples
+                .iter()
+                .any(|quad| matches!(quad.operation, Operation::Jump(_))),
+            "Unexpected jump: {:?}",
+            optimized.quadruples
+        );
+        assert_same_behavior(source, &quadruples, &optimized, &analyzer, &[]);
+    }
+
+    #[test]
+    fn test_peephole_identities_and_inversions() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y, z: Int;
+            BeginPg
+            {
+                input(x);
+                y := x * 1 + 0;
+                z := 1 * (y - 0) / 1;
+                if (!(y < 3)) then {
+                    output("big");
+                }
+                if (!z) then {
+                    output("zero");
+                }
+                output(y, z);
+            }
+            EndPg;
+        "#;
+
+        for input in ["0", "7"] {
+            let (_, optimized, peephole) = peephole_test(source, &[input], Peephole::new());
+            assert_eq!(hits(&peephole, "algebraic-identity"), 5);
+            assert_eq!(hits(&peephole, "inverted-comparison"), 1);
+            assert_eq!(hits(&peephole, "negated-branch"), 1);
+            assert!(
+                optimized
+                    .quadruples
+                    .iter()
+                    .all(|quad| !matches!(quad.operation, Operation::Not | Operation::LessThan))
+            );
+            assert!(
+                optimized
+                    .quadruples
+                    .iter()
+                    .any(|quad| quad.operation == Operation::GreaterEqual)
+            );
+        }
+    }
+
+    const PIPELINE_SOURCE: &str = r#"
+        MainPrgm test;
+        Var
+        let i, n, s: Int;
+        let a: [Int; 20];
+        BeginPg
+        {
+            input(n);
+            s := 0;
+            for i from 0 to n step 1 {
+                a[i * 4] := i * 1 + n * 2;
+                s := s + a[i * 4];
+            }
+            if (!(s < 10)) then {
+                output(s);
+            }
+        }
+        EndPg;
+    "#;
+
+    #[test]
+    fn test_optimization_levels_parse() {
+        for (text, level) in [
+            ("-O0", OptimizationLevel::O0),
+            ("O1", OptimizationLevel::O1),
+            ("2", OptimizationLevel::O2),
+            ("o2", OptimizationLevel::O2),
+        ] {
+            assert_eq!(text.parse::<OptimizationLevel>(), Ok(level));
+        }
+        assert!("O3".parse::<OptimizationLevel>().is_err());
+        assert_eq!(OptimizationLevel::O1.to_string(), "O1");
+    }
+
+    #[test]
+    fn test_pass_manager_levels() {
+        let (quadruples, analyzer) = generate_test(PIPELINE_SOURCE);
+        let symbols = analyzer.get_symbol_table();
+
+        let mut unchanged = quadruples.clone();
+        assert!(
+            PassManager::for_level(OptimizationLevel::O0, symbols)
+                .run(&mut unchanged)
+                .is_empty()
+        );
+        assert_eq!(unchanged.quadruples, quadruples.quadruples);
+
+        assert_eq!(
+            PassManager::for_level(OptimizationLevel::O1, symbols).pass_names(),
+            vec!["constant-folding", "peephole", "dead-code-elimination"]
+        );
+
+        let mut manager = PassManager::for_level(OptimizationLevel::O2, symbols);
+        assert_eq!(manager.pass_names().len(), 6);
+        let mut optimized = quadruples.clone();
+        let snapshots = manager.run(&mut optimized);
+        assert_eq!(snapshots.len(), 6);
+        assert!(snapshots.iter().all(|snapshot| snapshot.diff.is_none()));
+        assert!(!loop_body(&optimized).contains(&Operation::Multiply));
+        assert_eq!(
+            snapshots.last().unwrap().program.quadruples,
+            optimized.quadruples
+        );
+        assert!(validate(&optimized).is_empty());
+        assert_same_behavior(PIPELINE_SOURCE, &quadruples, &optimized, &analyzer, &["4"]);
+    }
+
+    #[test]
+    fn test_pass_snapshots_diff_previous_program() {
+        let (quadruples, analyzer) = generate_test(PIPELINE_SOURCE);
+        let mut optimized = quadruples.clone();
+        let snapshots = PassManager::for_level(OptimizationLevel::O2, analyzer.get_symbol_table())
+            .with_diffs()
+            .run(&mut optimized);
+
+        let mut before = quadruples;
+        for snapshot in snapshots {
+            let after = &snapshot.program;
+            let diff = snapshot.diff.as_ref().expect("Missing pass diff");
+            let (mut old, mut new) = (0, 0);
+            for change in diff {
+                match *change {
+                    QuadrupleChange::Kept {
+                        before: b,
+                        after: a,
+                    } => {
+                        assert_eq!(b, old);
+                        assert_eq!(a, new);
+                        assert_eq!(
+                            before.quadruples[b].to_string(),
+                            after.quadruples[a].to_string()
+                        );
+                        old += 1;
+                        new += 1;
+                    }
+                    QuadrupleChange::Removed { before: b } => {
+                        assert_eq!(b, old);
+                        old += 1;
+                    }
+                    QuadrupleChange::Added { after: a } => {
+                        assert_eq!(a, new);
+                        new += 1;
+                    }
+                }
+            }
+            assert_eq!(old, before.quadruples.len(), "{}", snapshot.pass);
+            assert_eq!(new, after.quadruples.len(), "{}", snapshot.pass);
+            if snapshot.changes == 0 {
+                assert!(
+                    diff.iter()
+                        .all(|change| matches!(change, QuadrupleChange::Kept { .. }))
+                );
+            }
+            before = snapshot.program;
+        }
+    }
+
+    fn outputs(values: impl Iterator<Item = i32>) -> QuadrupleProgram {
+        let mut program = QuadrupleProgram::new();
+        for value in values {
+            program.add(Quadruple {
+                operation: Operation::Output,
+                operand1: Operand::IntLiteral(value),
+                operand2: Operand::Empty,
+                result: Operand::Empty,
+                span: None,
+                statement_span: None,
+            });
+        }
+        program
+    }
+
+    #[test]
+    fn test_diff_matches_unchanged_ends() {
+        let before = outputs(0..3000);
+        let after = outputs((0..3000).map(|value| if value == 1500 { -1 } else { value }));
+        let changes = diff(&before, &after);
+        assert_eq!(changes.len(), 3001);
+        assert_eq!(changes[1500], QuadrupleChange::Removed { before: 1500 });
+        assert_eq!(changes[1501], QuadrupleChange::Added { after: 1500 });
+        assert_eq!(
+            changes[2000],
+            QuadrupleChange::Kept {
+                before: 1999,
+                after: 1999
+            }
+        );
+
+        // A middle too large to search is shown as removed then added
+        let after = outputs((0..3000).map(|value| {
+            if (1000..2200).contains(&value) {
+                -value
+            } else {
+                value
+            }
+        }));
+        let changes = diff(&before, &after);
+        assert_eq!(changes.len(), 4200);
+        assert!(
+            changes[..1000]
+                .iter()
+                .all(|change| matches!(change, QuadrupleChange::Kept { .. }))
+        );
+        assert_eq!(changes[1000], QuadrupleChange::Removed { before: 1000 });
+        assert_eq!(changes[2200], QuadrupleChange::Added { after: 1000 });
+        assert_eq!(
+            changes[3400],
+            QuadrupleChange::Kept {
+                before: 2200,
+                after: 2200
+            }
+        );
+    }
+
+    #[test]
+    fn test_compiler_optimization_option() {
+        let options = CompilerOptions {
+            optimization_level: OptimizationLevel::O0,
+            ..CompilerOptions::default()
+        };
+        let result = run_compiler(PIPELINE_SOURCE.to_string(), false, options).unwrap();
+        assert!(result.optimization_report.is_empty());
+        assert_eq!(
+            result.optimized_quadruples.quadruples.len(),
+            result.quadruples.quadruples.len()
+        );
+
+        let result = run_compiler(
+            PIPELINE_SOURCE.to_string(),
+            false,
+            CompilerOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.optimization_level, OptimizationLevel::O2);
+        assert!(
+            result
+                .optimization_report
+                .iter()
+                .all(|report| report.diff.is_none())
+        );
+
+        let options = CompilerOptions {
+            pass_diffs: true,
+            ..CompilerOptions::default()
+        };
+        let result = run_compiler(PIPELINE_SOURCE.to_string(), false, options).unwrap();
+        let peephole = result
+            .optimization_report
+            .iter()
+            .find(|report| report.pass == "peephole")
+            .expect("Missing peephole report");
+        assert!(!peephole.rule_hits.is_empty());
+        assert_eq!(
+            peephole.quadruples.quadruples.len(),
+            peephole
+                .diff
+                .as_ref()
+                .expect("Missing peephole diff")
+                .iter()
+                .filter(|change| change.after.is_some())
+                .count()
+        );
+    }
+}
@@ -1,10 +1,13 @@
 #[cfg(test)]
 mod parser_tests {
     use minisoft_compiler::parser::ast::{
-        DeclarationKind, ExpressionKind, Operator, Program, StatementKind, Type,
+        DeclarationKind, ExpressionKind, LiteralKind, Operator, Program, StatementKind, Type,
+        UnaryOperator,
     };
     use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::parser::formatter::format_program;
     use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::{FormatOptions, format_code};
 
     /// Helper function to parse a source string and return the AST
     fn parse_test(source: &str) -> Program {
@@ -90,6 +93,199 @@ mod parser_tests {
         assert!(matches!(&program.statements[2].node, StatementKind::For(_, _, _, _, _)));
     }
 
+    #[test]
+    fn test_while_statement() {
+        let source = "
+            MainPrgm test ;
+            Var
+            let i : Int ;
+            BeginPg {
+                while (i < 10) { i := i + 1 ; }
+                while (i > 0) { }
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+        assert_eq!(program.statements.len(), 2);
+        match &program.statements[0].node {
+            StatementKind::While(condition, body) => {
+                assert!(matches!(
+                    condition.node,
+                    ExpressionKind::BinaryOp(_, Operator::LessThan, _)
+                ));
+                assert_eq!(body.len(), 1);
+            }
+            other => panic!("Expected a while loop, found {:?}", other),
+        }
+        assert!(matches!(&program.statements[1].node, StatementKind::While(_, body) if body.is_empty()));
+
+        let formatted = format_program(&program);
+        assert!(formatted.contains("    while (i < 10) {\n        i := i + 1;\n    }\n"));
+        assert_eq!(format_program(&parse_test(&formatted)), formatted);
+    }
+
+    #[test]
+    fn test_subprograms() {
+        let source = "
+            MainPrgm test ;
+            Var
+            let r : Int ;
+            Function Max(t: [Int; 3], n: Int): Int
+            Var
+            let i, m : Int ;
+            {
+                m := t[0] ;
+                return Max(t, n - 1) + m ;
+            }
+            Procedure Show()
+            Var
+            { output(r) ; return ; }
+            BeginPg {
+                r := Max(r, 2) * 2 ;
+                Show() ;
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+        assert_eq!(program.subprograms.len(), 2);
+        let max = &program.subprograms[0].node;
+        assert_eq!(max.name, "Max");
+        assert_eq!(max.return_type, Some(Type::Int));
+        assert_eq!(max.parameters.len(), 2);
+        assert_eq!(max.parameters[0].node.type_name(), "[Int; 3]");
+        assert_eq!(max.parameters[1].node.size, None);
+        assert_eq!(max.local_names(), vec!["t", "n", "i", "m"]);
+        assert!(matches!(&max.statements[1].node, StatementKind::Return(Some(_))));
+
+        let show = &program.subprograms[1].node;
+        assert!(show.parameters.is_empty() && show.return_type.is_none());
+        assert!(matches!(&show.statements[1].node, StatementKind::Return(None)));
+
+        match &program.statements[0].node {
+            StatementKind::Assignment(_, value) => assert!(matches!(
+                &value.node,
+                ExpressionKind::BinaryOp(call, Operator::Multiply, _)
+                    if matches!(&call.node, ExpressionKind::Call(name, arguments) if name == "Max" && arguments.len() == 2)
+            )),
+            other => panic!("Expected an assignment, found {:?}", other),
+        }
+        assert!(matches!(&program.statements[1].node, StatementKind::Call(name, arguments) if name == "Show" && arguments.is_empty()));
+
+        let formatted = format_program(&program);
+        assert!(formatted.contains("Function Max(t: [Int; 3], n: Int): Int\nVar\nlet i, m: Int;\n{\n"));
+        assert!(formatted.contains("Procedure Show()\nVar\n{\n    output(r);\n    return;\n}\n"));
+        assert_eq!(format_program(&parse_test(&formatted)), formatted);
+
+        // Subprograms come before the program body
+        assert!(expect_parse_error(
+            "MainPrgm t; Var BeginPg { } EndPg; Procedure P() Var { }"
+        ));
+    }
+
+    #[test]
+    fn test_block_declarations() {
+        let source = "
+            MainPrgm test ;
+            Var
+            let x : Int ;
+            BeginPg {
+                { let x : Int = x + 1 ; @define Const K : Int = 2 ; output(x * K) ; }
+                for x from 0 to 2 step 1 { let y : [Float ; 2] ; y[0] := 1.5 ; }
+                if (x > 0) then { x := 1 ; }
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+        assert_eq!(program.statements.len(), 3);
+        match &program.statements[0].node {
+            StatementKind::Scope(declarations, statements) => {
+                assert_eq!(declarations.len(), 2);
+                assert_eq!(declarations[0].node.names(), vec!["x"]);
+                assert_eq!(declarations[1].node.names(), vec!["K"]);
+                assert_eq!(statements.len(), 1);
+            }
+            other => panic!("Expected a block, found {:?}", other),
+        }
+        // A loop body with declarations is a block of its own
+        match &program.statements[1].node {
+            StatementKind::For(.., body) => assert!(matches!(
+                &body[..],
+                [statement] if matches!(&statement.node, StatementKind::Scope(declarations, statements) if declarations.len() == 1 && statements.len() == 1)
+            )),
+            other => panic!("Expected a for loop, found {:?}", other),
+        }
+        assert!(matches!(&program.statements[2].node, StatementKind::IfThen(_, body) if matches!(body[0].node, StatementKind::Assignment(..))));
+
+        let formatted = format_program(&program);
+        assert!(formatted.contains(
+            "    {\n        let x: Int = x + 1;\n        @define Const K: Int = 2;\n        output(x * K);\n    }\n"
+        ));
+        assert!(formatted.contains(
+            "    for x from 0 to 2 step 1 {\n        let y: [Float; 2];\n        y[0] := 1.5;\n    }\n"
+        ));
+        assert_eq!(format_program(&parse_test(&formatted)), formatted);
+
+        // Declarations come before the statements of their block
+        assert!(expect_parse_error(
+            "MainPrgm t; Var BeginPg { { output(1); let x: Int; } } EndPg;"
+        ));
+    }
+
+    #[test]
+    fn test_string_expressions() {
+        let source = "
+            MainPrgm test ;
+            Var
+            let s : String = \"ab\" ;
+            let n : Int ;
+            @define Const Sep : String = \", \" ;
+            BeginPg {
+                s := s + Sep + \"c\" ;
+                n := len(s) * 2 ;
+                if (s == \"x\") then { output(\"s: \" + s, len(s)) ; }
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+        assert!(matches!(
+            &program.declarations[0].node,
+            DeclarationKind::VariableWithInit(_, Type::String, value)
+                if matches!(&value.node, ExpressionKind::Literal(literal) if literal.node == LiteralKind::String("ab".to_string()))
+        ));
+        assert!(matches!(&program.declarations[2].node, DeclarationKind::Constant(_, Type::String, _)));
+        match &program.statements[1].node {
+            StatementKind::Assignment(_, value) => assert!(matches!(
+                &value.node,
+                ExpressionKind::BinaryOp(length, Operator::Multiply, _)
+                    if matches!(&length.node, ExpressionKind::UnaryOp(UnaryOperator::Length, _))
+            )),
+            other => panic!("Expected an assignment, found {:?}", other),
+        }
+
+        let formatted = format_program(&program);
+        assert!(formatted.contains("let s: String = \"ab\";\n"));
+        assert!(formatted.contains("    n := len(s) * 2;\n"));
+        assert!(formatted.contains("        output(\"s: \" + s, len(s));\n"));
+        assert_eq!(format_program(&parse_test(&formatted)), formatted);
+
+        // len only means the builtin when it is called, it can still name a variable
+        let program = parse_test(
+            "MainPrgm t; Var let len: Int; let s: String; BeginPg { len := len(s) + len; } EndPg;",
+        );
+        match &program.statements[0].node {
+            StatementKind::Assignment(target, value) => {
+                assert!(matches!(&target.node, ExpressionKind::Identifier(name) if name == "len"));
+                assert!(matches!(
+                    &value.node,
+                    ExpressionKind::BinaryOp(length, Operator::Add, variable)
+                        if matches!(&length.node, ExpressionKind::UnaryOp(UnaryOperator::Length, _))
+                            && matches!(&variable.node, ExpressionKind::Identifier(name) if name == "len")
+                ));
+            }
+            other => panic!("Expected an assignment, found {:?}", other),
+        }
+    }
+
     #[test]
     fn test_input_output() {
         let source = "
@@ -488,4 +684,88 @@ mod parser_tests {
         // Note: The parser should accept these assignments since it doesn't do type checking,
         // but the semantic analyzer would catch the type errors later
     }
+
+    #[test]
+    fn test_format_program() {
+        let source = "
+            MainPrgm fmt ; Var let a , b : Int ; let t : [Float;3] = {1.0,(-2.5),3.25};
+            @define Const Low : Int = (-4) ;
+            BeginPg { a := (a+b)*(a-(b-1)) ; if (!(a>b) AND (a<b OR b==Low)) then { output(\"a\",a) ; }
+            else { do { b := b-1; } while (b>0) ; }
+            for a from Low to 10 step 2 { t[a/2] := 1.0 ; } } EndPg ;
+        ";
+        let expected = "MainPrgm fmt;
+Var
+let a, b: Int;
+let t: [Float; 3] = {1.0, (-2.5), 3.25};
+@define Const Low: Int = (-4);
+BeginPg
+{
+    a := (a + b) * (a - (b - 1));
+    if (!(a > b) AND (a < b OR b == Low)) then {
+        output(\"a\", a);
+    } else {
+        do {
+            b := b - 1;
+        } while (b > 0);
+    }
+    for a from Low to 10 step 2 {
+        t[a / 2] := 1.0;
+    }
+}
+EndPg;
+";
+
+        let formatted = format_program(&parse_test(source));
+        assert_eq!(formatted, expected);
+        // Formatting the formatted program changes nothing
+        assert_eq!(format_program(&parse_test(&formatted)), formatted);
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        let source = "{-- Sums
+   the inputs --}
+MainPrgm sum; Var
+<!- bounds -!>
+let i,n,s: Int; <!- all zero -!>
+BeginPg {
+  for i from 1 to n step 1 { <!- each one -!>
+  s:=s+i;
+  <!- end of body -!>
+  }
+  if (s > 10) then { output(s); } <!- big -!> else { }
+} EndPg; <!- done -!>
+";
+        let expected = "{-- Sums
+   the inputs --}
+MainPrgm sum;
+Var
+<!- bounds -!>
+let i: Int;
+let n: Int;
+let s: Int; <!- all zero -!>
+BeginPg
+{
+    for i from 1 to n step 1 { <!- each one -!>
+        s := s + i;
+        <!- end of body -!>
+    }
+    if (s > 10) then {
+        output(s);
+    } <!- big -!> else {
+    }
+}
+EndPg; <!- done -!>
+";
+        let options = FormatOptions {
+            one_declaration_per_line: true,
+        };
+        let formatted = format_code(source.to_string(), options.clone()).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_code(formatted.clone(), options).unwrap(), formatted);
+
+        let source = "MainPrgm x; Var BeginPg { x := ; } EndPg;";
+        assert!(format_code(source.to_string(), FormatOptions::default()).is_err());
+    }
 }
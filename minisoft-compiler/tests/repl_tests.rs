@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod repl_tests {
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::parser::ast::{DeclarationKind, StatementKind};
+    use minisoft_compiler::parser::parser_core::{parse_declaration, parse_statement};
+    use minisoft_compiler::repl::{FragmentKind, Repl, ReplError};
+    use minisoft_compiler::runtime::{BufferedInput, BufferedOutput, Value};
+
+    /// Compiles and executes one fragment, returning what it printed
+    fn eval(repl: &mut Repl, source: &str, inputs: &[&str]) -> Vec<String> {
+        let fragment = match repl.compile(source) {
+            Ok(fragment) => fragment,
+            Err(error) => panic!("Rejected '{}': {:?}", source, error),
+        };
+        let mut output = BufferedOutput::new();
+        repl.execute(
+            &fragment,
+            &mut BufferedInput::new(inputs.iter().copied()),
+            &mut output,
+        )
+        .expect("Runtime error");
+        output.into_lines()
+    }
+
+    #[test]
+    fn test_single_declaration_and_statement() {
+        let source = "let a, b: [Float; 4];";
+        let (tokens, _) = tokenize(source);
+        let declaration = parse_declaration(tokens, source).expect("Parse error");
+        assert_eq!(
+            declaration.node,
+            DeclarationKind::Array(
+                vec!["a".to_string(), "b".to_string()],
+                minisoft_compiler::parser::ast::Type::Float,
+                4
+            )
+        );
+
+        let source = "do { x := x + 1; } while (x < 3);";
+        let (tokens, _) = tokenize(source);
+        let statement = parse_statement(tokens, source).expect("Parse error");
+        assert!(matches!(statement.node, StatementKind::DoWhile(..)));
+
+        let (tokens, _) = tokenize("x := 1");
+        assert!(parse_statement(tokens, "x := 1").is_err());
+    }
+
+    #[test]
+    fn test_state_carries_over() {
+        let mut repl = Repl::new();
+        assert!(eval(&mut repl, "@define Const Step: Int = 2;", &[]).is_empty());
+        eval(&mut repl, "let total, n: Int = 0;", &[]);
+        eval(&mut repl, "input(n);", &["5"]);
+        eval(
+            &mut repl,
+            "for n from n to 9 step Step {\n    total := total + n;\n}",
+            &[],
+        );
+        assert_eq!(
+            eval(&mut repl, "output(\"total \", total);", &[]),
+            vec!["total 21"]
+        );
+        assert_eq!(repl.memory().variables.get("n"), Some(&Value::Int(11)));
+    }
+
+    #[test]
+    fn test_quadruples_are_numbered_across_fragments() {
+        let mut repl = Repl::new();
+        let fragment = repl.compile("let x: Int = 1;").unwrap();
+        assert!(matches!(fragment.kind, FragmentKind::Declaration(_)));
+        assert_eq!(fragment.first_index, 0);
+        assert_eq!(fragment.quadruples.quadruples.len(), 1);
+        repl.execute(
+            &fragment,
+            &mut BufferedInput::new(Vec::<String>::new()),
+            &mut BufferedOutput::new(),
+        )
+        .unwrap();
+
+        let fragment = repl.compile("x := x * 3 + 1;").unwrap();
+        assert_eq!(fragment.first_index, 1);
+        assert_eq!(
+            repl.quadruples().quadruples.len(),
+            1 + fragment.quadruples.quadruples.len()
+        );
+    }
+
+    #[test]
+    fn test_rejected_fragments_change_nothing() {
+        let mut repl = Repl::new();
+        eval(&mut repl, "let x: Int;", &[]);
+
+        assert!(matches!(
+            repl.compile("x := 1.5;"),
+            Err(ReplError::Semantic(_))
+        ));
+        assert!(matches!(repl.compile("x := ;"), Err(ReplError::Syntax(_))));
+        assert!(matches!(
+            repl.compile("x := 1 $ 2;"),
+            Err(ReplError::Lexical(_))
+        ));
+        // A declaration with an error doesn't declare its names
+        assert!(matches!(
+            repl.compile("let y: Int = 2.5;"),
+            Err(ReplError::Semantic(_))
+        ));
+        assert!(!repl.symbol_table().contains("y"));
+        assert!(matches!(
+            repl.compile("let x: Float;"),
+            Err(ReplError::Semantic(_))
+        ));
+        assert!(repl.quadruples().quadruples.is_empty());
+
+        eval(&mut repl, "x := 4;", &[]);
+        assert_eq!(eval(&mut repl, "output(x);", &[]), vec!["4"]);
+    }
+}
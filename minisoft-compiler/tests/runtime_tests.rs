@@ -0,0 +1,795 @@
+#[cfg(test)]
+mod runtime_tests {
+    use minisoft_compiler::codegen::generator::CodeGenerator;
+    use minisoft_compiler::codegen::{validate, validate_branches};
+    use minisoft_compiler::lexer::lexer_core::tokenize;
+    use minisoft_compiler::parser::parser_core::parse;
+    use minisoft_compiler::runtime::{
+        Debugger, ExecutionResult, RuntimeError, StopReason, Value, execute,
+    };
+    use minisoft_compiler::semantics::analyzer_core::SemanticAnalyzer;
+
+    /// Helper function to compile a valid program and execute it with the given inputs
+    fn run_test(source: &str, inputs: &[&str]) -> ExecutionResult {
+        let (tokens, _) = tokenize(source);
+        let program = match parse(tokens, source) {
+            Ok(program) => program,
+            Err(e) => panic!("Parse error: {}", e),
+        };
+
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        assert!(
+            analyzer.get_errors().is_empty(),
+            "Expected no semantic errors, but found: {:?}",
+            analyzer.get_errors()
+        );
+
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+        assert!(validate(&quadruples).is_empty(), "Generated quadruples are malformed");
+        assert!(validate_branches(&quadruples, &program).is_empty());
+
+        execute(
+            &quadruples,
+            analyzer.get_symbol_table(),
+            &source.to_string(),
+            inputs.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    /// Helper function to compile a valid program and open a debugging session on it
+    fn debug_test(source: &str, inputs: &[&str]) -> Debugger {
+        let (tokens, _) = tokenize(source);
+        let program = parse(tokens, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+        assert!(validate(&quadruples).is_empty(), "Generated quadruples are malformed");
+        assert!(validate_branches(&quadruples, &program).is_empty());
+
+        Debugger::new(
+            &quadruples,
+            analyzer.get_symbol_table(),
+            &source.to_string(),
+            inputs.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_arithmetic_and_output() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            let z: Float;
+            BeginPg
+            {
+                x := 7;
+                y := x * 3 - 1;
+                z := 1.5 * 2.0;
+                output("y = ", y);
+                output(x / 2, z);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["y = 20", "33"]);
+        assert_eq!(result.memory.variables.get("y"), Some(&Value::Int(20)));
+        assert_eq!(result.memory.variables.get("z"), Some(&Value::Float(3.0)));
+    }
+
+    #[test]
+    fn test_declaration_initializers_and_constants() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b: Int = 4;
+            let v: [Int; 3] = {1, 2, 3};
+            @define Const Limit: Int = 10;
+            BeginPg
+            {
+                output(a + b + v[2] + Limit);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["21"]);
+        assert_eq!(
+            result.memory.arrays.get("v"),
+            Some(&vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+        );
+    }
+
+    #[test]
+    fn test_do_while_loop_and_arrays() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, sum: Int;
+            let a: [Int; 5];
+            BeginPg
+            {
+                i := 0;
+                sum := 0;
+                do {
+                    a[i] := i * i;
+                    sum := sum + a[i];
+                    i := i + 1;
+                } while (i < 5);
+                output(sum);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["30"]);
+        assert_eq!(result.memory.arrays.get("a").unwrap()[4], Value::Int(16));
+    }
+
+    #[test]
+    fn test_while_loop_tests_before_body() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n, steps: Int;
+            BeginPg
+            {
+                input(n);
+                steps := 0;
+                while (n > 1) {
+                    if (n / 2 * 2 == n) then {
+                        n := n / 2;
+                    } else {
+                        n := 3 * n + 1;
+                    }
+                    steps := steps + 1;
+                }
+                output(steps);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &["6"]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["8"]);
+
+        // A false condition skips the body entirely
+        let result = run_test(source, &["1"]);
+        assert_eq!(result.output, vec!["0"]);
+    }
+
+    #[test]
+    fn test_subprogram_calls() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let total: Int;
+            let data: [Int; 3] = {4, 5, 6};
+            Function Fact(n: Int): Int
+            Var
+            {
+                if (n <= 1) then {
+                    return 1;
+                }
+                return n * Fact(n - 1);
+            }
+            Function Sum(t: [Int; 3]): Int
+            Var
+            let i, s: Int;
+            {
+                s := 0;
+                for i from 0 to 2 step 1 {
+                    s := s + t[i];
+                }
+                t[0] := 100;
+                return s;
+            }
+            Procedure Add(k: Int)
+            Var
+            {
+                total := total + k;
+            }
+            BeginPg
+            {
+                total := 0;
+                Add(Fact(5));
+                Add(Sum(data));
+                output(total, " ", data[0], " ", Fact(7));
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        // The array is passed as a copy, the store in Sum doesn't reach data
+        assert_eq!(result.output, vec!["135 4 5040"]);
+    }
+
+    #[test]
+    fn test_block_declarations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, i: Int;
+            Function Sum(n: Int): Int
+            Var
+            let s: Int;
+            {
+                s := 0;
+                for i from 1 to n step 1 {
+                    let square: Int = i * i;
+                    s := s + square;
+                }
+                if (n > 2) then {
+                    let s: Int = Sum(n - 1);
+                    output(s);
+                }
+                return s;
+            }
+            BeginPg
+            {
+                x := 1;
+                {
+                    let x: Int = x + 10;
+                    let t: [Int; 3] = {7, 8, 9};
+                    output(x, " ", t[2]);
+                }
+                for i from 0 to 1 step 1 {
+                    let k: Int = x;
+                    k := k + i;
+                    output(k);
+                }
+                i := Sum(3);
+                output(x, " ", i);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        // Each iteration runs the declaration again and the recursive call keeps its own block
+        assert_eq!(result.output, vec!["11 9", "1", "2", "5", "1 14"]);
+        assert_eq!(result.memory.arrays.get("1.t").unwrap()[0], Value::Int(7));
+    }
+
+    #[test]
+    fn test_string_operations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let name, greeting: String;
+            let known: [String; 2] = {"ada", "bob"};
+            let n: Int;
+            Function Shout(s: String): String
+            Var
+            {
+                return s + "!";
+            }
+            BeginPg
+            {
+                input(name);
+                greeting := "Hello, " + name;
+                n := len(greeting);
+                output(Shout(greeting), " ", n);
+                if (name == known[1]) then {
+                    output("again, " + known[1]);
+                }
+                if (name != known[0]) then {
+                    output(len(""));
+                }
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &["bob"]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["Hello, bob! 10", "again, bob", "0"]);
+        assert_eq!(
+            result.memory.variables.get("greeting"),
+            Some(&Value::String("Hello, bob".to_string()))
+        );
+
+        // Numbers are read as text into a string
+        let result = run_test(source, &["42"]);
+        assert_eq!(result.output, vec!["Hello, 42! 9", "0"]);
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let names: [String; 3];
+            let i: Int;
+            BeginPg
+            {
+                for i from 0 to 2 step 1 {
+                    input(names[i]);
+                }
+                output(names[1] + ", " + names[0], " ", len(names[2]));
+            }
+            EndPg;
+        "#;
+        let result = run_test(source, &["hello", "world", "3.5"]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["world, hello 3"]);
+        assert_eq!(
+            result.memory.arrays.get("names").unwrap()[2],
+            Value::String("3.5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_runaway_recursion() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Function Down(n: Int): Int
+            Var
+            {
+                return Down(n - 1);
+            }
+            BeginPg
+            {
+                x := Down(3);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(
+            matches!(
+                result.error,
+                Some(RuntimeError::CallDepthExceeded {
+                    depth: 1000,
+                    line: 8,
+                    ..
+                })
+            ),
+            "{:?}",
+            result.error
+        );
+    }
+
+    #[test]
+    fn test_input_into_variables_and_elements() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n: Int;
+            let f: Float;
+            let a: [Int; 2];
+            BeginPg
+            {
+                input(n);
+                input(f);
+                input(a[1]);
+                if (n > a[1]) then {
+                    output("bigger");
+                } else {
+                    output("smaller");
+                }
+                output(f);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &["3", "2.5", "8"]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.memory.variables.get("n"), Some(&Value::Int(3)));
+        assert_eq!(result.memory.arrays.get("a").unwrap()[1], Value::Int(8));
+        assert_eq!(result.output.last(), Some(&"2.5".to_string()));
+
+        // An element is read as the type of its array
+        let result = run_test(source, &["3", "2.5", "3.5"]);
+        assert!(
+            matches!(
+                &result.error,
+                Some(RuntimeError::InvalidInput { value, expected, line: 11, .. })
+                    if value == "3.5" && expected == "Int"
+            ),
+            "{:?}",
+            result.error
+        );
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let g: [Float; 2];
+            let k: Int;
+            BeginPg
+            {
+                k := 1;
+                input(g[k]);
+                output(g[k] / 2);
+            }
+            EndPg;
+        "#;
+        let result = run_test(source, &["3"]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.memory.arrays.get("g").unwrap()[1], Value::Float(3.0));
+        assert_eq!(result.output, vec!["1.5"]);
+    }
+
+    #[test]
+    fn test_missing_input_keeps_partial_output() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n: Int;
+            BeginPg
+            {
+                output("before");
+                input(n);
+                output("after");
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_some(), "Expected an input error");
+        assert_eq!(result.output, vec!["before"]);
+    }
+
+    #[test]
+    fn test_dynamic_division_by_zero() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            BeginPg
+            {
+                input(y);
+                x := 10 / y;
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &["0"]);
+        assert_eq!(
+            result.error,
+            Some(RuntimeError::DivisionByZero { line: 8, column: 22 })
+        );
+    }
+
+    #[test]
+    fn test_dynamic_index_out_of_bounds() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            let a: [Int; 3];
+            BeginPg
+            {
+                input(i);
+                a[i] := 1;
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &["3"]);
+        assert!(
+            matches!(
+                result.error,
+                Some(RuntimeError::ArrayIndexOutOfBounds { index: 3, size: 3, line: 9, .. })
+            ),
+            "Expected an out of bounds error, but found: {:?}",
+            result.error
+        );
+    }
+
+    #[test]
+    fn test_uninitialized_read() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            let a: [Int; 2];
+            BeginPg
+            {
+                y := x + 1;
+                output(a[0]);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(
+            matches!(
+                &result.error,
+                Some(RuntimeError::UninitializedRead { name, line: 8, .. }) if name == "x"
+            ),
+            "Expected an uninitialized read of 'x', but found: {:?}",
+            result.error
+        );
+
+        // Locals are reported by the name they have in the source
+        let source = r#"
+            MainPrgm test;
+            Var
+            let y: Int;
+            Procedure P(n: Int)
+            Var
+            {
+                if (n > 0) then {
+                    let w: [Int; 2];
+                    output(w[n]);
+                }
+            }
+            BeginPg
+            {
+                {
+                    let z: Int;
+                    y := z;
+                }
+            }
+            EndPg;
+        "#;
+        let result = run_test(source, &[]);
+        assert!(
+            matches!(&result.error, Some(RuntimeError::UninitializedRead { name, .. }) if name == "z"),
+            "{:?}",
+            result.error
+        );
+        let source = source.replace("y := z;", "y := 1;\n P(1);");
+        let result = run_test(&source, &[]);
+        let error = result.error.expect("Expected an uninitialized read of 'w[1]'");
+        assert!(
+            matches!(&error, RuntimeError::UninitializedRead { name, .. } if name == "w[1]"),
+            "{:?}",
+            error
+        );
+        assert!(!error.to_string().contains("P."), "{}", error);
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                input(x);
+                x := x * 2;
+            }
+            EndPg;
+        "#;
+
+        let ok = run_test(source, &["16000"]);
+        assert!(ok.error.is_none(), "Unexpected error: {:?}", ok.error);
+        assert_eq!(ok.memory.variables.get("x"), Some(&Value::Int(32000)));
+
+        let overflow = run_test(source, &["20000"]);
+        assert!(
+            matches!(
+                overflow.error,
+                Some(RuntimeError::IntegerOverflow { value: 40000, line: 8, .. })
+            ),
+            "Expected an overflow error, but found: {:?}",
+            overflow.error
+        );
+    }
+
+    #[test]
+    fn test_for_loop_inclusive_bounds() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, count, sum: Int;
+            BeginPg
+            {
+                count := 0;
+                sum := 0;
+                for i from 1 to 5 step 1 {
+                    count := count + 1;
+                    sum := sum + i;
+                }
+                output(count, " ", sum, " ", i);
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["5 15 6"]);
+    }
+
+    #[test]
+    fn test_for_loop_negative_step() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                for i from 6 to 0 step (-2) {
+                    output(i);
+                }
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &[]);
+        assert!(result.error.is_none(), "Unexpected error: {:?}", result.error);
+        assert_eq!(result.output, vec!["6", "4", "2", "0"]);
+    }
+
+    #[test]
+    fn test_for_loop_bounds_evaluated_once() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n, s, count: Int;
+            BeginPg
+            {
+                input(n);
+                input(s);
+                count := 0;
+                for i from 0 to n step s {
+                    n := n + 10;
+                    s := s * 2;
+                    count := count + 1;
+                }
+                output(count);
+            }
+            EndPg;
+        "#;
+
+        let up = run_test(source, &["3", "1"]);
+        assert!(up.error.is_none(), "Unexpected error: {:?}", up.error);
+        assert_eq!(up.output, vec!["4"]);
+
+        // A step only known at runtime still picks the direction from its sign
+        let down = run_test(source, &["-4", "-2"]);
+        assert!(down.error.is_none(), "Unexpected error: {:?}", down.error);
+        assert_eq!(down.output, vec!["3"]);
+    }
+
+    #[test]
+    fn test_for_loop_reaches_int_bounds() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, c, n, s: Int;
+            BeginPg
+            {
+                input(n);
+                input(s);
+                c := 0;
+                for i from 32765 to 32767 step 1 {
+                    c := c + 1;
+                }
+                for i from (-32766) to (-32768) step (-1) {
+                    c := c + 1;
+                }
+                for i from 32760 to 32767 step 5 {
+                    c := c + 1;
+                }
+                for i from 0 to n step s {
+                    c := c + 1;
+                }
+                output(c, " ", i);
+            }
+            EndPg;
+        "#;
+
+        // The last value stays in the iterator, stepping past it would overflow
+        let up = run_test(source, &["32767", "16384"]);
+        assert!(up.error.is_none(), "Unexpected error: {:?}", up.error);
+        assert_eq!(up.output, vec!["10 16384"]);
+
+        let down = run_test(source, &["-32768", "-16384"]);
+        assert!(down.error.is_none(), "Unexpected error: {:?}", down.error);
+        assert_eq!(down.output, vec!["11 -32768"]);
+    }
+
+    #[test]
+    fn test_for_loop_dynamic_zero_step() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, s: Int;
+            BeginPg
+            {
+                input(s);
+                for i from 0 to 10 step s {
+                    output(i);
+                }
+            }
+            EndPg;
+        "#;
+
+        let result = run_test(source, &["0"]);
+        assert!(
+            matches!(result.error, Some(RuntimeError::ZeroLoopStep { line: 8, column: 41 })),
+            "Expected a zero step error, but found: {:?}",
+            result.error
+        );
+        assert!(result.output.is_empty());
+    }
+
+    const DEBUG_SOURCE: &str = r#"MainPrgm test;
+Var
+let i, sum: Int;
+BeginPg
+{
+    i := 0;
+    sum := 0;
+    do {
+        sum := sum + i;
+        i := i + 1;
+    } while (i < 3);
+    output(sum);
+}
+EndPg;"#;
+
+    #[test]
+    fn test_debugger_breakpoints_stop_every_iteration() {
+        let mut debugger = debug_test(DEBUG_SOURCE, &[]);
+        debugger.set_breakpoint(9);
+
+        for expected_i in 0..3 {
+            assert_eq!(debugger.continue_execution(), StopReason::Breakpoint(9));
+            let snapshot = debugger.inspect();
+            assert_eq!(snapshot.line, Some(9));
+            let i = snapshot.variables.iter().find(|(name, _)| name == "i").unwrap();
+            assert_eq!(i.1, Value::Int(expected_i));
+        }
+
+        assert_eq!(debugger.continue_execution(), StopReason::Halted);
+        assert!(debugger.is_finished());
+        assert_eq!(debugger.output().clone().into_lines(), vec!["3"]);
+    }
+
+    #[test]
+    fn test_debugger_step_statement() {
+        let mut debugger = debug_test(DEBUG_SOURCE, &[]);
+        assert_eq!(debugger.current_line(), Some(6));
+
+        assert_eq!(debugger.step_statement(), StopReason::Step);
+        assert_eq!(debugger.current_line(), Some(7));
+
+        // The do-while statement starts with its loop label, then enters the body
+        assert_eq!(debugger.step_statement(), StopReason::Step);
+        assert_eq!(debugger.current_line(), Some(8));
+        assert_eq!(debugger.step_statement(), StopReason::Step);
+        assert_eq!(debugger.current_line(), Some(9));
+
+        let snapshot = debugger.inspect();
+        assert!(snapshot.variables.contains(&("sum".to_string(), Value::Int(0))));
+    }
+
+    #[test]
+    fn test_debugger_step_quadruple_and_errors() {
+        let source = r#"MainPrgm test;
+Var
+let x, y: Int;
+BeginPg
+{
+    input(y);
+    x := 1 / y;
+}
+EndPg;"#;
+
+        let mut debugger = debug_test(source, &["0"]);
+        assert_eq!(debugger.step_quadruple(), StopReason::Step);
+        assert_eq!(debugger.inspect().pc, 1);
+
+        let reason = debugger.continue_execution();
+        assert!(
+            matches!(reason, StopReason::Error(RuntimeError::DivisionByZero { line: 7, .. })),
+            "Expected a division by zero, but found: {:?}",
+            reason
+        );
+        assert!(debugger.is_finished());
+        assert_eq!(debugger.step_statement(), reason);
+    }
+}
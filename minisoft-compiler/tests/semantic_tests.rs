@@ -27,6 +27,24 @@ mod semantic_tests {
             .collect()
     }
 
+    /// Helper function to analyze a program and return its warnings as strings
+    fn warnings_test(source: &str) -> Vec<String> {
+        let (tokens, _) = tokenize(source);
+        let program = parse(tokens, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        analyzer.analyze(&program);
+        assert!(
+            analyzer.get_errors().is_empty(),
+            "Expected no errors, but found: {:?}",
+            analyzer.get_errors()
+        );
+        analyzer
+            .get_warnings()
+            .iter()
+            .map(|w| format!("{:?}", w))
+            .collect()
+    }
+
     /// Helper to check if errors match expected patterns (now operating on error messages)
     fn contains_error_of_type(errors: &[String], error_type: &str) -> bool {
         errors
@@ -446,6 +464,394 @@ mod semantic_tests {
         // This should cause a type error for the condition
     }
 
+    #[test]
+    fn test_subprogram_calls() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            let a: [Int; 3];
+            let f: [Float; 3];
+            Function Sum(t: [Int; 3], n: Int): Int
+            Var
+            {
+                return t[0] + n;
+            }
+            Procedure Show(v: Float)
+            Var
+            {
+                output(v);
+            }
+            BeginPg
+            {
+                x := Sum(a);
+                x := Sum(f, 1);
+                x := Sum(x, 1.5);
+                x := Show(1.0);
+                Show(1.0, 2.0);
+                Missing(x);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 7, "{:?}", errors);
+        assert!(errors[0].contains("ArgumentCountMismatch { name: \"Sum\", expected: 2, found: 1"));
+        assert!(
+            errors[1].contains("expected: \"[Int; 3]\", found: \"[Float; 3]\"")
+                && errors[1].contains("argument 1 of 'Sum'")
+        );
+        assert!(errors[2].contains("found: \"Int\"") && errors[2].contains("argument 1 of 'Sum'"));
+        assert!(errors[3].contains("found: \"Float\"") && errors[3].contains("argument 2 of 'Sum'"));
+        assert!(errors[4].contains("ProcedureValueUsed { name: \"Show\""));
+        assert!(errors[5].contains("ArgumentCountMismatch { name: \"Show\", expected: 1, found: 2"));
+        assert!(errors[6].contains("UndefinedSubprogram { name: \"Missing\""));
+    }
+
+    #[test]
+    fn test_subprogram_returns_and_scopes() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Function Sign(n: Int): Int
+            Var
+            {
+                if (n > 0) then {
+                    return 1;
+                }
+            }
+            Function Half(n: Int): Int
+            Var
+            let x: Int;
+            {
+                return n / 2.0;
+            }
+            Procedure Stop()
+            Var
+            {
+                return 0;
+            }
+            BeginPg
+            {
+                return;
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[0].contains("MissingReturn { name: \"Sign\", line: 5"));
+        assert!(errors[1].contains("return value of 'Half'"));
+        assert!(errors[2].contains("procedure 'Stop' cannot return a value"));
+        assert!(errors[3].contains("return outside of a function or procedure"));
+
+        // Both branches return, the body is recursive and locals don't leak to the program
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Function Fib(n: Int): Int
+            Var
+            let a: Int;
+            {
+                if (n < 2) then {
+                    return n;
+                } else {
+                    a := Fib(n - 1);
+                    return a + Fib(n - 2);
+                }
+                x := 1;
+            }
+            BeginPg
+            {
+                x := Fib(10);
+            }
+            EndPg;
+        "#;
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 0, "{:?}", warnings);
+
+        let source = source.replace("x := Fib(10);", "a := Fib(10);");
+        let errors = analyze_test(&source);
+        assert!(errors[0].contains("UndeclaredIdentifier { name: \"a\""), "{:?}", errors);
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Procedure P(n: Int)
+            Var
+            {
+                return;
+                output(n);
+            }
+            BeginPg
+            {
+                P(1);
+            }
+            EndPg;
+        "#;
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("statement after a return") && warnings[0].contains("line: 9"));
+    }
+
+    #[test]
+    fn test_subprograms_named_like_globals() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            let arr: [Int; 3];
+            Function x(a: Int): Int
+            Var
+            {
+                return a;
+            }
+            Procedure arr(n: Int)
+            Var
+            {
+                return;
+            }
+            BeginPg
+            {
+                x := 1;
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("DuplicateDeclaration { name: \"x\", line: 6"));
+        assert!(errors[1].contains("DuplicateDeclaration { name: \"arr\", line: 11"));
+
+        // The second body isn't checked against the first signature
+        let source = r#"
+            MainPrgm test;
+            Var
+            let y: Int;
+            Function f(a: Int): Int
+            Var
+            {
+                return a;
+            }
+            Procedure f()
+            Var
+            {
+                return;
+            }
+            BeginPg
+            {
+                y := f(1);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("DuplicateDeclaration { name: \"f\", line: 10"));
+    }
+
+    #[test]
+    fn test_block_scopes() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Procedure P(x: Int)
+            Var
+            {
+                output(x);
+            }
+            BeginPg
+            {
+                x := 1;
+                {
+                    let x: Float = x + 0.5;
+                    let y: Int = 2;
+                    {
+                        let y: Int = y * 2;
+                        output(x, y);
+                    }
+                    output(y);
+                }
+                P(x);
+            }
+            EndPg;
+        "#;
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings[0].contains(
+            "ShadowedDeclaration { name: \"x\", line: 5, column: 25, original_line: 4"
+        ));
+        assert!(warnings[1].contains("name: \"x\", line: 14") && warnings[1].contains("original_line: 4"));
+        assert!(warnings[2].contains("name: \"y\", line: 17") && warnings[2].contains("original_line: 15"));
+
+        // Names declared in a block are gone after it and can't be declared twice in it
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                {
+                    let y: Int = 1;
+                    let y: Float;
+                    x := y;
+                }
+                x := y;
+                if (x > 0) then {
+                    let z: Int = 1;
+                }
+                output(z);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].contains("DuplicateDeclaration { name: \"y\", line: 9"));
+        assert!(errors[1].contains("UndeclaredIdentifier { name: \"y\", line: 12"));
+        assert!(errors[2].contains("UndeclaredIdentifier { name: \"z\", line: 16"));
+    }
+
+    #[test]
+    fn test_string_operations() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let s, t: String;
+            let n: Int;
+            @define Const Sep: String = ", ";
+            Function Twice(s: String): String
+            Var
+            {
+                return s + s;
+            }
+            BeginPg
+            {
+                input(s);
+                t := Twice(s) + Sep;
+                n := len(t) + len("abc");
+                if (s == t) then {
+                    output(t);
+                }
+                if (Sep != "") then {
+                    output(n);
+                }
+            }
+            EndPg;
+        "#;
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("ShadowedDeclaration { name: \"s\""));
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let s: String;
+            let n: Int;
+            @define Const C: String = 3;
+            BeginPg
+            {
+                s := 1;
+                n := s;
+                s := s + 1;
+                n := s - "a";
+                n := len(n);
+                if (s < "b") then {
+                    n := 1;
+                }
+                if (s) then {
+                    n := 2;
+                }
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 8, "{:?}", errors);
+        assert!(errors[0].contains("expected: \"String\", found: \"Int\"") && errors[0].contains("\"constant\""));
+        assert!(errors[1].contains("expected: \"String\", found: \"Int\"") && errors[1].contains("\"assignment\""));
+        assert!(errors[2].contains("expected: \"Int\", found: \"String\"") && errors[2].contains("\"assignment\""));
+        assert!(errors[3].contains("found: \"Int\"") && errors[3].contains("\"concatenation\""));
+        assert!(errors[4].contains("found: \"String\"") && errors[4].contains("\"arithmetic\""));
+        assert!(errors[5].contains("expected: \"String\"") && errors[5].contains("\"len\""));
+        assert!(errors[6].contains("found: \"String\"") && errors[6].contains("\"comparison\""));
+        assert!(errors[7].contains("found: \"String\"") && errors[7].contains("\"if condition\""));
+
+        // A call of len always means the builtin, so no subprogram can take the name
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n: Int;
+            Function len(x: Int): Int
+            Var
+            {
+                return x;
+            }
+            BeginPg
+            {
+                n := 1;
+                output(len(5));
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(errors[0].contains("BuiltinRedefined { name: \"len\", line: 5"), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_while_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Float;
+            BeginPg
+            {
+                while (x) {
+                    x := x - 1.0;
+                }
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("TypeMismatch") && errors[0].contains("while condition"));
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n, s, i: Int;
+            let a: [Int; 5];
+            BeginPg
+            {
+                input(n);
+                s := 0;
+                i := 0;
+                while (i < 5) {
+                    a[i] := i;
+                    i := i + 1;
+                }
+                while (0) {
+                    s := 1;
+                }
+                while (n > 0) {
+                    s := s + 1;
+                }
+                while (1) {
+                    s := s + 1;
+                }
+                output(s);
+            }
+            EndPg;
+        "#;
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 4, "{:?}", warnings);
+        assert!(warnings[0].contains("while body") && warnings[0].contains("line: 16"));
+        assert!(warnings[1].contains("InfiniteLoop { constant: false, line: 18"));
+        assert!(warnings[2].contains("InfiniteLoop { constant: true, line: 21"));
+        assert!(
+            warnings[3].contains("statement after an infinite loop")
+                && warnings[3].contains("line: 24")
+        );
+    }
+
     #[test]
     fn test_empty_source() {
         let source = r#"
@@ -597,4 +1003,336 @@ mod semantic_tests {
         assert!(!errors.is_empty());
         assert!(contains_error_of_type(&errors, "TypeMismatch"));
     }
+
+    #[test]
+    fn test_for_loop_zero_step_invalid() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i : Int;
+            @define Const Zero: Int = 0;
+            BeginPg { for i from 1 to 5 step Zero { } } EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(!errors.is_empty());
+        assert!(contains_error_of_type(&errors, "ZeroLoopStep"));
+    }
+
+    #[test]
+    fn test_not_of_runtime_condition_valid() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            BeginPg
+            {
+                input(x);
+                if (!(x < 3)) then { y := 1; }
+                if (!x) then { y := 2; }
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(errors.is_empty(), "Expected no errors, but found: {:?}", errors);
+    }
+
+    #[test]
+    fn test_read_before_initialization_warning() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y: Int;
+            BeginPg
+            {
+                y := x + 1;
+                output(y);
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("UseBeforeInitialization"));
+        assert!(warnings[0].contains("name: \"x\""));
+        assert!(warnings[0].contains("line: 7"));
+        assert!(warnings[0].contains("column: 22"));
+    }
+
+    #[test]
+    fn test_initialization_follows_branches() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let c, both, one: Int;
+            BeginPg
+            {
+                input(c);
+                if (c > 0) then {
+                    both := 1;
+                    one := 1;
+                } else {
+                    both := 2;
+                }
+                output(both);
+                output(one);
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("name: \"one\""));
+    }
+
+    #[test]
+    fn test_initialization_in_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n, s, first, maybe: Int;
+            BeginPg
+            {
+                input(n);
+                do {
+                    first := 1;
+                    n := n + 1;
+                } while (n < 0);
+                output(first);
+                for i from 0 to n step 1 {
+                    maybe := i;
+                }
+                output(maybe);
+                s := 0;
+                do {
+                    s := s + 1;
+                } while (s < n);
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("name: \"maybe\""));
+    }
+
+    #[test]
+    fn test_array_element_initialization() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            let a: [Int; 3];
+            let b: [Int; 3];
+            let c: [Int; 2] = {1, 2};
+            BeginPg
+            {
+                a[0] := 1;
+                output(a[0]);
+                output(a[1]);
+                output(a[i]);
+                for i from 0 to 2 step 1 {
+                    b[i] := i;
+                }
+                output(b[2]);
+                output(c[1]);
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings[0].contains("index: Some(\"1\")"));
+        // The index itself is read before it's written, then the element it selects
+        assert!(warnings[1].contains("name: \"i\""));
+        assert!(warnings[2].contains("index: Some(\"i\")"));
+    }
+
+    #[test]
+    fn test_never_executed_branches() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, x: Int;
+            @define Const Debug: Int = 0;
+            BeginPg
+            {
+                input(x);
+                if (Debug == 1) then {
+                    output(x);
+                }
+                if (1 < 2) then {
+                    output(x);
+                } else {
+                    x := 0;
+                }
+                for i from 5 to 1 step 1 {
+                    output(i);
+                }
+                if (x > 0) then {
+                    output(x);
+                }
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings[0].contains("then branch") && warnings[0].contains("line: 10"));
+        assert!(warnings[1].contains("else branch") && warnings[1].contains("line: 15"));
+        assert!(warnings[2].contains("loop body") && warnings[2].contains("line: 18"));
+    }
+
+    #[test]
+    fn test_infinite_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let n, s: Int;
+            BeginPg
+            {
+                input(n);
+                s := 0;
+                do {
+                    s := s + 1;
+                } while (n > 0);
+                do {
+                    s := s + 1;
+                    n := n - 1;
+                } while (n > 0);
+                do {
+                    s := s + 1;
+                } while (1);
+                output(s);
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        assert!(warnings[0].contains("InfiniteLoop { constant: false, line: 11"));
+        assert!(warnings[1].contains("InfiniteLoop { constant: true, line: 18"));
+        assert!(
+            warnings[2].contains("statement after an infinite loop")
+                && warnings[2].contains("line: 19")
+        );
+
+        // A body returning on every path leaves the loop before it repeats
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            Function F(n: Int): Int
+            Var
+            {
+                while (n > 0) {
+                    return 1;
+                }
+                while (n < 0) {
+                    if (n < (-5)) then {
+                        return 2;
+                    } else {
+                        return 3;
+                    }
+                }
+                while (n == 0) {
+                    if (n < (-5)) then {
+                        return 4;
+                    }
+                }
+                return 0;
+            }
+            BeginPg
+            {
+                x := F(1);
+            }
+            EndPg;
+        "#;
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("InfiniteLoop { constant: false, line: 18"));
+    }
+
+    #[test]
+    fn test_loop_index_ranges() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: [Int; 10];
+            let i, j: Int;
+            BeginPg
+            {
+                for i from 0 to 10 step 1 {
+                    a[i] := 0;
+                }
+                for i from 0 to 9 step 1 {
+                    a[i] := i;
+                }
+                for i from 9 to 0 step (-1) {
+                    output(a[i]);
+                }
+                j := 0;
+                do {
+                    a[j + 1] := j;
+                    j := j + 1;
+                } while (j < 9);
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("range: \"[0, 10]\""));
+        assert!(warnings[0].contains("definite: false, line: 9"));
+    }
+
+    #[test]
+    fn test_index_ranges_through_branches() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: [Int; 5];
+            let i, k: Int;
+            BeginPg
+            {
+                input(i);
+                if (i >= 0 AND i < 5) then {
+                    a[i] := 1;
+                }
+                a[i] := 2;
+                k := 3;
+                if (i > 0) then {
+                    k := k + 3;
+                }
+                a[k] := 3;
+                a[k - 2] := 4;
+            }
+            EndPg;
+        "#;
+
+        let warnings = warnings_test(source);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("range: \"[3, 6]\"") && warnings[0].contains("line: 17"));
+    }
+
+    #[test]
+    fn test_definite_index_out_of_range() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: [Int; 4];
+            let i: Int;
+            BeginPg
+            {
+                i := 2;
+                a[i * 2] := 1;
+                a[i - 3] := 1;
+            }
+            EndPg;
+        "#;
+
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("range: \"[4, 4]\"") && errors[0].contains("definite: true"));
+        assert!(errors[1].contains("range: \"[-1, -1]\""));
+    }
 }
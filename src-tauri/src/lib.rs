@@ -1,7 +1,17 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-use minisoft_compiler::{run_compiler, SerializableCompilationResult};
+use minisoft_compiler::runtime::{Debugger, StopReason};
+use minisoft_compiler::{
+  debug_state, export_dot, format_code, run_compiler, run_program, start_debugger,
+  CompilerOptions, FormatOptions, SerializableCompilationResult, SerializableDebugState,
+  SerializableDotGraphs, SerializableExecutionResult,
+};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::command;
+use tauri::{command, State};
+
+// Debugging session kept alive between debugger commands
+#[derive(Default)]
+struct DebugSession(Mutex<Option<Debugger>>);
 
 #[command]
 fn greet() -> String {
@@ -11,15 +21,72 @@ fn greet() -> String {
 }
 
 #[command]
-fn compile_minisoft(code: String, verbose: bool) -> Result<SerializableCompilationResult, String> {
-  run_compiler(code, verbose)
+fn compile_minisoft(
+  code: String,
+  verbose: bool,
+  options: Option<CompilerOptions>,
+) -> Result<SerializableCompilationResult, String> {
+  run_compiler(code, verbose, options.unwrap_or_default())
+}
+
+#[command]
+fn export_minisoft_dot(code: String) -> Result<SerializableDotGraphs, String> {
+  export_dot(code)
+}
+
+#[command]
+fn format_minisoft(code: String, options: Option<FormatOptions>) -> Result<String, String> {
+  format_code(code, options.unwrap_or_default())
+}
+
+#[command]
+fn run_minisoft(code: String, inputs: Vec<String>) -> Result<SerializableExecutionResult, String> {
+  run_program(code, inputs)
+}
+
+#[command]
+fn debug_start(
+  session: State<DebugSession>,
+  code: String,
+  inputs: Vec<String>,
+  breakpoints: Vec<usize>,
+) -> Result<SerializableDebugState, String> {
+  let debugger = start_debugger(code, inputs, breakpoints)?;
+  let state = debug_state(&debugger, StopReason::Step);
+  *session.0.lock().map_err(|e| e.to_string())? = Some(debugger);
+  Ok(state)
+}
+
+#[command]
+fn debug_action(
+  session: State<DebugSession>,
+  action: String,
+) -> Result<SerializableDebugState, String> {
+  let mut guard = session.0.lock().map_err(|e| e.to_string())?;
+  let debugger = guard.as_mut().ok_or("No debugging session is running")?;
+  let reason = match action.as_str() {
+    "step_quadruple" => debugger.step_quadruple(),
+    "step_statement" => debugger.step_statement(),
+    "continue" => debugger.continue_execution(),
+    other => return Err(format!("Unknown debugger action '{}'", other)),
+  };
+  Ok(debug_state(debugger, reason))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
     .plugin(tauri_plugin_opener::init())
-    .invoke_handler(tauri::generate_handler![greet, compile_minisoft])
+    .manage(DebugSession::default())
+    .invoke_handler(tauri::generate_handler![
+      greet,
+      compile_minisoft,
+      export_minisoft_dot,
+      format_minisoft,
+      run_minisoft,
+      debug_start,
+      debug_action
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
@@ -56,12 +56,14 @@ export default function Home() {
   
   const { showNotification } = useNotifications(theme);
   
-  const { compileCode } = useCompiler({
+  const { compileCode, formatCode } = useCompiler({
     code,
+    setCode,
     setCompilationResult,
     setIsCompiling,
     setError,
     showNotification,
+    passDiffs: activeTab === "quadruples",
   });
   
   const { handleFileUpload, downloadFile } = useFileOperations({
@@ -114,6 +116,7 @@ export default function Home() {
         code={code}
         setCode={setCode}
         compileCode={compileCode}
+        formatCode={formatCode}
         fontSizeMultiplier={fontSizeMultiplier}
         indentSize={indentSize}
         showMinimap={showMinimap}
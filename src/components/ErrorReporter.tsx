@@ -242,6 +242,15 @@ export default function ErrorReporter({
             suggestion: `Use a different name for this variable, or remove this duplicate declaration`,
           };
           break;
+        case "ShadowedDeclaration":
+          errorDetails = {
+            title: "Shadowed Declaration",
+            message: `"${semanticError.data.name}" hides the declaration at line ${semanticError.data.original_position.line}, column ${semanticError.data.original_position.column}`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `Rename this "${semanticError.data.name}" if the outer one is meant to be used here`,
+          };
+          break;
         case "TypeMismatch":
           errorDetails = {
             title: "Type Mismatch",
@@ -282,6 +291,109 @@ export default function ErrorReporter({
             suggestion: `Use indices from 0 to ${semanticError.data.size - 1}`,
           };
           break;
+        case "InfiniteLoop":
+          errorDetails = {
+            title: "Infinite Loop",
+            message: semanticError.data.constant
+              ? "Loop condition is always true, the loop never terminates"
+              : "Loop condition never changes in the loop body, the loop never terminates once it repeats",
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: semanticError.data.constant
+              ? "Use a condition that eventually becomes false"
+              : "Change one of the variables of the condition in the loop body",
+          };
+          break;
+        case "UnreachableCode":
+          errorDetails = {
+            title: "Unreachable Code",
+            message: `The ${semanticError.data.context} never runs`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: "Remove this code or fix the condition that skips it",
+          };
+          break;
+        case "UseBeforeInitialization": {
+          const target =
+            semanticError.data.index === null
+              ? `Variable "${semanticError.data.name}"`
+              : `Array element "${semanticError.data.name}[${semanticError.data.index}]"`;
+          errorDetails = {
+            title: "Use Before Initialization",
+            message: `${target} may be read before being written`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `Assign a value to "${semanticError.data.name}" on every path before reading it`,
+          };
+          break;
+        }
+        case "IndexRangeOutOfBounds":
+          errorDetails = {
+            title: semanticError.data.definite
+              ? "Array Index Out of Bounds"
+              : "Possible Array Index Out of Bounds",
+            message: semanticError.data.definite
+              ? `Index in ${semanticError.data.range} is always outside array "${semanticError.data.name}" of size ${semanticError.data.size}`
+              : `Index in ${semanticError.data.range} may leave array "${semanticError.data.name}" of size ${semanticError.data.size}`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `Keep the index between 0 and ${semanticError.data.size - 1}`,
+          };
+          break;
+        case "UndefinedSubprogram":
+          errorDetails = {
+            title: "Undefined Subprogram",
+            message: `Undefined function or procedure "${semanticError.data.name}"`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `Declare a function or procedure named "${semanticError.data.name}" before BeginPg`,
+          };
+          break;
+        case "ArgumentCountMismatch":
+          errorDetails = {
+            title: "Wrong Number of Arguments",
+            message: `"${semanticError.data.name}" expects ${semanticError.data.expected} argument(s), found ${semanticError.data.found}`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `Pass exactly ${semanticError.data.expected} argument(s) to "${semanticError.data.name}"`,
+          };
+          break;
+        case "ProcedureValueUsed":
+          errorDetails = {
+            title: "Procedure Used as a Value",
+            message: `Procedure "${semanticError.data.name}" does not return a value`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `Call "${semanticError.data.name}" as a statement, or make it a Function with a return type`,
+          };
+          break;
+        case "MissingReturn":
+          errorDetails = {
+            title: "Missing Return",
+            message: `Function "${semanticError.data.name}" may end without returning a value`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: `End every path through "${semanticError.data.name}" with a return statement`,
+          };
+          break;
+        case "InvalidReturn":
+          errorDetails = {
+            title: "Invalid Return",
+            message: semanticError.data.message,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: "Only return from a function or procedure, with a value in a function only",
+          };
+          break;
+        case "BuiltinRedefined":
+          errorDetails = {
+            title: "Builtin Redefined",
+            message: `"${semanticError.data.name}" is a builtin and cannot name a function or procedure`,
+            line: semanticError.data.position.line,
+            column: semanticError.data.position.column,
+            suggestion: "Give the function or procedure another name",
+          };
+          break;
         default:
           if (semanticError.data?.position) {
             errorDetails = {
@@ -13,6 +13,7 @@ interface MainContentProps {
   code: string;
   setCode: (code: string) => void;
   compileCode: () => void;
+  formatCode: () => void;
   fontSizeMultiplier: number;
   indentSize: number;
   showMinimap: boolean;
@@ -27,6 +28,7 @@ export default function MainContent({
   code,
   setCode,
   compileCode,
+  formatCode,
   fontSizeMultiplier,
   indentSize,
   showMinimap,
@@ -64,6 +66,7 @@ export default function MainContent({
             setCode={setCode}
             theme={theme}
             onCompile={compileCode}
+            onFormat={formatCode}
             fontSizeMultiplier={fontSizeMultiplier}
             indentSize={indentSize}
             showMinimap={showMinimap}
@@ -26,6 +26,7 @@ export default function Editor({
   setCode,
   theme,
   onCompile,
+  onFormat,
   fontSizeMultiplier: initialFontSize,
   indentSize: initialIndent,
   showMinimap: initialMinimap,
@@ -133,6 +134,12 @@ export default function Editor({
         shortcut: "Ctrl+Enter",
         action: onCompile,
       },
+      {
+        id: "format",
+        name: "Format Document",
+        shortcut: "Shift+Alt+F",
+        action: onFormat,
+      },
       {
         id: "toggle-comment",
         name: "Toggle Comment",
@@ -156,6 +163,7 @@ export default function Editor({
       toggleReplacePanel,
       saveFile,
       onCompile,
+      onFormat,
       toggleComment,
       goToLine,
     ],
@@ -339,6 +347,12 @@ export default function Editor({
         handleRedo();
       }
 
+      // Format document
+      if (e.shiftKey && e.altKey && e.key.toLowerCase() === "f") {
+        e.preventDefault();
+        onFormat();
+      }
+
       // Comment/uncomment
       if (e.ctrlKey && e.key === "/") {
         e.preventDefault();
@@ -395,7 +409,7 @@ export default function Editor({
 
     document.addEventListener("keydown", handleKeyDown);
     return () => document.removeEventListener("keydown", handleKeyDown);
-  }, [code, onCompile, selectedText]);
+  }, [code, onCompile, onFormat, selectedText]);
 
   // Enhanced auto-pairing of brackets
   const handleTextareaKeyDown = (
@@ -436,6 +450,7 @@ export default function Editor({
         onSaveClick={saveFile}
         onCompileClick={onCompile}
         onCommandPaletteClick={openCommandPalette}
+        onFormatClick={onFormat}
         onCommentClick={toggleComment}
         onIndentClick={indentSelectedLines}
         onOutdentClick={outdentSelectedLines}
@@ -6,6 +6,7 @@ import {
   Play,
   Command,
   AlignJustify,
+  AlignLeft,
   Indent,
   Outdent,
   Settings,
@@ -19,6 +20,7 @@ interface EditorToolbarProps {
   onSaveClick: () => void;
   onCompileClick: () => void;
   onCommandPaletteClick: () => void;
+  onFormatClick: () => void;
   onCommentClick: () => void;
   onIndentClick: () => void;
   onOutdentClick: () => void;
@@ -33,6 +35,7 @@ const EditorToolbar: React.FC<EditorToolbarProps> = ({
   onSaveClick,
   onCompileClick,
   onCommandPaletteClick,
+  onFormatClick,
   onCommentClick,
   onIndentClick,
   onOutdentClick,
@@ -90,6 +93,13 @@ const EditorToolbar: React.FC<EditorToolbarProps> = ({
         </button>
       </div>
       <div className="flex items-center gap-2">
+        <button
+          onClick={onFormatClick}
+          className={buttonClass}
+          title="Format Document (Shift+Alt+F)"
+        >
+          <AlignLeft size={16} />
+        </button>
         <button
           onClick={onCommentClick}
           className={buttonClass}
@@ -3,6 +3,7 @@ export interface EditorProps {
   setCode: (code: string) => void;
   theme: "dark" | "light";
   onCompile: () => void;
+  onFormat: () => void;
   fontSizeMultiplier?: number;
   indentSize?: number;
   showMinimap?: boolean;
@@ -7,6 +7,7 @@ import ASTViewer from "./components/ASTViewer";
 import TokensViewer from "./components/TokensViewer";
 import SymbolsViewer from "./components/SymbolsViewer";
 import QuadruplesViewer from "./components/QuadruplesViewer";
+import { getPanelBorderColor } from "../../utils/theme";
 
 interface ResultPanelProps {
   result: CompilationResult;
@@ -21,6 +22,8 @@ export default function ResultPanel({
 }: ResultPanelProps) {
   const [searchTerm, setSearchTerm] = useState<string>("");
   const [isSearchOpen, setIsSearchOpen] = useState<boolean>(false);
+  // Index into the optimization report, -1 shows the generated quadruples
+  const [passIndex, setPassIndex] = useState<number>(-1);
 
   if (!result) return null;
 
@@ -106,15 +109,35 @@ export default function ResultPanel({
               "symbols.json",
             ),
         };
-      case "quadruples":
+      case "quadruples": {
+        const report = result.optimization_report[passIndex];
         return {
           title: "Quadruples",
           content: (
-            <QuadruplesViewer
-              program={result.quadruples}
-              theme={theme}
-              searchTerm={searchTerm}
-            />
+            <>
+              {result.optimization_report.length > 0 && (
+                <select
+                  className={`mb-4 px-2 py-1 rounded border text-sm ${getPanelBorderColor(theme)}`}
+                  value={passIndex}
+                  onChange={(e) => setPassIndex(Number(e.target.value))}
+                >
+                  <option value={-1}>Generated</option>
+                  {result.optimization_report.map((pass, index) => (
+                    <option key={pass.pass} value={index}>
+                      After {pass.pass} ({pass.changes} changes)
+                    </option>
+                  ))}
+                </select>
+              )}
+              <QuadruplesViewer
+                program={report ? report.quadruples : result.quadruples}
+                theme={theme}
+                searchTerm={searchTerm}
+                diff={report?.diff ?? undefined}
+                passName={report?.pass}
+                dataflow={report ? undefined : result.dataflow.points}
+              />
+            </>
           ),
           copy: () =>
             copyToClipboard(JSON.stringify(result.quadruples, null, 2)),
@@ -124,6 +147,7 @@ export default function ResultPanel({
               "quadruples.json",
             ),
         };
+      }
       default:
         return {
           title: "",
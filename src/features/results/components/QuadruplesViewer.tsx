@@ -1,4 +1,10 @@
-import { Operand, Quadruple, QuadrupleProgram } from "@/types";
+import {
+  Operand,
+  ProgramPoint,
+  Quadruple,
+  QuadrupleChange,
+  QuadrupleProgram,
+} from "@/types";
 import { useState } from "react";
 import { filterQuadruples } from "../utils";
 import { 
@@ -20,16 +26,32 @@ interface QuadruplesViewerProps {
   program: QuadrupleProgram;
   theme: "dark" | "light";
   searchTerm?: string;
+  // Changes made by the pass that produced the program, highlighted when given
+  diff?: QuadrupleChange[];
+  passName?: string;
+  // Live variables and reaching definitions around each quadruple, when given
+  dataflow?: ProgramPoint[];
 }
 
 export default function QuadruplesViewer({
   program,
   theme,
   searchTerm = "",
+  diff,
+  passName,
+  dataflow,
 }: QuadruplesViewerProps) {
   const [expandedIndex, setExpandedIndex] = useState<number | null>(null);
   const { quadruples, next_temp, next_label } = program;
   const filteredQuadruples = filterQuadruples(quadruples, searchTerm);
+  const added = new Set(
+    (diff ?? [])
+      .filter((change) => change.kind === "Added")
+      .map((change) => quadruples[change.after as number]),
+  );
+  const removedCount = (diff ?? []).filter(
+    (change) => change.kind === "Removed",
+  ).length;
 
   const renderOperand = (operand: Operand) => {
     switch (operand.type) {
@@ -125,6 +147,31 @@ export default function QuadruplesViewer({
       );
     }
 
+    if (quad.operation.type === "Entry") {
+      return (
+        <div className={getLabelColor(theme) + " font-bold"}>
+          {quad.operation.data.name}:
+        </div>
+      );
+    }
+
+    if (quad.operation.type === "Call") {
+      return (
+        <div className="flex items-center gap-2">
+          {quad.result.type !== "Empty" && (
+            <>
+              {renderOperand(quad.result)}
+              <span className="px-1">=</span>
+            </>
+          )}
+          <span className={getJumpColor(theme) + " font-bold"}>
+            CALL
+          </span>
+          <span>{quad.operation.data.name}</span>
+        </div>
+      );
+    }
+
     if (quad.operation.type === "Input") {
       return (
         <div className="flex items-center gap-2">
@@ -153,6 +200,7 @@ export default function QuadruplesViewer({
       Subtract: "-",
       Multiply: "*",
       Divide: "/",
+      Concat: "+",
       Equal: "==",
       NotEqual: "!=",
       LessThan: "<",
@@ -190,6 +238,21 @@ export default function QuadruplesViewer({
       );
     }
 
+    if (quad.operation.type === "Length") {
+      return (
+        <div className="flex items-center gap-2">
+          {renderOperand(quad.result)}
+          <span className="px-1">=</span>
+          <span className={getOperatorColor(theme) + " font-bold"}>
+            len
+          </span>
+          <span>(</span>
+          {renderOperand(quad.operand1)}
+          <span>)</span>
+        </div>
+      );
+    }
+
     if (quad.operation.type === "Assign") {
       return (
         <div className="flex items-center gap-2">
@@ -242,6 +305,11 @@ export default function QuadruplesViewer({
         >
           {filteredQuadruples.length}{" "}
           {filteredQuadruples.length === 1 ? "quadruple" : "quadruples"}
+          {diff && passName && (
+            <span className="ml-4">
+              {passName}: {added.size} added, {removedCount} removed
+            </span>
+          )}
         </div>
       </div>
 
@@ -266,6 +334,14 @@ export default function QuadruplesViewer({
                 >
                   {index}:
                 </div>
+                {added.has(quad) && (
+                  <span
+                    className={`mr-3 px-2 rounded text-xs font-semibold ${getOperatorColor(theme)}`}
+                    title={`Added or rewritten by ${passName ?? "this pass"}`}
+                  >
+                    {passName ?? "changed"}
+                  </span>
+                )}
                 {expandedIndex === index ? (
                   <div className="w-full">
                     <div className="grid grid-cols-[auto_1fr] gap-x-4 gap-y-2">
@@ -298,6 +374,23 @@ export default function QuadruplesViewer({
 
                       <div className="font-bold">Result:</div>
                       <div>{renderOperand(quad.result)}</div>
+
+                      {dataflow?.[quadruples.indexOf(quad)] && (
+                        <>
+                          <div className="font-bold">Live in:</div>
+                          <div className={getVariableColor(theme)}>
+                            {dataflow[quadruples.indexOf(quad)].live_in.join(", ") || "-"}
+                          </div>
+                          <div className="font-bold">Live out:</div>
+                          <div className={getVariableColor(theme)}>
+                            {dataflow[quadruples.indexOf(quad)].live_out.join(", ") || "-"}
+                          </div>
+                          <div className="font-bold">Reaching definitions:</div>
+                          <div>
+                            {dataflow[quadruples.indexOf(quad)].reaching_definitions.join(", ") || "-"}
+                          </div>
+                        </>
+                      )}
                     </div>
                   </div>
                 ) : (
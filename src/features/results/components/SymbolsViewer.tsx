@@ -22,6 +22,11 @@ export default function SymbolsViewer({
       header: "Name",
       render: (name: string) => <span className="font-mono">{name}</span>,
     },
+    {
+      key: "scope",
+      header: "Scope",
+      render: (scope: string) => <span className="font-mono">{scope}</span>,
+    },
     { 
       key: "kind", 
       header: "Kind",
@@ -14,6 +14,7 @@ export const filterSymbols = (symbols: Symbol[], searchTerm: string = ""): Symbo
   return symbols.filter(
     (symbol) =>
       symbol.name.toLowerCase().includes(searchTerm.toLowerCase()) ||
+      symbol.scope.toLowerCase().includes(searchTerm.toLowerCase()) ||
       symbol.kind.toLowerCase().includes(searchTerm.toLowerCase()) ||
       symbol.symbol_type.toLowerCase().includes(searchTerm.toLowerCase()) ||
       symbol.value.toLowerCase().includes(searchTerm.toLowerCase())
@@ -1,21 +1,32 @@
 import { useEffect } from 'react';
 import { invoke } from "@tauri-apps/api/core";
-import type { CompilationErrors, CompilationResult } from "@/types";
+import type {
+  CompilationErrors,
+  CompilationResult,
+  FormatOptions,
+  OptimizationLevel,
+} from "@/types";
 
 interface UseCompilerProps {
   code: string;
+  setCode: (code: string) => void;
   setCompilationResult: (result: CompilationResult | null) => void;
   setIsCompiling: (isCompiling: boolean) => void;
   setError: (error: CompilationErrors | null) => void;
   showNotification: (message: string, isError?: boolean) => void;
+  optimizationLevel?: OptimizationLevel;
+  passDiffs?: boolean;
 }
 
 export function useCompiler({
   code,
+  setCode,
   setCompilationResult,
   setIsCompiling,
   setError,
   showNotification,
+  optimizationLevel = "O2",
+  passDiffs = false,
 }: UseCompilerProps) {
   
   const compileCode = async () => {
@@ -26,6 +37,10 @@ export function useCompiler({
         {
           code,
           verbose: false,
+          options: {
+            optimization_level: optimizationLevel,
+            pass_diffs: passDiffs,
+          },
         },
       );
       setCompilationResult(compilationResult);
@@ -33,6 +48,14 @@ export function useCompiler({
       if (compilationResult.errors) {
         setError(compilationResult.errors);
         showNotification("Compilation process failed with errors", true);
+      } else if (
+        compilationResult.warnings.length + compilationResult.lints.length >
+        0
+      ) {
+        const count =
+          compilationResult.warnings.length + compilationResult.lints.length;
+        setError(null);
+        showNotification(`Compilation completed with ${count} warning(s)`);
       } else {
         setError(null);
         showNotification("Compilation process completed successfully!");
@@ -49,5 +72,20 @@ export function useCompiler({
     }
   };
 
-  return { compileCode };
+  const formatCode = async (options: FormatOptions = {}) => {
+    try {
+      const formatted = await invoke<string>("format_minisoft", {
+        code,
+        options,
+      });
+      if (formatted !== code) {
+        setCode(formatted);
+      }
+    } catch (err: unknown) {
+      // Only code that parses can be formatted
+      showNotification(`Cannot format the code: ${err}`, true);
+    }
+  };
+
+  return { compileCode, formatCode };
 }
@@ -40,6 +40,7 @@ export type StatementKind =
       };
     }
   | { kind: "DoWhile"; data: { body: Statement[]; condition: Expression } }
+  | { kind: "While"; data: { condition: Expression; body: Statement[] } }
   | {
       kind: "For";
       data: {
@@ -52,7 +53,9 @@ export type StatementKind =
     }
   | { kind: "Input"; data: { target: Expression } }
   | { kind: "Output"; data: { values: Expression[] } }
-  | { kind: "Scope"; data: { statements: Statement[] } }
+  | { kind: "Scope"; data: { declarations: Declaration[]; statements: Statement[] } }
+  | { kind: "Call"; data: { name: string; arguments: Expression[] } }
+  | { kind: "Return"; data: { value: Expression | null } }
   | { kind: "Empty" };
 
 export type ExpressionKind =
@@ -63,10 +66,32 @@ export type ExpressionKind =
       kind: "BinaryOp";
       data: { left: Expression; operator: string; right: Expression };
     }
-  | { kind: "UnaryOp"; data: { operator: string; operand: Expression } };
+  | { kind: "UnaryOp"; data: { operator: string; operand: Expression } }
+  | { kind: "Call"; data: { name: string; arguments: Expression[] } };
+
+export interface ParameterKind {
+  name: string;
+  type_name: string;
+  // Size of an array parameter, null for a scalar
+  size: number | null;
+}
+
+export type Parameter = Located<ParameterKind>;
+
+export interface SubprogramKind {
+  name: string;
+  parameters: Parameter[];
+  // null for a procedure
+  return_type: string | null;
+  declarations: Declaration[];
+  statements: Statement[];
+}
+
+export type Subprogram = Located<SubprogramKind>;
 
 export interface Program {
   name: string;
   declarations: Declaration[];
+  subprograms: Subprogram[];
   statements: Statement[];
 }
\ No newline at end of file
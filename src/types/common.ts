@@ -8,6 +8,8 @@ export interface Token {
 
 export interface Symbol {
   name: string;
+  /** Declaring scope: "global", a subprogram name, then block numbers */
+  scope: string;
   kind: string;
   symbol_type: string;
   value: string;
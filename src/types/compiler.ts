@@ -1,12 +1,78 @@
 import { Token, Symbol } from './common';
 import { Program } from './ast';
-import { QuadrupleProgram } from './quadruple';
-import { CompilationErrors } from './errors';
+import { ControlFlowGraph, QuadrupleProgram } from './quadruple';
+import { CompilationErrors, LintWarning, SemanticError } from './errors';
 
 export interface CompilationResult {
   tokens: Token[];
   ast: Program;
   symbol_table: Symbol[];
   quadruples: QuadrupleProgram;
+  optimized_quadruples: QuadrupleProgram;
+  optimization_report: PassReport[];
+  optimization_level: OptimizationLevel;
+  control_flow_graph: ControlFlowGraph;
+  dataflow: Dataflow;
+  dot: DotGraphs;
   errors?: CompilationErrors;
-}
\ No newline at end of file
+  // Diagnostics reported even when compilation succeeds
+  warnings: SemanticError[];
+  lints: LintWarning[];
+}
+
+export interface PassReport {
+  pass: string;
+  changes: number;
+  // Per-rule breakdown of the changes, empty for single-rule passes
+  rule_hits: RuleHits[];
+  // The program as the pass left it
+  quadruples: QuadrupleProgram;
+  // Only present when the compilation asked for pass diffs
+  diff: QuadrupleChange[] | null;
+}
+
+// Indices into the programs before and after a pass
+export interface QuadrupleChange {
+  kind: "Kept" | "Removed" | "Added";
+  before: number | null;
+  after: number | null;
+}
+
+export type OptimizationLevel = "O0" | "O1" | "O2";
+
+export interface CompilerOptions {
+  optimization_level: OptimizationLevel;
+  // Names of the lint rules not to run
+  disabled_lints?: string[];
+  // Whether pass reports carry a diff against the previous program
+  pass_diffs?: boolean;
+}
+
+export interface FormatOptions {
+  // Give every declared name a `let` of its own
+  one_declaration_per_line?: boolean;
+}
+
+export interface RuleHits {
+  rule: string;
+  hits: number;
+}
+
+// Dataflow facts around every generated quadruple, in program order
+export interface Dataflow {
+  points: ProgramPoint[];
+}
+
+export interface ProgramPoint {
+  live_in: string[];
+  live_out: string[];
+  // Indices of the quadruples whose definitions may reach this one
+  reaching_definitions: number[];
+}
+
+// Graphviz sources, empty strings when the stage was not reached
+export interface DotGraphs {
+  ast: string;
+  control_flow_graph: string;
+  symbol_table: string;
+}
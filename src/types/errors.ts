@@ -48,6 +48,17 @@ export type SyntaxError =
     }
   | { type: "Custom"; data: string };
 
+// Only errors stop the compilation
+export type Severity = "Error" | "Warning" | "Note";
+
+export interface LintWarning {
+  rule: string;
+  severity: Severity;
+  message: string;
+  suggestion?: string;
+  position: ErrorPosition;
+}
+
 // Semantic errors
 export type SemanticError =
   | {
@@ -81,6 +92,14 @@ export type SemanticError =
         original_position: ErrorPosition;
       };
     }
+  | {
+      type: "ShadowedDeclaration";
+      data: {
+        name: string;
+        position: ErrorPosition;
+        original_position: ErrorPosition;
+      };
+    }
   | {
       type: "TypeMismatch";
       data: {
@@ -126,6 +145,12 @@ export type SemanticError =
         position: ErrorPosition;
       };
     }
+  | {
+      type: "ZeroLoopStep";
+      data: {
+        position: ErrorPosition;
+      };
+    }
   | {
       type: "InvalidArraySize";
       data: {
@@ -134,7 +159,86 @@ export type SemanticError =
         position: ErrorPosition;
       };
     }
-  | { type: "EmptyProgram" };
+  | { type: "EmptyProgram" }
+  | {
+      type: "InfiniteLoop";
+      data: {
+        // Whether the condition is always true, rather than never changed by the body
+        constant: boolean;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "UnreachableCode";
+      data: {
+        context: string;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "UseBeforeInitialization";
+      data: {
+        name: string;
+        index: string | null;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "IndexRangeOutOfBounds";
+      data: {
+        name: string;
+        // Values the index may take, such as "[0, 10]"
+        range: string;
+        size: number;
+        // Whether every value of the range is out of bounds, rather than some of them
+        definite: boolean;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "UndefinedSubprogram";
+      data: {
+        name: string;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "ArgumentCountMismatch";
+      data: {
+        name: string;
+        expected: number;
+        found: number;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "ProcedureValueUsed";
+      data: {
+        name: string;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "MissingReturn";
+      data: {
+        name: string;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "InvalidReturn";
+      data: {
+        message: string;
+        position: ErrorPosition;
+      };
+    }
+  | {
+      type: "BuiltinRedefined";
+      data: {
+        name: string;
+        position: ErrorPosition;
+      };
+    };
 
 // Compilation errors container
 export interface CompilationErrors {
@@ -4,6 +4,9 @@ export type Operation =
   | { type: "Subtract" }
   | { type: "Multiply" }
   | { type: "Divide" }
+  // String operations
+  | { type: "Concat" }
+  | { type: "Length" }
   // Assignment and memory operations
   | { type: "Assign" }
   | { type: "ArrayStore" }
@@ -13,6 +16,7 @@ export type Operation =
   | { type: "Jump"; data: { target: number } }
   | { type: "JumpIfTrue"; data: { target: number } }
   | { type: "JumpIfFalse"; data: { target: number } }
+  | { type: "CheckStep" }
   // Comparison operations
   | { type: "Equal" }
   | { type: "NotEqual" }
@@ -28,8 +32,10 @@ export type Operation =
   | { type: "Input" }
   | { type: "Output" }
   // Function operations
+  | { type: "Param" }
   | { type: "Call"; data: { name: string } }
-  | { type: "Return" };
+  | { type: "Return" }
+  | { type: "Entry"; data: { name: string } };
 
 export type Operand =
   | { type: "IntLiteral"; data: { value: number } }
@@ -51,4 +57,16 @@ export interface QuadrupleProgram {
   quadruples: Quadruple[];
   next_temp: number;
   next_label: number;
-}
\ No newline at end of file
+}
+export interface BasicBlock {
+  id: number;
+  start: number;
+  quadruples: Quadruple[];
+  successors: number[];
+  predecessors: number[];
+}
+
+export interface ControlFlowGraph {
+  blocks: BasicBlock[];
+  reverse_post_order: number[];
+}
@@ -71,6 +71,7 @@ export const getNodeColor = (kind: string, theme: "dark" | "light"): string => {
     case "IfThen":
     case "IfThenElse":
     case "DoWhile":
+    case "While":
     case "For":
       return `text-[var(--accent-color)]`;
     case "Input":
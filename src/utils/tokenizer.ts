@@ -18,10 +18,10 @@ export function tokenize(code: string): Token[] {
     ["comment", /^\{--[\s\S]*?--\}/], // Simplified pattern for {-- --}
 
     // Keywords
-    ["keyword", /^(MainPrgm|Var|BeginPg|EndPg|let|Int|Float)\b/],
+    ["keyword", /^(MainPrgm|Var|BeginPg|EndPg|let|Int|Float|String|Function|Procedure)\b/],
 
     // Control flow
-    ["control", /^(if|then|else|while|for|do|from|to|step)\b/],
+    ["control", /^(if|then|else|while|for|do|from|to|step|return)\b/],
 
     // I/O operations
     ["io", /^(input|output)\b/],